@@ -6,6 +6,9 @@ pub enum Action {
     Right,
     Confirm,
     Back,
+    PageUp,
+    PageDown,
+    Tick, // from the main loop, for spinners
 
     // screen jumps
     GoConnections,
@@ -16,12 +19,19 @@ pub enum Action {
 
     // actions
     ConnectSelected,
+    OpenTableData,
+    OpenDatabasePicker,
     Disconnect,
+    ConnectInNewTab,
+    NextSession,
+    PrevSession,
+    CloseSession,
 
     // connection
     OpenAddConnection,
     EditSelectedConnection,
     DeleteSelectedConnection,
+    OpenConnectionImport,
 
     // delete-confirm modal
     ConfirmDeleteConnection,
@@ -29,11 +39,62 @@ pub enum Action {
 
     // add-connection modal
     CancelModal,
+    TestConnection,
     NextField,
     PrevField,
     Backspace,
     InputChar(char),
 
+    // runner editor
+    MoveCursor {
+        mv: crate::app::editor::CursorMove,
+        select: bool,
+    },
+    Newline,
+    Delete,
+    SelectAll,
+    Undo,
+    Redo,
+    RunQuery,
+    CancelQuery,
+    FocusEditor,
+    FocusResults,
+
+    // data filter builder
+    OpenFilterBuilder,
+    ClearRowFilter,
+    AddFilterCondition,
+    AddSortKey,
+    DeleteFilterRow,
+
+    // export dialog (Data / Runner grids); Space marks Data rows to export
+    OpenExport,
+    ToggleRowMark,
+
+    // import dialog (Data grid)
+    OpenImport,
+
+    // grid edits (Data); Enter opens the cell editor
+    InsertRow,
+    DeleteRow,
+    ReviewChanges,
+    DiscardChanges,
+
+    // record view (Data / Runner grids); ←/→ step rows while it is open
+    OpenRecordDetail,
+
+    // foreign keys (Data); Esc goes back along the trail
+    FollowReference,
+    OpenReferencingTables,
+
+    // query library
+    SaveQuery,
+    StartFilter,
+    RenameSelectedQuery,
+    DeleteSelectedQuery,
+    ConfirmDeleteQuery,
+    CancelDeleteQuery,
+
     // app control
     Quit,
 }
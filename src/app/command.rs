@@ -1,6 +1,6 @@
 #[derive(Debug, Clone)]
 pub enum Command {
-    Db(DbCommand),
+    Db(crate::app::state::SessionId, DbCommand), // routed to that session's worker
     Storage(StorageCommand),
 }
 
@@ -9,21 +9,92 @@ pub enum StorageCommand {
     SaveConnections {
         connections: Vec<crate::storage::model::ConnectionProfile>,
     },
+    SaveQueries {
+        queries: Vec<crate::domain::querylib::SavedQuery>,
+    },
+    ImportConnections {
+        source: String, // a DSN or a file; see `storage::external::read_connections`
+    },
+}
+
+/// Where a connection goes and how it gets there.
+#[derive(Debug, Clone)]
+pub struct ConnectTarget {
+    pub kind: crate::domain::connection::DbKind,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub db: String,
+    pub ssh: Option<crate::domain::connection::SshTunnel>, // forward through this first
+    pub ssh_passphrase: String,
+    pub tls: crate::domain::connection::TlsSettings,
+    pub options: crate::domain::connection::ConnectionOptions,
 }
 
 #[derive(Debug, Clone)]
 pub enum DbCommand {
     Connect {
+        id: ulid::Ulid,
         name: String,
-        host: String,
-        port: u16,
-        user: String,
-        password: String,
-        db: String,
+        target: Box<ConnectTarget>,
+        statement_timeout: Option<std::time::Duration>, // for `Execute`
+    },
+    TestConnection {
+        target: Box<ConnectTarget>, // opened and closed again, leaving the session as is
     },
     Disconnect,
-    LoadTables,
-    LoadColumns {
+    LoadSchemas,
+    UseSchema {
+        name: String,
+    },
+    LoadObjects,
+    DescribeTable {
+        table: String,
+    },
+    DescribeDataTable {
+        table: String,
+    },
+    LoadDefinition {
+        object: crate::db::introstect::SchemaObject,
+    },
+    LoadRows {
+        table: String,
+        order_by: Option<String>,
+        filter: Option<crate::domain::filter::CompiledFilter>,
+        offset: u64,
+        limit: u64,
+    },
+    Execute {
+        id: u64, // answered with `QueryRows`, then `QueryFinished`, `QueryFailed` or `QueryExpired`
+        sql: String,
+    },
+    FetchRows {
+        id: u64,
+        upto: u64, // rows the result may stream before pausing again
+    },
+    Cancel {
+        id: u64, // stops the fetch if rows are already streaming
+    },
+    Export {
+        id: u64, // task id, shared with Runner statements for `Cancel`
+        source: crate::db::export::ExportSource,
+        path: String, // a leading `~/` is expanded by the worker
+        options: crate::db::export::ExportOptions,
+    },
+    PreviewImport {
+        table: String,
+        path: String, // a leading `~/` is expanded by the worker
+        options: crate::db::import::ImportOptions,
+    },
+    Import {
+        id: u64,
+        plan: crate::db::import::ImportPlan,
+        path: String,
+        options: crate::db::import::ImportOptions,
+    },
+    ApplyChanges {
         table: String,
+        statements: Vec<crate::db::edit::EditStatement>,
     },
 }
@@ -0,0 +1,324 @@
+/// Multi-line text buffer behind the Runner editor.
+///
+/// Positions are `(line, column)` with the column counted in chars, not bytes.
+#[derive(Debug, Clone)]
+pub struct TextBuffer {
+    lines: Vec<String>,
+    cursor: Pos,
+    anchor: Option<Pos>, // selection start; selection runs anchor..cursor
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: EditKind,
+}
+
+pub type Pos = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorMove {
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Top,
+    Bottom,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    cursor: Pos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    None,
+    Typing,
+    Other,
+}
+
+const UNDO_LIMIT: usize = 200;
+
+impl Default for TextBuffer {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            cursor: (0, 0),
+            anchor: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: EditKind::None,
+        }
+    }
+}
+
+impl TextBuffer {
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            lines: split_lines(text),
+            ..Default::default()
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn cursor(&self) -> Pos {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    /// Replace the whole buffer as one undoable edit.
+    pub fn set_text(&mut self, text: &str) {
+        self.checkpoint(EditKind::Other);
+        self.lines = split_lines(text);
+        self.cursor = (0, 0);
+        self.anchor = None;
+    }
+
+    /// Char offset of the cursor within `text()`.
+    pub fn cursor_offset(&self) -> usize {
+        self.offset_of(self.cursor)
+    }
+
+    /// Ordered `(start, end)` of the active selection, if any non-empty one exists.
+    pub fn selection(&self) -> Option<(Pos, Pos)> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+        Some(if anchor < self.cursor {
+            (anchor, self.cursor)
+        } else {
+            (self.cursor, anchor)
+        })
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        let text = self.text();
+        let (a, b) = (self.offset_of(start), self.offset_of(end));
+        Some(text.chars().skip(a).take(b - a).collect())
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        if c == '\n' {
+            self.newline();
+            return;
+        }
+        let kind = if c.is_whitespace() {
+            EditKind::Other
+        } else {
+            EditKind::Typing
+        };
+        self.checkpoint(kind);
+        self.delete_selection();
+
+        let (row, col) = self.cursor;
+        let at = byte_idx(&self.lines[row], col);
+        self.lines[row].insert(at, c);
+        self.cursor = (row, col + 1);
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.checkpoint(EditKind::Other);
+        self.delete_selection();
+
+        let (row, col) = self.cursor;
+        let at = byte_idx(&self.lines[row], col);
+        let tail = self.lines[row].split_off(at);
+
+        let mut inserted = split_lines(s);
+        let last_len = inserted.last().map(|l| l.chars().count()).unwrap_or(0);
+        let n = inserted.len();
+        self.lines[row].push_str(&inserted.remove(0));
+        for (i, l) in inserted.into_iter().enumerate() {
+            self.lines.insert(row + 1 + i, l);
+        }
+
+        let end_row = row + n - 1;
+        let end_col = if n == 1 { col + last_len } else { last_len };
+        self.lines[end_row].push_str(&tail);
+        self.cursor = (end_row, end_col);
+    }
+
+    pub fn newline(&mut self) {
+        self.checkpoint(EditKind::Other);
+        self.delete_selection();
+
+        let (row, col) = self.cursor;
+        let at = byte_idx(&self.lines[row], col);
+        let tail = self.lines[row].split_off(at);
+        self.lines.insert(row + 1, tail);
+        self.cursor = (row + 1, 0);
+    }
+
+    pub fn backspace(&mut self) {
+        if self.selection().is_some() {
+            self.checkpoint(EditKind::Other);
+            self.delete_selection();
+            return;
+        }
+        let (row, col) = self.cursor;
+        if row == 0 && col == 0 {
+            return;
+        }
+        self.checkpoint(EditKind::Other);
+        if col > 0 {
+            let at = byte_idx(&self.lines[row], col - 1);
+            self.lines[row].remove(at);
+            self.cursor = (row, col - 1);
+        } else {
+            let line = self.lines.remove(row);
+            let prev_len = self.line_len(row - 1);
+            self.lines[row - 1].push_str(&line);
+            self.cursor = (row - 1, prev_len);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.selection().is_some() {
+            self.checkpoint(EditKind::Other);
+            self.delete_selection();
+            return;
+        }
+        let (row, col) = self.cursor;
+        if col < self.line_len(row) {
+            self.checkpoint(EditKind::Other);
+            let at = byte_idx(&self.lines[row], col);
+            self.lines[row].remove(at);
+        } else if row + 1 < self.lines.len() {
+            self.checkpoint(EditKind::Other);
+            let next = self.lines.remove(row + 1);
+            self.lines[row].push_str(&next);
+        }
+    }
+
+    /// Move the cursor; with `select` the selection is extended instead of cleared.
+    pub fn move_cursor(&mut self, mv: CursorMove, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.last_edit = EditKind::None;
+
+        let (row, col) = self.cursor;
+        self.cursor = match mv {
+            CursorMove::Left if col > 0 => (row, col - 1),
+            CursorMove::Left if row > 0 => (row - 1, self.line_len(row - 1)),
+            CursorMove::Right if col < self.line_len(row) => (row, col + 1),
+            CursorMove::Right if row + 1 < self.lines.len() => (row + 1, 0),
+            CursorMove::Up if row > 0 => (row - 1, col.min(self.line_len(row - 1))),
+            CursorMove::Down if row + 1 < self.lines.len() => {
+                (row + 1, col.min(self.line_len(row + 1)))
+            }
+            CursorMove::Home => (row, 0),
+            CursorMove::End => (row, self.line_len(row)),
+            CursorMove::Top => (0, 0),
+            CursorMove::Bottom => {
+                let last = self.lines.len() - 1;
+                (last, self.line_len(last))
+            }
+            _ => (row, col),
+        };
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some((0, 0));
+        let last = self.lines.len() - 1;
+        self.cursor = (last, self.line_len(last));
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(snap) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(self.snapshot());
+        self.restore(snap);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(snap) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(self.snapshot());
+        self.restore(snap);
+        true
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    fn offset_of(&self, (row, col): Pos) -> usize {
+        // +1 per preceding line for the '\n' joiner
+        self.lines[..row]
+            .iter()
+            .map(|l| l.chars().count() + 1)
+            .sum::<usize>()
+            + col
+    }
+
+    fn delete_selection(&mut self) {
+        let Some(((r1, c1), (r2, c2))) = self.selection() else {
+            self.anchor = None;
+            return;
+        };
+        let tail: String = self.lines[r2].chars().skip(c2).collect();
+        let head: String = self.lines[r1].chars().take(c1).collect();
+        self.lines.drain(r1 + 1..=r2);
+        self.lines[r1] = head + &tail;
+        self.cursor = (r1, c1);
+        self.anchor = None;
+    }
+
+    /// Record an undo point unless this continues a run of plain typing.
+    fn checkpoint(&mut self, kind: EditKind) {
+        let coalesce = kind == EditKind::Typing && self.last_edit == EditKind::Typing;
+        self.last_edit = kind;
+        if coalesce {
+            return;
+        }
+        self.undo.push(self.snapshot());
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        }
+    }
+
+    fn restore(&mut self, snap: Snapshot) {
+        self.lines = snap.lines;
+        self.cursor = snap.cursor;
+        self.anchor = None;
+        self.last_edit = EditKind::None;
+    }
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.replace("\r\n", "\n")
+        .split('\n')
+        .map(str::to_string)
+        .collect()
+}
+
+fn byte_idx(s: &str, col: usize) -> usize {
+    s.char_indices().nth(col).map(|(i, _)| i).unwrap_or(s.len())
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::app::editor::{CursorMove, TextBuffer};
+
+    fn typed(s: &str) -> TextBuffer {
+        let mut b = TextBuffer::default();
+        for c in s.chars() {
+            b.insert_char(c);
+        }
+        b
+    }
+
+    #[test]
+    fn typing_and_newlines_build_multiline_text() {
+        let b = typed("SELECT 1;\nSELECT 2;");
+        assert_eq!(b.lines().len(), 2);
+        assert_eq!(b.text(), "SELECT 1;\nSELECT 2;");
+        assert_eq!(b.cursor(), (1, 9));
+    }
+
+    #[test]
+    fn backspace_at_line_start_joins_lines() {
+        let mut b = typed("ab\ncd");
+        b.move_cursor(CursorMove::Home, false);
+        b.backspace();
+        assert_eq!(b.text(), "abcd");
+        assert_eq!(b.cursor(), (0, 2));
+    }
+
+    #[test]
+    fn vertical_moves_clamp_to_line_length() {
+        let mut b = typed("long line\nab");
+        b.move_cursor(CursorMove::Up, false);
+        assert_eq!(b.cursor(), (0, 2));
+        b.move_cursor(CursorMove::End, false);
+        b.move_cursor(CursorMove::Down, false);
+        assert_eq!(b.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn shift_selection_is_replaced_by_typing() {
+        let mut b = typed("hello world");
+        b.move_cursor(CursorMove::Home, false);
+        for _ in 0..5 {
+            b.move_cursor(CursorMove::Right, true);
+        }
+        assert_eq!(b.selected_text().as_deref(), Some("hello"));
+
+        b.insert_char('X');
+        assert_eq!(b.text(), "X world");
+        assert!(b.selection().is_none());
+    }
+
+    #[test]
+    fn undo_reverts_a_word_at_a_time_and_redo_restores() {
+        let mut b = typed("select id");
+        assert!(b.undo());
+        assert_eq!(b.text(), "select ");
+        assert!(b.undo());
+        assert_eq!(b.text(), "select");
+        assert!(b.redo());
+        assert_eq!(b.text(), "select ");
+    }
+
+    #[test]
+    fn insert_str_spanning_lines_places_cursor_at_end() {
+        let mut b = typed("AB");
+        b.move_cursor(CursorMove::Left, false);
+        b.insert_str("1\n23");
+        assert_eq!(b.text(), "A1\n23B");
+        assert_eq!(b.cursor(), (1, 2));
+        assert_eq!(b.cursor_offset(), 5);
+    }
+}
@@ -1,29 +1,115 @@
 #[derive(Debug, Clone)]
 pub enum Event {
-    Db(DbEvent),
+    Db(crate::app::state::SessionId, DbEvent), // from that session's worker
     Storage(StorageEvent),
 }
 
 #[derive(Debug, Clone)]
 pub enum StorageEvent {
     ConnectionsSaved,
-    Error { message: String },
+    QueriesSaved,
+    ConnectionsImported {
+        connections: Vec<crate::storage::external::ImportedConnection>,
+    },
+    Error {
+        message: String,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum DbEvent {
     Connected {
+        id: ulid::Ulid,
         display: String,
     },
     Disconnected,
-    TablesLoaded {
-        tables: Vec<String>,
+    ConnectionTested {
+        result: Result<std::time::Duration, String>, // time to connect, or why it failed
+    },
+    SchemasLoaded {
+        schemas: Vec<String>,
+        current: Option<String>,
+    },
+    SchemaChanged {
+        schema: String,
+        display: String,
+    },
+    ObjectsLoaded {
+        objects: Vec<crate::db::introstect::SchemaObject>,
     },
-    ColumnsLoaded {
+    TableDescribed {
+        details: crate::db::introstect::TableDetails,
+    },
+    DataTableDescribed {
+        details: crate::db::introstect::TableDetails,
+    },
+    DefinitionLoaded {
+        object: crate::db::introstect::SchemaObject,
+        definition: String,
+    },
+    RowsLoaded {
         table: String,
-        columns: Vec<crate::app::state::ColumnInfo>,
+        columns: Vec<crate::db::types::ResultColumn>,
+        rows: Vec<Vec<crate::db::types::CellValue>>,
+        offset: u64,
+        total_estimate: Option<u64>,
     },
-    Error {
+    RowsFailed {
+        table: String,
         message: String,
     },
+    QueryRows {
+        id: u64,
+        columns: Vec<crate::db::types::ResultColumn>,
+        rows: Vec<Vec<crate::db::types::CellValue>>,
+    },
+    QueryFinished {
+        id: u64,
+        result: crate::db::types::QueryResult,
+    },
+    QueryFailed {
+        id: u64,
+        message: String,
+    },
+    QueryExpired {
+        id: u64, // the server closed a result left paused too long
+    },
+    ExportProgress {
+        rows: u64,
+    },
+    ExportFinished {
+        path: std::path::PathBuf,
+        rows: u64,
+    },
+    ExportFailed {
+        id: u64,
+        message: String, // why it failed, or that it was cancelled
+    },
+    ImportPreviewed {
+        preview: crate::db::import::ImportPreview,
+    },
+    ImportProgress {
+        rows: u64,
+        bytes: u64, // of the file read so far
+        total_bytes: u64,
+    },
+    ImportFinished {
+        table: String,
+        rows: u64,
+        dry_run: bool, // every batch was rolled back
+    },
+    ImportFailed {
+        id: u64,
+        message: String, // why it failed, or that it was cancelled
+    },
+    ChangesApplied {
+        table: String,
+        count: usize, // statements run
+    },
+    ChangesFailed {
+        message: String, // nothing was saved
+    },
+    Error {
+        message: String, // for the status bar only
+    },
 }
@@ -1,4 +1,9 @@
-use super::{action::Action, screen::Screen, state::AppState};
+use super::{
+    action::Action,
+    editor::CursorMove,
+    screen::Screen,
+    state::{AppState, RunnerFocus},
+};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 pub fn map_key(state: &AppState, key: KeyEvent) -> Option<Action> {
@@ -6,8 +11,64 @@ pub fn map_key(state: &AppState, key: KeyEvent) -> Option<Action> {
     if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
         return Some(Action::Quit);
     }
-    if key.code == KeyCode::Char('q') {
-        return Some(Action::Quit);
+
+    // --- Modal: Save query prompt (over the Runner) ---
+    if state.screen == Screen::Runner && state.runner.save_prompt.is_some() {
+        return map_input_key(key);
+    }
+
+    // --- Modal: export dialog (over the Runner results) ---
+    if state.screen == Screen::Runner && state.runner.export.is_some() {
+        return map_input_key(key);
+    }
+
+    // --- Modal: record view (over the Data / Runner grids) ---
+    if (state.screen == Screen::Data && state.data.detail.is_some())
+        || (state.screen == Screen::Runner && state.runner.detail.is_some())
+    {
+        return match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('v') => Some(Action::CancelModal),
+            KeyCode::Up => Some(Action::Up),
+            KeyCode::Down => Some(Action::Down),
+            KeyCode::Left => Some(Action::Left),
+            KeyCode::Right => Some(Action::Right),
+            KeyCode::PageUp => Some(Action::PageUp),
+            KeyCode::PageDown => Some(Action::PageDown),
+            _ => None,
+        };
+    }
+
+    // --- Modal: typed-name confirmation of a destructive statement ---
+    if state.screen == Screen::Runner && state.runner.destructive_confirm.is_some() {
+        return map_input_key(key);
+    }
+
+    // --- Runner editor: captures plain keys, so it goes before single-letter shortcuts ---
+    if state.screen == Screen::Runner && state.runner.focus == RunnerFocus::Editor {
+        return map_editor_key(key);
+    }
+
+    // --- Modal: Delete saved query confirmation ---
+    if state.screen == Screen::Queries && state.queries.delete_confirm.is_some() {
+        return match key.code {
+            KeyCode::Esc | KeyCode::Char('n') => Some(Action::CancelDeleteQuery),
+            KeyCode::Enter | KeyCode::Char('y') => Some(Action::ConfirmDeleteQuery),
+            _ => None,
+        };
+    }
+
+    // --- Inline inputs on Queries: rename / filter ---
+    if state.screen == Screen::Queries
+        && (state.queries.renaming.is_some() || state.queries.filtering)
+    {
+        return map_input_key(key);
+    }
+
+    // --- Inline filter inputs on Connections / Schema ---
+    if (state.screen == Screen::Connections && state.connections.filtering)
+        || (state.screen == Screen::Schema && state.schema.filtering)
+    {
+        return map_input_key(key);
     }
 
     // --- Modal: Delete Confirmation (takes priority over everything else) ---
@@ -21,29 +82,149 @@ pub fn map_key(state: &AppState, key: KeyEvent) -> Option<Action> {
         };
     }
 
+    // --- Modal: import prompt ---
+    if state.screen == Screen::Connections && state.connections.importing.is_some() {
+        return map_input_key(key);
+    }
+
     // --- Modal: Add Connection (takes priority over everything else) ---
     if state.screen == Screen::Connections && state.connections.adding.is_some() {
+        return match key.code {
+            KeyCode::PageDown => Some(Action::PageDown),
+            KeyCode::PageUp => Some(Action::PageUp),
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Action::TestConnection)
+            }
+            _ => map_input_key(key),
+        };
+    }
+
+    // --- Modal: database picker (over Schema) ---
+    if state.screen == Screen::Schema && state.schema.picker.is_some() {
         return match key.code {
             KeyCode::Esc => Some(Action::CancelModal),
             KeyCode::Enter => Some(Action::Confirm),
-            KeyCode::Tab => Some(Action::NextField),
-            KeyCode::BackTab => Some(Action::PrevField),
-            KeyCode::Backspace => Some(Action::Backspace),
-            KeyCode::Char(c) => Some(Action::InputChar(c)),
+            KeyCode::Up => Some(Action::Up),
+            KeyCode::Down => Some(Action::Down),
             _ => None,
         };
     }
 
+    // --- Modal: export dialog (over Data) ---
+    if state.screen == Screen::Data && state.data.export.is_some() {
+        return map_input_key(key);
+    }
+
+    // --- Modal: import dialog (over Data); ↑/↓ pick a column on the mapping page ---
+    if state.screen == Screen::Data && state.data.import.is_some() {
+        return match key.code {
+            KeyCode::Up => Some(Action::Up),
+            KeyCode::Down => Some(Action::Down),
+            _ => map_input_key(key),
+        };
+    }
+
+    // --- Modal: cell editor (over Data); Delete sets NULL ---
+    if state.screen == Screen::Data && state.data.cell_editor.is_some() {
+        return match key.code {
+            KeyCode::Delete => Some(Action::Delete),
+            _ => map_input_key(key),
+        };
+    }
+
+    // --- Modal: review of staged changes (over Data) ---
+    if state.screen == Screen::Data && state.data.review.is_some() {
+        return match key.code {
+            KeyCode::Esc => Some(Action::CancelModal),
+            KeyCode::Enter => Some(Action::Confirm),
+            KeyCode::Up => Some(Action::Up),
+            KeyCode::Down => Some(Action::Down),
+            _ => None,
+        };
+    }
+
+    // --- Modal: tables referencing the open one (over Data) ---
+    if state.screen == Screen::Data && state.data.reference_picker.is_some() {
+        return match key.code {
+            KeyCode::Esc => Some(Action::CancelModal),
+            KeyCode::Enter => Some(Action::Confirm),
+            KeyCode::Up => Some(Action::Up),
+            KeyCode::Down => Some(Action::Down),
+            _ => None,
+        };
+    }
+
+    // --- Modal: filter builder (over Data) ---
+    if state.screen == Screen::Data && state.data.filter_draft.is_some() {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        return match key.code {
+            KeyCode::Char('n') if ctrl => Some(Action::AddFilterCondition),
+            KeyCode::Char('o') if ctrl => Some(Action::AddSortKey),
+            KeyCode::Char('d') if ctrl => Some(Action::DeleteFilterRow),
+            KeyCode::Up => Some(Action::Up),
+            KeyCode::Down => Some(Action::Down),
+            KeyCode::Left => Some(Action::Left),
+            KeyCode::Right => Some(Action::Right),
+            _ => map_input_key(key),
+        };
+    }
+
+    // quit (after the modals, so 'q' can be typed into inputs)
+    if key.code == KeyCode::Char('q') {
+        return Some(Action::Quit);
+    }
+
     // --- Connections screen shortcuts ---
     if state.screen == Screen::Connections {
         match key.code {
+            KeyCode::Char('/') => return Some(Action::StartFilter),
             KeyCode::Char('a') => return Some(Action::OpenAddConnection),
             KeyCode::Char('e') => return Some(Action::EditSelectedConnection),
             KeyCode::Char('d') => return Some(Action::DeleteSelectedConnection),
+            KeyCode::Char('t') => return Some(Action::ConnectInNewTab),
+            KeyCode::Char('i') => return Some(Action::OpenConnectionImport),
             _ => {}
         }
     }
 
+    // --- Queries screen shortcuts ---
+    if state.screen == Screen::Queries {
+        match key.code {
+            KeyCode::Char('/') => return Some(Action::StartFilter),
+            KeyCode::Char('r') => return Some(Action::RenameSelectedQuery),
+            KeyCode::Char('d') => return Some(Action::DeleteSelectedQuery),
+            _ => {}
+        }
+    }
+
+    // --- Runner results pane ---
+    if state.screen == Screen::Runner {
+        match key.code {
+            KeyCode::Char('i') => return Some(Action::FocusEditor),
+            KeyCode::Char('s') => return Some(Action::SaveQuery),
+            KeyCode::Char('e') => return Some(Action::OpenExport),
+            KeyCode::Char('v') => return Some(Action::OpenRecordDetail),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Action::RunQuery);
+            }
+            KeyCode::F(5) => return Some(Action::RunQuery),
+            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Action::CancelQuery);
+            }
+            _ => {}
+        }
+    }
+
+    // session tabs
+    match key.code {
+        KeyCode::Char(']') => return Some(Action::NextSession),
+        KeyCode::Char('[') => return Some(Action::PrevSession),
+        KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            return Some(Action::CloseSession);
+        }
+        _ => {}
+    }
+
     // screen shortcuts
     match key.code {
         KeyCode::Char('1') => return Some(Action::GoConnections),
@@ -51,10 +232,55 @@ pub fn map_key(state: &AppState, key: KeyEvent) -> Option<Action> {
         KeyCode::Char('3') => return Some(Action::GoData),
         KeyCode::Char('4') => return Some(Action::GoQueries),
         KeyCode::Char('5') => return Some(Action::GoRunner),
-        KeyCode::Char('t') => {
-            if state.screen == Screen::Schema {
-                return Some(Action::GoData);
-            }
+        KeyCode::Char('t') if state.screen == Screen::Schema => {
+            return Some(Action::OpenTableData);
+        }
+        KeyCode::Char('d') if state.screen == Screen::Schema => {
+            return Some(Action::OpenDatabasePicker);
+        }
+        KeyCode::Char('/') if state.screen == Screen::Schema => {
+            return Some(Action::StartFilter);
+        }
+        KeyCode::Char('f') if state.screen == Screen::Data => {
+            return Some(Action::OpenFilterBuilder);
+        }
+        KeyCode::Char('x')
+            if state.screen == Screen::Data && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            return Some(Action::CancelQuery);
+        }
+        KeyCode::Char('x') if state.screen == Screen::Data => {
+            return Some(Action::ClearRowFilter);
+        }
+        KeyCode::Char('e') if state.screen == Screen::Data => {
+            return Some(Action::OpenExport);
+        }
+        KeyCode::Char(' ') if state.screen == Screen::Data => {
+            return Some(Action::ToggleRowMark);
+        }
+        KeyCode::Char('i') if state.screen == Screen::Data => {
+            return Some(Action::OpenImport);
+        }
+        KeyCode::Char('n') if state.screen == Screen::Data => {
+            return Some(Action::InsertRow);
+        }
+        KeyCode::Char('d') if state.screen == Screen::Data => {
+            return Some(Action::DeleteRow);
+        }
+        KeyCode::Char('s') if state.screen == Screen::Data => {
+            return Some(Action::ReviewChanges);
+        }
+        KeyCode::Char('u') if state.screen == Screen::Data => {
+            return Some(Action::DiscardChanges);
+        }
+        KeyCode::Char('v') if state.screen == Screen::Data => {
+            return Some(Action::OpenRecordDetail);
+        }
+        KeyCode::Char('g') if state.screen == Screen::Data => {
+            return Some(Action::FollowReference);
+        }
+        KeyCode::Char('r') if state.screen == Screen::Data => {
+            return Some(Action::OpenReferencingTables);
         }
         _ => {}
     }
@@ -65,8 +291,56 @@ pub fn map_key(state: &AppState, key: KeyEvent) -> Option<Action> {
         KeyCode::Down => Some(Action::Down),
         KeyCode::Left => Some(Action::Left),
         KeyCode::Right => Some(Action::Right),
+        KeyCode::PageUp => Some(Action::PageUp),
+        KeyCode::PageDown => Some(Action::PageDown),
         KeyCode::Enter => Some(Action::Confirm),
         KeyCode::Esc => Some(Action::Back),
         _ => None,
     }
 }
+
+fn map_editor_key(key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let mv = |mv| Some(Action::MoveCursor { mv, select: shift });
+
+    match key.code {
+        KeyCode::Esc => Some(Action::FocusResults),
+        KeyCode::F(5) => Some(Action::RunQuery),
+        KeyCode::Char('r') if ctrl => Some(Action::RunQuery),
+        KeyCode::Char('x') if ctrl => Some(Action::CancelQuery),
+        KeyCode::Char('z') if ctrl => Some(Action::Undo),
+        KeyCode::Char('y') if ctrl => Some(Action::Redo),
+        KeyCode::Char('a') if ctrl => Some(Action::SelectAll),
+        KeyCode::Char('s') if ctrl => Some(Action::SaveQuery),
+        KeyCode::Char(_) if ctrl => None,
+        KeyCode::Char(c) => Some(Action::InputChar(c)),
+        KeyCode::Enter => Some(Action::Newline),
+        KeyCode::Backspace => Some(Action::Backspace),
+        KeyCode::Delete => Some(Action::Delete),
+        KeyCode::Left => mv(CursorMove::Left),
+        KeyCode::Right => mv(CursorMove::Right),
+        KeyCode::Up => mv(CursorMove::Up),
+        KeyCode::Down => mv(CursorMove::Down),
+        KeyCode::Home if ctrl => mv(CursorMove::Top),
+        KeyCode::End if ctrl => mv(CursorMove::Bottom),
+        KeyCode::Home => mv(CursorMove::Home),
+        KeyCode::End => mv(CursorMove::End),
+        _ => None,
+    }
+}
+
+/// Single-line text inputs (prompts, inline filters): Enter accepts, Esc cancels.
+fn map_input_key(key: KeyEvent) -> Option<Action> {
+    match key.code {
+        KeyCode::Esc => Some(Action::CancelModal),
+        KeyCode::Enter => Some(Action::Confirm),
+        KeyCode::Tab => Some(Action::NextField),
+        KeyCode::BackTab => Some(Action::PrevField),
+        KeyCode::Backspace => Some(Action::Backspace),
+        KeyCode::Left => Some(Action::Left),
+        KeyCode::Right => Some(Action::Right),
+        KeyCode::Char(c) => Some(Action::InputChar(c)),
+        _ => None,
+    }
+}
@@ -1,10 +1,13 @@
 pub mod action;
 pub mod command;
+pub mod editor;
 pub mod event;
 pub mod keymap;
 pub mod reducer;
 pub mod screen;
 pub mod state;
 
+#[cfg(test)]
+mod editor_tests;
 #[cfg(test)]
 mod reducer_tests;
@@ -1,19 +1,209 @@
 use super::{
     action::Action,
-    command::{Command, DbCommand, StorageCommand},
+    command::{Command, ConnectTarget, DbCommand, StorageCommand},
     event::{DbEvent, Event, StorageEvent},
     screen::Screen,
-    state::{AppState, DeleteConnectionConfirm, NewConnectionDraft},
+    state::{
+        AppState, CellEditor, ChangeReview, ConnectionItem, ConnectionRow, DATA_PAGE_SIZE,
+        DataState, DatabasePicker, DeleteConnectionConfirm, DeleteQueryConfirm, DestructiveConfirm,
+        ExportDraft, FilterDraft, ImportDraft, ImportProgress, NewConnectionDraft, QueryTask,
+        RecordDetail, RunnerFocus, SaveQueryDraft, SchemaRow, SchemaState, SessionId,
+    },
 };
+use crate::{
+    db::{
+        driver::{RowSource, STREAM_AHEAD},
+        edit::{EditError, parse_cell},
+        export::{ExportFormat, ExportSource, plain_text},
+        import::ImportPlan,
+        introstect::SchemaObject,
+        query::{StatementClass, classify, is_read_only, statement_at},
+        types::{CellValue, QueryResult},
+    },
+    domain::{
+        connection::{
+            ConnectionLabels, ConnectionOptions, DbKind, Environment, LabelColor, SshTunnel,
+            TlsSettings, cycle_option,
+        },
+        filter::{CompiledFilter, Condition, FilterError, FilterOp, RowFilter, SortKey},
+        querylib::{SavedQuery, parse_tags},
+    },
+    storage::external::{DEFAULT_SOURCE, ImportedConnection},
+};
+use std::time::{Duration, Instant};
+use ulid::Ulid;
+
+/// Text behind the active field; `None` for the selectors.
+fn draft_field_mut(d: &mut NewConnectionDraft) -> Option<&mut String> {
+    match d.field {
+        NewConnectionDraft::NAME_FIELD => Some(&mut d.name),
+        NewConnectionDraft::KIND_FIELD => None,
+        NewConnectionDraft::HOST_FIELD => Some(&mut d.host),
+        NewConnectionDraft::PORT_FIELD => Some(&mut d.port),
+        NewConnectionDraft::USER_FIELD => Some(&mut d.user),
+        NewConnectionDraft::PASSWORD_FIELD => Some(&mut d.password),
+        NewConnectionDraft::DATABASE_FIELD => Some(&mut d.database),
+        NewConnectionDraft::TIMEOUT_FIELD => Some(&mut d.timeout),
+        NewConnectionDraft::SSH_HOST_FIELD => Some(&mut d.ssh_host),
+        NewConnectionDraft::SSH_PORT_FIELD => Some(&mut d.ssh_port),
+        NewConnectionDraft::SSH_USER_FIELD => Some(&mut d.ssh_user),
+        NewConnectionDraft::SSH_KEY_FIELD => Some(&mut d.ssh_key),
+        NewConnectionDraft::SSH_PASSPHRASE_FIELD => Some(&mut d.ssh_passphrase),
+        NewConnectionDraft::TLS_CA_FIELD => Some(&mut d.tls_ca),
+        NewConnectionDraft::TLS_CERT_FIELD => Some(&mut d.tls_cert),
+        NewConnectionDraft::TLS_KEY_FIELD => Some(&mut d.tls_key),
+        NewConnectionDraft::CHARSET_FIELD => Some(&mut d.charset),
+        NewConnectionDraft::COLLATION_FIELD => Some(&mut d.collation),
+        NewConnectionDraft::TIMEZONE_FIELD => Some(&mut d.timezone),
+        NewConnectionDraft::SOCKET_FIELD => Some(&mut d.socket),
+        NewConnectionDraft::CONNECT_TIMEOUT_FIELD => Some(&mut d.connect_timeout),
+        NewConnectionDraft::POOL_SIZE_FIELD => Some(&mut d.pool_size),
+        NewConnectionDraft::GROUP_FIELD => Some(&mut d.group),
+        NewConnectionDraft::TAGS_FIELD => Some(&mut d.tags),
+        _ => None, // the selectors and switches
+    }
+}
+
+fn cycle_draft_kind(d: &mut NewConnectionDraft, forward: bool) {
+    let old = d.kind;
+    d.kind = old.cycle(forward);
+    // Follow the engine's default port unless the user picked a custom one.
+    if d.port.trim().is_empty() || d.port.trim() == old.default_port().to_string() {
+        d.port = d.kind.default_port().to_string();
+    }
+}
+
+/// Validate the add/edit modal and build the profile it describes; the error
+/// is the status message naming what to fix.
+fn draft_item(d: &NewConnectionDraft) -> Result<ConnectionItem, String> {
+    let name = d.name.trim().to_string();
+    let host = d.host.trim().to_string();
+    let user = d.user.trim().to_string();
+    let db = d.database.trim().to_string();
+
+    if d.kind.is_file() {
+        if name.is_empty() || db.is_empty() {
+            return Err("Missing required fields (name/file)".to_string());
+        }
+    } else if name.is_empty() || host.is_empty() || user.is_empty() {
+        return Err("Missing required fields (name/host/user)".to_string());
+    }
+
+    let port: u16 = match d.port.trim().parse() {
+        Ok(p) => p,
+        Err(_) if d.kind.is_file() => 0,
+        Err(_) => {
+            return Err(format!(
+                "Port must be a number (e.g. {})",
+                d.kind.default_port()
+            ));
+        }
+    };
+
+    let timeout = d.timeout.trim();
+    let statement_timeout_secs = match timeout.parse::<u64>() {
+        Ok(0) => None,
+        Ok(s) => Some(s),
+        Err(_) if timeout.is_empty() => None,
+        Err(_) => {
+            return Err("Timeout must be a whole number of seconds (blank for none)".to_string());
+        }
+    };
+
+    // A bastion host turns the tunnel on; file engines never use one.
+    let ssh_host = d.ssh_host.trim();
+    let ssh = if ssh_host.is_empty() || d.kind.is_file() {
+        None
+    } else {
+        let ssh_port = d.ssh_port.trim();
+        let port = match ssh_port.parse::<u16>() {
+            Ok(p) => p,
+            Err(_) if ssh_port.is_empty() => SshTunnel::DEFAULT_PORT,
+            Err(_) => {
+                return Err(format!(
+                    "SSH port must be a number (e.g. {})",
+                    SshTunnel::DEFAULT_PORT
+                ));
+            }
+        };
+        let key = d.ssh_key.trim();
+        Some(SshTunnel {
+            host: ssh_host.to_string(),
+            port,
+            user: d.ssh_user.trim().to_string(),
+            key_file: (!key.is_empty()).then(|| key.to_string()),
+            skip_host_key_check: d.ssh_skip_host_key_check,
+        })
+    };
+
+    let path = |p: &str| (!p.trim().is_empty()).then(|| p.trim().to_string());
+    let tls = TlsSettings {
+        mode: d.tls_mode,
+        ca: path(&d.tls_ca),
+        cert: path(&d.tls_cert),
+        key: path(&d.tls_key),
+    };
+    if tls.cert.is_some() != tls.key.is_some() {
+        return Err("A client certificate needs its key (and the other way round)".to_string());
+    }
+
+    let connect_timeout = d.connect_timeout.trim();
+    let connect_timeout_secs = match connect_timeout.parse::<u64>() {
+        Ok(s) if s > 0 => Some(s),
+        Err(_) if connect_timeout.is_empty() => None,
+        _ => {
+            return Err(
+                "Connect timeout must be a positive number of seconds (blank for 30)".to_string(),
+            );
+        }
+    };
+    let pool_size = d.pool_size.trim();
+    let pool_size = match pool_size.parse::<u32>() {
+        Ok(n) if n > 0 => Some(n),
+        Err(_) if pool_size.is_empty() => None,
+        _ => return Err("Pool size must be a positive number (blank for 5)".to_string()),
+    };
+    let options = ConnectionOptions {
+        charset: path(&d.charset),
+        collation: path(&d.collation),
+        timezone: path(&d.timezone),
+        socket: path(&d.socket),
+        connect_timeout_secs,
+        pool_size,
+        read_only: d.read_only,
+    };
+    if options.socket.is_some() && ssh.is_some() {
+        return Err("A socket is local; it can't be reached through an SSH tunnel".to_string());
+    }
+
+    let mut item =
+        ConnectionItem::new_with_id(d.id, d.kind, &name, &host, port, &user, &d.password, &db);
+    item.statement_timeout_secs = statement_timeout_secs;
+    if ssh.is_some() {
+        item.ssh_passphrase = d.ssh_passphrase.clone();
+    }
+    item.ssh = ssh;
+    if d.kind.is_file() {
+        // Of the options, only the read-only switch applies to a file.
+        item.options.read_only = d.read_only;
+    } else {
+        item.tls = tls;
+        item.options = options;
+    }
+    item.production = d.production;
+    item.labels = ConnectionLabels {
+        group: path(&d.group),
+        tags: parse_tags(&d.tags),
+        environment: d.environment,
+        color: d.color,
+    };
+    Ok(item)
+}
 
-fn draft_field_mut(d: &mut NewConnectionDraft) -> &mut String {
+fn save_draft_field_mut(d: &mut SaveQueryDraft) -> &mut String {
     match d.field {
         0 => &mut d.name,
-        1 => &mut d.host,
-        2 => &mut d.port,
-        3 => &mut d.user,
-        4 => &mut d.password,
-        _ => &mut d.database, // 5
+        _ => &mut d.tags, // 1
     }
 }
 
@@ -24,108 +214,1420 @@ fn build_profiles(
         .iter()
         .map(|i| crate::storage::model::ConnectionProfile {
             id: i.id.to_string(), // <-- FIX: Ulid -> String
+            kind: i.kind,
             name: i.name.clone(),
             host: i.host.clone(),
             port: i.port,
             user: i.user.clone(),
-            database: i.db.clone(),
+            database: (!i.db.is_empty()).then(|| i.db.clone()),
+            statement_timeout_secs: i.statement_timeout_secs,
+            ssh: i.ssh.clone(),
+            tls: i.tls.clone(),
+            options: i.options.clone(),
+            labels: i.labels.clone(),
+            production: i.production,
         })
         .collect()
 }
 
-pub fn reduce_action(state: &mut AppState, action: Action) -> Vec<Command> {
-    // --- Modal first: if add/edit connection modal is open, most actions operate on it ---
-    if state.screen == Screen::Connections && state.connections.adding.is_some() {
-        match action {
-            Action::CancelModal | Action::Back => {
-                state.connections.adding = None;
-                state.status.message = "Cancelled".to_string();
+fn connect(state: &AppState, item: ConnectionItem) -> Command {
+    state.sessions.command(DbCommand::Connect {
+        id: item.id,
+        name: item.name.clone(),
+        statement_timeout: item.statement_timeout_secs.map(Duration::from_secs),
+        target: Box::new(target(item)),
+    })
+}
+
+fn target(item: ConnectionItem) -> ConnectTarget {
+    ConnectTarget {
+        kind: item.kind,
+        host: item.host,
+        port: item.port,
+        user: item.user,
+        password: item.password,
+        db: item.db,
+        ssh: item.ssh,
+        ssh_passphrase: item.ssh_passphrase,
+        tls: item.tls,
+        options: item.options,
+    }
+}
+
+/// Profile name of the active session, for tab titles and messages.
+fn session_name(state: &AppState) -> String {
+    let i = state.sessions.index(state.sessions.active).unwrap_or(0);
+    state.session_titles().swap_remove(i)
+}
+
+/// Describe the selected table/view, or fetch the selected routine's source.
+fn load_selected_object(state: &mut AppState) -> Vec<Command> {
+    let Some(object) = state.schema.selected_object().cloned() else {
+        return vec![];
+    };
+    state.schema.detail_scroll = 0;
+    if object.kind.is_relation() {
+        state.status.message = format!("Loading columns for {}…", object.name);
+        vec![
+            state
+                .sessions
+                .command(DbCommand::DescribeTable { table: object.name }),
+        ]
+    } else {
+        state.status.message = format!("Loading definition of {}…", object.name);
+        vec![state.sessions.command(DbCommand::LoadDefinition { object })]
+    }
+}
+
+fn save_queries(state: &AppState) -> Command {
+    Command::Storage(StorageCommand::SaveQueries {
+        queries: state.queries.items.clone(),
+    })
+}
+
+/// The import prompt takes a DSN or the path of a file to read connections
+/// from; the storage worker does the reading.
+fn reduce_connection_import(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(source) = state.connections.importing.as_mut() else {
+        return vec![];
+    };
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.connections.importing = None;
+            state.status.message = "Cancelled".to_string();
+            vec![]
+        }
+        Action::Backspace => {
+            source.pop();
+            vec![]
+        }
+        Action::InputChar(c) if !c.is_control() => {
+            source.push(c);
+            vec![]
+        }
+        Action::Confirm => {
+            let source = std::mem::take(source);
+            state.connections.importing = None;
+            // Not echoed: a DSN may carry a password.
+            state.status.message = "Importing connections…".to_string();
+            vec![Command::Storage(StorageCommand::ImportConnections {
+                source,
+            })]
+        }
+        _ => vec![],
+    }
+}
+
+/// Send `sql` to the active session as a new Runner task.
+fn run_statement(state: &mut AppState, sql: String) -> Vec<Command> {
+    // A result still streaming stops where it is.
+    let mut cmds = Vec::new();
+    if let Some(old) = state.runner.task.take() {
+        cmds.push(state.sessions.command(DbCommand::Cancel { id: old.id }));
+    }
+
+    state.runner.tasks_started += 1;
+    let id = state.runner.tasks_started;
+    state.runner.running = true;
+    state.runner.task = Some(QueryTask {
+        id,
+        sql: sql.clone(),
+        started: Instant::now(),
+        cancelling: false,
+    });
+    state.runner.error = None;
+    state.runner.expired = false;
+    state.status.message = "Running… (Ctrl+X: cancel)".to_string();
+    cmds.push(state.sessions.command(DbCommand::Execute { id, sql }));
+    cmds
+}
+
+/// The statement runs only once the connection's name is typed exactly.
+fn reduce_destructive_confirm(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(confirm) = state.runner.destructive_confirm.as_mut() else {
+        return vec![];
+    };
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.runner.destructive_confirm = None;
+            state.status.message = "Cancelled".to_string();
+            vec![]
+        }
+        Action::Backspace => {
+            confirm.typed.pop();
+            vec![]
+        }
+        Action::InputChar(c) if !c.is_control() => {
+            confirm.typed.push(c);
+            vec![]
+        }
+        Action::Confirm if confirm.typed == confirm.name => {
+            let sql = std::mem::take(&mut confirm.sql);
+            state.runner.destructive_confirm = None;
+            run_statement(state, sql)
+        }
+        Action::Confirm => {
+            state.status.message =
+                format!("Type '{}' exactly to run it (Esc: cancel)", confirm.name);
+            vec![]
+        }
+        _ => vec![],
+    }
+}
+
+/// Add imported profiles with their passwords in the keyring, skipping any
+/// that point where an existing profile already does.
+fn import_connections(state: &mut AppState, found: Vec<ImportedConnection>) -> Vec<Command> {
+    let secrets = crate::storage::secrets::ConnectionSecrets::default();
+    let total = found.len();
+    let mut added: Vec<ConnectionItem> = Vec::new();
+    for c in found {
+        let p = &c.profile;
+        let db = p.database.as_deref().unwrap_or_default();
+        let known = state.connections.items.iter().chain(&added).any(|i| {
+            i.kind == p.kind
+                && i.host == p.host
+                && i.port == p.port
+                && i.user == p.user
+                && i.db == db
+        });
+        if known {
+            continue;
+        }
+        let item = ConnectionItem::from_profile(Ulid::new(), c.profile, c.password, String::new());
+        if let Err(e) = secrets.set_password(&item.id.to_string(), &item.password) {
+            state.status.message = format!("Failed to store password in keyring: {}", e);
+            return vec![];
+        }
+        added.push(item);
+    }
+
+    let skipped = total - added.len();
+    if added.is_empty() {
+        state.status.message = format!("All {} connections found are already here", total);
+        return vec![];
+    }
+    state.status.message = match skipped {
+        0 => format!("Imported {} connections (saving…)", added.len()),
+        _ => format!(
+            "Imported {} connections, skipped {} already here (saving…)",
+            added.len(),
+            skipped
+        ),
+    };
+    // Select the first new one, with the filter dropped so it is in view.
+    state.connections.filter.clear();
+    let first = state.connections.items.len();
+    state.connections.items.extend(added);
+    state.connections.select_item(first);
+    vec![Command::Storage(StorageCommand::SaveConnections {
+        connections: build_profiles(&state.connections.items),
+    })]
+}
+
+fn reduce_save_prompt(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(d) = state.runner.save_prompt.as_mut() else {
+        return vec![];
+    };
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.runner.save_prompt = None;
+            state.status.message = "Cancelled".to_string();
+            vec![]
+        }
+        Action::NextField | Action::PrevField => {
+            d.field = 1 - d.field;
+            vec![]
+        }
+        Action::Backspace => {
+            save_draft_field_mut(d).pop();
+            vec![]
+        }
+        Action::InputChar(c) if !c.is_control() => {
+            save_draft_field_mut(d).push(c);
+            vec![]
+        }
+        Action::Confirm => {
+            let name = d.name.trim().to_string();
+            if name.is_empty() {
+                state.status.message = "Query name is required".to_string();
                 return vec![];
             }
+            let tags = parse_tags(&d.tags);
+            let target = d.id.clone();
+            state.runner.save_prompt = None;
 
-            Action::NextField => {
-                if let Some(d) = state.connections.adding.as_mut() {
-                    d.field = (d.field + 1) % 6;
+            let sql = state.runner.editor.text();
+            let existing = target
+                .as_ref()
+                .and_then(|id| state.queries.items.iter_mut().find(|q| &q.id == id));
+
+            let id = match existing {
+                Some(q) => {
+                    q.name = name.clone();
+                    q.sql = sql;
+                    q.tags = tags;
+                    q.touch();
+                    state.status.message = format!("Query '{}' updated (saving…)", name);
+                    q.id.clone()
                 }
-                return vec![];
+                None => {
+                    let connection_id = state.connections.active.map(|id| id.to_string());
+                    let q = SavedQuery::new(&name, &sql, tags, connection_id);
+                    let id = q.id.clone();
+                    state.queries.items.push(q);
+                    state.status.message = format!("Query '{}' saved (saving…)", name);
+                    id
+                }
+            };
+
+            state.runner.loaded_query = Some(id);
+            vec![save_queries(state)]
+        }
+        _ => vec![],
+    }
+}
+
+fn reduce_queries_input(state: &mut AppState, action: Action) -> Vec<Command> {
+    // Rename takes precedence; the filter input can't be open at the same time.
+    if let Some(name) = state.queries.renaming.as_mut() {
+        return match action {
+            Action::CancelModal | Action::Back => {
+                state.queries.renaming = None;
+                state.status.message = "Rename cancelled".to_string();
+                vec![]
+            }
+            Action::Backspace => {
+                name.pop();
+                vec![]
+            }
+            Action::InputChar(c) if !c.is_control() => {
+                name.push(c);
+                vec![]
             }
+            Action::Confirm => {
+                let new_name = name.trim().to_string();
+                if new_name.is_empty() {
+                    state.status.message = "Query name is required".to_string();
+                    return vec![];
+                }
+                state.queries.renaming = None;
 
-            Action::PrevField => {
-                if let Some(d) = state.connections.adding.as_mut() {
-                    d.field = (d.field + 5) % 6;
+                let Some(id) = state.queries.selected_item().map(|q| q.id.clone()) else {
+                    return vec![];
+                };
+                if let Some(q) = state.queries.items.iter_mut().find(|q| q.id == id) {
+                    q.name = new_name;
+                    q.touch();
                 }
+                state.status.message = "Query renamed (saving…)".to_string();
+                vec![save_queries(state)]
+            }
+            _ => vec![],
+        };
+    }
+
+    reduce_filter_input(state, action)
+}
+
+/// Inline `/` filter on the Connections, Schema and Queries lists. Typing
+/// jumps to the best match; clearing the filter keeps the selected item.
+fn reduce_filter_input(state: &mut AppState, action: Action) -> Vec<Command> {
+    let screen = state.screen;
+    // Row indices shift as the filter changes, so remember the object itself.
+    let before = state.schema.selected_object().cloned();
+    let (filter, filtering) = match screen {
+        Screen::Connections => (
+            &mut state.connections.filter,
+            &mut state.connections.filtering,
+        ),
+        Screen::Schema => (&mut state.schema.filter, &mut state.schema.filtering),
+        _ => (&mut state.queries.filter, &mut state.queries.filtering),
+    };
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            *filtering = false;
+            state.status.message.clear();
+            return clear_filter(state);
+        }
+        Action::Confirm => {
+            *filtering = false;
+            state.status.message.clear();
+            return vec![];
+        }
+        Action::Backspace => {
+            filter.pop();
+        }
+        Action::InputChar(c) if !c.is_control() => filter.push(c),
+        _ => return vec![],
+    }
+
+    match screen {
+        Screen::Connections => state.connections.selected = 0,
+        Screen::Schema => return select_first_object(state, before),
+        _ => state.queries.selected = 0,
+    }
+    vec![]
+}
+
+/// Drop the current screen's filter, keeping the selection on the same item.
+fn clear_filter(state: &mut AppState) -> Vec<Command> {
+    match state.screen {
+        Screen::Connections => {
+            let row = state.connections.selected_row();
+            let idx = match row {
+                Some(ConnectionRow::Item(i)) => Some(i),
+                _ => None,
+            };
+            state.connections.filter.clear();
+            match idx {
+                Some(i) => state.connections.select_item(i),
+                None => state.connections.selected = 0,
+            }
+        }
+        Screen::Schema => {
+            let object = state.schema.selected_object().cloned();
+            state.schema.filter.clear();
+            state.schema.selected = object
+                .and_then(|o| {
+                    state
+                        .schema
+                        .rows()
+                        .iter()
+                        .position(|r| matches!(r, SchemaRow::Object(x) if **x == o))
+                })
+                .unwrap_or(0);
+        }
+        _ => {
+            let idx = state.queries.visible().get(state.queries.selected).copied();
+            state.queries.filter.clear();
+            state.queries.selected = idx.unwrap_or(0);
+        }
+    }
+    vec![]
+}
+
+/// Select the first object row (the best match while filtering) and load it
+/// unless it is still `before`.
+fn select_first_object(state: &mut AppState, before: Option<SchemaObject>) -> Vec<Command> {
+    state.schema.selected = state
+        .schema
+        .rows()
+        .iter()
+        .position(|r| matches!(r, SchemaRow::Object(_)))
+        .unwrap_or(0);
+    if state.schema.selected_object() == before.as_ref() {
+        return vec![];
+    }
+    load_selected_object(state)
+}
+
+fn reduce_database_picker(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(p) = state.schema.picker.as_mut() else {
+        return vec![];
+    };
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.schema.picker = None;
+            state.status.message.clear();
+            vec![]
+        }
+        Action::Up => {
+            p.selected = p.selected.saturating_sub(1);
+            vec![]
+        }
+        Action::Down if p.selected + 1 < p.items.len() => {
+            p.selected += 1;
+            vec![]
+        }
+        Action::Confirm => {
+            let Some(name) = p.items.get(p.selected).cloned() else {
+                return vec![];
+            };
+            let current = p.current.clone();
+            state.schema.picker = None;
+            if current.as_deref() == Some(name.as_str()) {
                 return vec![];
             }
+            state.status.message = format!("Switching to {}…", name);
+            vec![state.sessions.command(DbCommand::UseSchema { name })]
+        }
+        _ => vec![],
+    }
+}
 
-            Action::Backspace => {
-                if let Some(d) = state.connections.adding.as_mut() {
-                    draft_field_mut(d).pop();
+/// Next (or previous) column name after `current`, wrapping.
+fn cycle_column(columns: &[String], current: &str, forward: bool) -> String {
+    let n = columns.len();
+    if n == 0 {
+        return current.to_string();
+    }
+    let next = match columns.iter().position(|c| c == current) {
+        Some(i) if forward => (i + 1) % n,
+        Some(i) => (i + n - 1) % n,
+        None => 0,
+    };
+    columns[next].clone()
+}
+
+fn reduce_filter_builder(state: &mut AppState, action: Action) -> Vec<Command> {
+    let columns: Vec<String> = state
+        .data
+        .filter_columns()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    let Some(d) = state.data.filter_draft.as_mut() else {
+        return vec![];
+    };
+    let conds = d.filter.conditions.len();
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.data.filter_draft = None;
+            state.status.message = "Cancelled".to_string();
+        }
+        Action::Up => {
+            d.row = d.row.saturating_sub(1);
+            d.field = d.field.min(d.field_count() - 1);
+        }
+        Action::Down if d.row + 1 < d.row_count() => {
+            d.row += 1;
+            d.field = d.field.min(d.field_count() - 1);
+        }
+        Action::NextField if d.row_count() > 0 => d.field = (d.field + 1) % d.field_count(),
+        Action::PrevField if d.row_count() > 0 => {
+            d.field = (d.field + d.field_count() - 1) % d.field_count();
+        }
+        Action::Left | Action::Right => {
+            let forward = action == Action::Right;
+            if let Some(c) = d.filter.conditions.get_mut(d.row) {
+                match d.field {
+                    0 => c.join = c.join.toggle(),
+                    1 => c.column = cycle_column(&columns, &c.column, forward),
+                    2 => c.op = c.op.cycle(forward),
+                    _ => {}
                 }
+            } else if let Some(k) = d.filter.sort.get_mut(d.row - conds) {
+                match d.field {
+                    0 => k.column = cycle_column(&columns, &k.column, forward),
+                    _ => k.descending = !k.descending,
+                }
+            }
+        }
+        Action::Backspace => {
+            if let Some(c) = d.filter.conditions.get_mut(d.row)
+                && d.field == FilterDraft::VALUE_FIELD
+            {
+                c.value.pop();
+            }
+        }
+        Action::InputChar(ch) if !ch.is_control() => {
+            if let Some(c) = d.filter.conditions.get_mut(d.row)
+                && d.field == FilterDraft::VALUE_FIELD
+            {
+                c.value.push(ch);
+            }
+        }
+        Action::AddFilterCondition => {
+            d.filter.conditions.push(Condition {
+                column: columns.first().cloned().unwrap_or_default(),
+                ..Default::default()
+            });
+            d.row = conds;
+            d.field = 1;
+        }
+        Action::AddSortKey => {
+            // Default to the first column not already sorted on.
+            let column = columns
+                .iter()
+                .find(|c| !d.filter.sort.iter().any(|k| &k.column == *c))
+                .or(columns.first())
+                .cloned()
+                .unwrap_or_default();
+            d.filter.sort.push(SortKey {
+                column,
+                descending: false,
+            });
+            d.row = d.row_count() - 1;
+            d.field = 0;
+        }
+        Action::DeleteFilterRow if d.row_count() > 0 => {
+            if d.on_condition() {
+                d.filter.conditions.remove(d.row);
+            } else {
+                d.filter.sort.remove(d.row - conds);
+            }
+            d.row = d.row.min(d.row_count().saturating_sub(1));
+            d.field = d.field.min(d.field_count() - 1);
+        }
+        Action::Confirm => {
+            // Validate before closing so a bad value can be fixed in place.
+            let kind = active_kind(state);
+            let Some(d) = state.data.filter_draft.as_ref() else {
+                return vec![];
+            };
+            if let Err(e) = d.filter.compile(kind, &state.data.filter_columns()) {
+                state.status.message = format!("Filter error: {}", e);
                 return vec![];
             }
+            state.data.filter = d.filter.clone();
+            state.data.filter_draft = None;
+            return load_data_page(state, 0);
+        }
+        _ => {}
+    }
+    vec![]
+}
 
-            Action::InputChar(c) => {
-                if let Some(d) = state.connections.adding.as_mut() {
-                    if !c.is_control() {
-                        draft_field_mut(d).push(c);
+fn reduce_export(state: &mut AppState, action: Action) -> Vec<Command> {
+    let draft = if state.screen == Screen::Data {
+        state.data.export.as_mut()
+    } else {
+        state.runner.export.as_mut()
+    };
+    let Some(d) = draft else {
+        return vec![];
+    };
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.data.export = None;
+            state.runner.export = None;
+            state.status.message = "Export cancelled".to_string();
+        }
+        Action::NextField => d.step_field(true),
+        Action::PrevField => d.step_field(false),
+        Action::Left | Action::Right => {
+            let forward = action == Action::Right;
+            match d.field {
+                ExportDraft::FORMAT_FIELD => {
+                    d.set_format(d.options.format.cycle(forward));
+                    if !d.fields().contains(&d.field) {
+                        d.field = ExportDraft::FORMAT_FIELD;
                     }
                 }
-                return vec![];
+                ExportDraft::ROWS_FIELD => d.all_rows = !d.all_rows,
+                ExportDraft::HEADER_FIELD => d.options.header = !d.options.header,
+                ExportDraft::QUOTE_FIELD => d.options.quote = d.options.quote.cycle(forward),
+                ExportDraft::STATEMENT_FIELD => {
+                    d.options.statement = d.options.statement.cycle(forward)
+                }
+                _ => {}
             }
+        }
+        Action::Backspace => match d.field {
+            ExportDraft::PATH_FIELD => {
+                d.path.pop();
+            }
+            ExportDraft::NULL_FIELD => {
+                d.options.null.pop();
+            }
+            ExportDraft::TABLE_FIELD => {
+                d.options.table.pop();
+            }
+            ExportDraft::BATCH_FIELD => d.options.batch_size /= 10,
+            _ => {}
+        },
+        Action::InputChar(c) if !c.is_control() => match d.field {
+            ExportDraft::PATH_FIELD => d.path.push(c),
+            ExportDraft::DELIMITER_FIELD => d.options.delimiter = c,
+            ExportDraft::NULL_FIELD => d.options.null.push(c),
+            ExportDraft::TABLE_FIELD => d.options.table.push(c),
+            ExportDraft::BATCH_FIELD => {
+                if let Some(n) = c.to_digit(10) {
+                    d.options.batch_size = (d.options.batch_size * 10 + n as usize).min(100_000);
+                }
+            }
+            _ => {}
+        },
+        Action::Confirm => return start_export(state),
+        _ => {}
+    }
+    vec![]
+}
 
-            Action::Confirm => {
-                // Enter = Save (add or edit)
-                let Some(d) = state.connections.adding.take() else {
-                    return vec![];
-                };
-
-                let name = d.name.trim().to_string();
-                let host = d.host.trim().to_string();
-                let user = d.user.trim().to_string();
-                let db = d.database.trim().to_string();
+/// Turn the open export dialog into a worker command. The rows on screen, or
+/// the marked ones, go along with it; a full export is streamed by the worker
+/// instead.
+fn start_export(state: &mut AppState) -> Vec<Command> {
+    let on_data = state.screen == Screen::Data;
+    let Some(draft) = (if on_data {
+        state.data.export.clone()
+    } else {
+        state.runner.export.clone()
+    }) else {
+        return vec![];
+    };
+    if draft.path.trim().is_empty() {
+        state.status.message = "Export needs a file path".to_string();
+        return vec![];
+    }
+    if draft.options.format == ExportFormat::Sql {
+        if draft.options.table.trim().is_empty() {
+            state.status.message = "SQL export needs a target table".to_string();
+            return vec![];
+        }
+        if draft.options.batch_size == 0 {
+            state.status.message = "Batch size must be at least 1".to_string();
+            return vec![];
+        }
+    }
 
-                if name.is_empty() || host.is_empty() || user.is_empty() || db.is_empty() {
-                    state.status.message =
-                        "Missing required fields (name/host/user/database)".to_string();
-                    state.connections.adding = Some(d);
+    let source = if on_data {
+        let Some(table) = state.data.table.clone() else {
+            return vec![];
+        };
+        if draft.all_rows {
+            let filter = match compiled_data_filter(state) {
+                Ok(f) => f,
+                Err(e) => {
+                    state.status.message = format!("Filter error: {}", e);
                     return vec![];
                 }
+            };
+            ExportSource::Stream(RowSource::Table {
+                table,
+                order_by: state.data.pk_column.clone(),
+                filter,
+            })
+        } else {
+            // Marked rows narrow the export to just those.
+            let data = &state.data;
+            let rows = if data.marked.is_empty() {
+                data.rows.clone()
+            } else {
+                data.marked
+                    .iter()
+                    .filter_map(|&i| data.rows.get(i).cloned())
+                    .collect()
+            };
+            ExportSource::Rows {
+                columns: data.columns.clone(),
+                rows,
+            }
+        }
+    } else {
+        let Some(result) = state.runner.result.as_ref() else {
+            return vec![];
+        };
+        if draft.all_rows {
+            // Getting every row means running the statement again.
+            if !is_read_only(&result.sql, active_kind(state)) {
+                state.status.message =
+                    "Only a single read-only statement can be re-run for a full export".to_string();
+                return vec![];
+            }
+            ExportSource::Stream(RowSource::Query {
+                sql: result.sql.clone(),
+            })
+        } else {
+            ExportSource::Rows {
+                columns: result.columns.clone(),
+                rows: result.rows.iter().cloned().collect(),
+            }
+        }
+    };
 
-                let port: u16 = match d.port.trim().parse() {
-                    Ok(p) => p,
-                    Err(_) => {
-                        state.status.message = "Port must be a number (e.g. 3306)".to_string();
-                        state.connections.adding = Some(d);
-                        return vec![];
-                    }
-                };
+    state.data.export = None;
+    state.runner.export = None;
+    state.status.message = format!("Exporting to {}… (Ctrl+X: cancel)", draft.path);
+    let id = start_transfer(state);
+    vec![state.sessions.command(DbCommand::Export {
+        id,
+        source,
+        path: draft.path,
+        options: draft.options,
+    })]
+}
 
-                let item = crate::app::state::ConnectionItem::new_with_id(
-                    d.id,
-                    &name,
-                    &host,
-                    port,
-                    &user,
-                    &d.password,
-                    &db,
-                );
+/// A task id for an export or import, kept so Ctrl+X can cancel it.
+fn start_transfer(state: &mut AppState) -> u64 {
+    state.runner.tasks_started += 1;
+    state.runner.transfer = Some(state.runner.tasks_started);
+    state.runner.tasks_started
+}
 
-                // Store secret (password) in keyring; only persist non-secret fields to disk.
-                if let Err(e) = crate::storage::secrets::ConnectionSecrets::default()
-                    .set_password(&item.id.to_string(), &item.password)
-                {
-                    state.status.message = format!("Failed to store password in keyring: {}", e);
+fn reduce_import(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(d) = state.data.import.as_mut() else {
+        return vec![];
+    };
+    if d.progress.is_some() {
+        if let (Action::CancelModal | Action::Back, Some(id)) = (action, state.runner.transfer) {
+            state.status.message = "Cancelling the import…".to_string();
+            return vec![state.sessions.command(DbCommand::Cancel { id })];
+        }
+        state.status.message = "Import running… (Esc: cancel)".to_string();
+        return vec![];
+    }
+
+    // Mapping page: pick the file column feeding each table column.
+    if let Some(preview) = d.preview.as_ref() {
+        let n = preview.table.columns.len();
+        match action {
+            Action::CancelModal | Action::Back => {
+                d.preview = None;
+                state.status.message =
+                    "Import: Tab field • ←/→ change • Enter read file • Esc cancel".to_string();
+            }
+            Action::Up => d.selected = d.selected.saturating_sub(1),
+            Action::Down if d.selected + 1 < n => d.selected += 1,
+            Action::Left | Action::Right => {
+                let Some(col) = preview.table.columns.get(d.selected) else {
+                    return vec![];
+                };
+                if col.is_generated() {
+                    state.status.message = format!("{} is generated by the database", col.name);
                     return vec![];
                 }
-
-                // Upsert by id: if exists, replace; else append.
-                if let Some(idx) = state.connections.items.iter().position(|c| c.id == item.id) {
-                    state.connections.items[idx] = item;
-                    state.connections.selected = idx;
-                    state.status.message = "Connection updated (saving…)".to_string();
+                // Cycle through "not imported" and each file column.
+                let choices = preview.columns.len() + 1;
+                let current = d.mapping[d.selected].map_or(0, |i| i + 1);
+                let next = if action == Action::Right {
+                    (current + 1) % choices
                 } else {
-                    state.connections.items.push(item);
-                    state.connections.selected = state.connections.items.len().saturating_sub(1);
+                    (current + choices - 1) % choices
+                };
+                d.mapping[d.selected] = next.checked_sub(1);
+            }
+            Action::Confirm => {
+                let plan = match ImportPlan::new(preview, &d.mapping, d.options.mode) {
+                    Ok(plan) => plan,
+                    Err(e) => {
+                        state.status.message = format!("Import error: {}", e);
+                        return vec![];
+                    }
+                };
+                d.progress = Some(ImportProgress::default());
+                state.status.message = if d.options.dry_run {
+                    "Dry run: checking rows… (Esc: cancel)".to_string()
+                } else {
+                    format!("Importing into {}… (Esc: cancel)", plan.table)
+                };
+                let (path, options) = (d.path.clone(), d.options.clone());
+                let id = start_transfer(state);
+                return vec![state.sessions.command(DbCommand::Import {
+                    id,
+                    plan,
+                    path,
+                    options,
+                })];
+            }
+            _ => {}
+        }
+        return vec![];
+    }
+
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.data.import = None;
+            state.status.message = "Import cancelled".to_string();
+        }
+        Action::NextField => d.step_field(true),
+        Action::PrevField => d.step_field(false),
+        Action::Left | Action::Right => {
+            let forward = action == Action::Right;
+            match d.field {
+                ImportDraft::FORMAT_FIELD => {
+                    d.options.format = d.options.format.cycle(forward);
+                    if !d.fields().contains(&d.field) {
+                        d.field = ImportDraft::FORMAT_FIELD;
+                    }
+                }
+                ImportDraft::HEADER_FIELD => d.options.header = !d.options.header,
+                ImportDraft::MODE_FIELD => d.options.mode = d.options.mode.toggle(),
+                ImportDraft::DRY_RUN_FIELD => d.options.dry_run = !d.options.dry_run,
+                _ => {}
+            }
+        }
+        Action::Backspace => match d.field {
+            ImportDraft::PATH_FIELD => {
+                d.path.pop();
+            }
+            ImportDraft::NULL_FIELD => {
+                d.options.null.pop();
+            }
+            ImportDraft::BATCH_FIELD => d.options.batch_size /= 10,
+            _ => {}
+        },
+        Action::InputChar(c) if !c.is_control() => match d.field {
+            ImportDraft::PATH_FIELD => d.path.push(c),
+            ImportDraft::DELIMITER_FIELD => d.options.delimiter = c,
+            ImportDraft::NULL_FIELD => d.options.null.push(c),
+            ImportDraft::BATCH_FIELD => {
+                if let Some(n) = c.to_digit(10) {
+                    d.options.batch_size = (d.options.batch_size * 10 + n as usize).min(100_000);
+                }
+            }
+            _ => {}
+        },
+        Action::Confirm => {
+            if d.path.trim().is_empty() {
+                state.status.message = "Import needs a file path".to_string();
+                return vec![];
+            }
+            if d.options.batch_size == 0 {
+                state.status.message = "Batch size must be at least 1".to_string();
+                return vec![];
+            }
+            let Some(table) = state.data.table.clone() else {
+                return vec![];
+            };
+            state.status.message = format!("Reading {}…", d.path);
+            return vec![state.sessions.command(DbCommand::PreviewImport {
+                table,
+                path: d.path.clone(),
+                options: d.options.clone(),
+            })];
+        }
+        _ => {}
+    }
+    vec![]
+}
+
+/// Cell editor over the Data grid. Enter checks the text against the column
+/// type and stages it; Delete sets NULL.
+fn reduce_cell_editor(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(ed) = state.data.cell_editor.as_mut() else {
+        return vec![];
+    };
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.data.cell_editor = None;
+            state.status.message = "Edit cancelled".to_string();
+        }
+        Action::Delete => ed.value = None,
+        Action::Backspace => {
+            if let Some(v) = ed.value.as_mut() {
+                v.pop();
+            }
+        }
+        Action::InputChar(c) if !c.is_control() => {
+            ed.value.get_or_insert_with(String::new).push(c);
+        }
+        Action::Confirm => {
+            let CellEditor { row, col, value } = ed.clone();
+            let column = state.data.edit_columns().swap_remove(col);
+            if value.is_none() && !column.is_nullable {
+                state.status.message = format!("Edit error: {} can't be NULL", column.name);
+                return vec![];
+            }
+            let value = match parse_cell(&column, value.as_deref()) {
+                Ok(v) => v,
+                Err(e) => {
+                    state.status.message = format!("Edit error: {}", e);
+                    return vec![];
+                }
+            };
+            let data = &mut state.data;
+            data.changes.stage(row, col, value, &data.rows[row][col]);
+            data.cell_editor = None;
+            state.status.message = pending_summary(data);
+        }
+        _ => {}
+    }
+    vec![]
+}
+
+/// Review of the statements a save will run; Enter runs them.
+fn reduce_change_review(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(r) = state.data.review.as_mut() else {
+        return vec![];
+    };
+    if r.saving {
+        state.status.message = "Saving…".to_string();
+        return vec![];
+    }
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.data.review = None;
+            state.status.message = pending_summary(&state.data);
+        }
+        Action::Up => r.scroll = r.scroll.saturating_sub(1),
+        Action::Down if r.scroll + 1 < r.statements.len() => r.scroll += 1,
+        Action::Confirm => {
+            let Some(table) = state.data.table.clone() else {
+                return vec![];
+            };
+            r.saving = true;
+            state.status.message = format!("Saving {} changes…", r.statements.len());
+            return vec![state.sessions.command(DbCommand::ApplyChanges {
+                table,
+                statements: r.statements.clone(),
+            })];
+        }
+        _ => {}
+    }
+    vec![]
+}
+
+/// Whether an answer from the worker belongs to the statement in flight;
+/// a cancelled SQLite statement may still answer after being dropped.
+fn is_current_query(state: &AppState, id: u64) -> bool {
+    state.runner.task.as_ref().is_some_and(|t| t.id == id)
+}
+
+/// Rows PgUp/PgDn move the Runner grid's cursor by.
+const RESULT_PAGE: usize = 20;
+
+/// Let a streaming result read on once the Runner cursor comes within half
+/// of [`STREAM_AHEAD`] of its last fetched row. Only cursor moves call this,
+/// so rows dropped from the buffer's front never pull in more by themselves.
+fn fetch_ahead(state: &AppState) -> Vec<Command> {
+    let runner = &state.runner;
+    let (Some(task), Some(result)) = (runner.task.as_ref(), runner.result.as_ref()) else {
+        return vec![];
+    };
+    if runner.running || task.cancelling {
+        return vec![];
+    }
+    let cursor = result.rows.dropped() + runner.selected_row as u64;
+    if result.rows.fetched() > cursor + STREAM_AHEAD / 2 {
+        return vec![];
+    }
+    vec![state.sessions.command(DbCommand::FetchRows {
+        id: task.id,
+        upto: cursor + STREAM_AHEAD,
+    })]
+}
+
+/// Lines PgUp/PgDn scroll the record view by.
+const DETAIL_PAGE: usize = 10;
+
+/// The selected row of the Data or Runner grid, as the record view shows it,
+/// with the number of rows it can step through.
+fn detail_record(state: &AppState) -> Option<(Vec<CellValue>, usize)> {
+    match state.screen {
+        Screen::Data => {
+            let data = &state.data;
+            Some((data.record(data.selected_row)?, data.rows.len()))
+        }
+        Screen::Runner => {
+            let rows = &state.runner.result.as_ref()?.rows;
+            Some((rows.get(state.runner.selected_row)?.clone(), rows.len()))
+        }
+        _ => None,
+    }
+}
+
+fn reduce_record_detail(state: &mut AppState, action: Action) -> Vec<Command> {
+    let (height, rows) = detail_record(state)
+        .map(|(row, n)| (RecordDetail::height(&row), n))
+        .unwrap_or_default();
+    let (selected, detail) = match state.screen {
+        Screen::Data => (&mut state.data.selected_row, &mut state.data.detail),
+        _ => (&mut state.runner.selected_row, &mut state.runner.detail),
+    };
+    if matches!(action, Action::CancelModal | Action::Back) {
+        *detail = None;
+        state.status.message = "Ready".to_string();
+        return vec![];
+    }
+    let Some(d) = detail.as_mut() else {
+        return vec![];
+    };
+    let last = height.saturating_sub(1);
+    match action {
+        Action::Up => d.scroll = d.scroll.saturating_sub(1),
+        Action::Down => d.scroll = (d.scroll + 1).min(last),
+        Action::PageUp => d.scroll = d.scroll.saturating_sub(DETAIL_PAGE),
+        Action::PageDown => d.scroll = (d.scroll + DETAIL_PAGE).min(last),
+        Action::Left if *selected > 0 => {
+            *selected -= 1;
+            d.scroll = 0;
+        }
+        Action::Right if *selected + 1 < rows => {
+            *selected += 1;
+            d.scroll = 0;
+        }
+        _ => return vec![],
+    }
+    state.status.message = format!("Row {} of {}", *selected + 1, rows);
+    fetch_ahead(state)
+}
+
+/// Open the row the selected cell's foreign key points at.
+fn follow_reference(state: &mut AppState) -> Vec<Command> {
+    let data = &state.data;
+    let Some(column) = data.columns.get(data.selected_col) else {
+        return vec![];
+    };
+    let Some(fk) = data
+        .foreign_keys
+        .iter()
+        .find(|fk| fk.columns.contains(&column.name))
+    else {
+        state.status.message = format!("{} is not a foreign key", column.name);
+        return vec![];
+    };
+    let Some(row) = data.rows.get(data.selected_row) else {
+        return vec![];
+    };
+    let pairs = fk.columns.iter().zip(&fk.ref_columns);
+    match match_row(data, row, pairs.map(|(c, r)| (r.as_str(), c.as_str()))) {
+        Ok(filter) => open_linked_table(state, fk.ref_table.clone(), filter),
+        Err(message) => {
+            state.status.message = message;
+            vec![]
+        }
+    }
+}
+
+fn reduce_reference_picker(state: &mut AppState, action: Action) -> Vec<Command> {
+    let Some(selected) = state.data.reference_picker else {
+        return vec![];
+    };
+    match action {
+        Action::CancelModal | Action::Back => {
+            state.data.reference_picker = None;
+            state.status.message = "Cancelled".to_string();
+        }
+        Action::Up => state.data.reference_picker = Some(selected.saturating_sub(1)),
+        Action::Down if selected + 1 < state.data.referenced_by.len() => {
+            state.data.reference_picker = Some(selected + 1);
+        }
+        Action::Confirm => {
+            state.data.reference_picker = None;
+            let data = &state.data;
+            let (Some(r), Some(row)) = (
+                data.referenced_by.get(selected),
+                data.rows.get(data.selected_row),
+            ) else {
+                return vec![];
+            };
+            let pairs = r.key.columns.iter().zip(&r.key.ref_columns);
+            return match match_row(data, row, pairs.map(|(c, p)| (c.as_str(), p.as_str()))) {
+                Ok(filter) => open_linked_table(state, r.table.clone(), filter),
+                Err(message) => {
+                    state.status.message = message;
+                    vec![]
+                }
+            };
+        }
+        _ => {}
+    }
+    vec![]
+}
+
+/// A filter matching `row` on another table: each `(their column, our
+/// column)` pair becomes `their column = value`. NULLs match nothing.
+fn match_row<'a>(
+    data: &DataState,
+    row: &[CellValue],
+    pairs: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<RowFilter, String> {
+    let mut filter = RowFilter::default();
+    for (theirs, ours) in pairs {
+        let Some(i) = data.columns.iter().position(|c| c.name == ours) else {
+            return Err(format!("{} is not among the loaded columns", ours));
+        };
+        if row[i].is_null() {
+            return Err(format!("{} is NULL, so no row matches", ours));
+        }
+        filter.conditions.push(Condition {
+            column: theirs.to_string(),
+            op: FilterOp::Eq,
+            value: plain_text(&row[i]),
+            ..Default::default()
+        });
+    }
+    Ok(filter)
+}
+
+/// Open `table` on Data filtered by `filter`, keeping the current table on
+/// the back stack. Rows load once the table is described, since the filter
+/// needs its column types.
+fn open_linked_table(state: &mut AppState, table: String, filter: RowFilter) -> Vec<Command> {
+    if has_pending_changes(state) {
+        return vec![];
+    }
+    let mut prev = std::mem::take(&mut state.data);
+    let mut trail = std::mem::take(&mut prev.trail);
+    prev.detail = None;
+    trail.push(prev);
+    state.data = DataState {
+        table: Some(table.clone()),
+        filter,
+        trail,
+        ..Default::default()
+    };
+    state.status.message = format!("Opening {}…", state.data.breadcrumb());
+    vec![
+        state
+            .sessions
+            .command(DbCommand::DescribeDataTable { table }),
+    ]
+}
+
+/// "N pending changes" with the keys that act on them.
+fn pending_summary(data: &DataState) -> String {
+    match data.changes.count() {
+        0 => "No pending changes".to_string(),
+        n => format!("{} pending changes • s: review and save • u: discard", n),
+    }
+}
+
+/// Refuse to leave the page while edits are staged on it.
+fn has_pending_changes(state: &mut AppState) -> bool {
+    let n = state.data.changes.count();
+    if n > 0 {
+        state.status.message = format!(
+            "{} unsaved changes on this page • s: review and save • u: discard",
+            n
+        );
+    }
+    n > 0
+}
+
+/// Editing needs a row key; says so in the status bar when there is none.
+fn require_edit_key(state: &mut AppState) -> bool {
+    if state.data.edit_key().is_empty() {
+        let table = state.data.table.clone().unwrap_or_default();
+        state.status.message = EditError::NoKey(table).to_string();
+        return false;
+    }
+    true
+}
+
+fn open_cell_editor(state: &mut AppState) -> Vec<Command> {
+    let data = &state.data;
+    let (row, col) = (data.selected_row, data.selected_col);
+    if row >= data.rows.len() || col >= data.columns.len() || !require_edit_key(state) {
+        return vec![];
+    }
+    let data = &mut state.data;
+    if data.changes.deleted.contains(&row) {
+        state.status.message = "Row is marked for deletion (d to unmark)".to_string();
+        return vec![];
+    }
+    let current = data.changes.cell(row, col).unwrap_or(&data.rows[row][col]);
+    let value = (!current.is_null()).then(|| plain_text(current));
+    state.status.message = format!(
+        "Edit {}: Enter stage • Del NULL • Esc cancel",
+        data.columns[col].name
+    );
+    data.cell_editor = Some(CellEditor { row, col, value });
+    vec![]
+}
+
+/// Move the Data grid's cell cursor one column in display order. The grid
+/// scrolls right by itself to keep the cursor in view.
+fn step_data_column(data: &mut DataState, forward: bool) {
+    let order = data.column_order();
+    let Some(last) = order.len().checked_sub(1) else {
+        return;
+    };
+    let pos = order
+        .iter()
+        .position(|&c| c == data.selected_col)
+        .unwrap_or(0);
+    let pos = if forward {
+        (pos + 1).min(last)
+    } else {
+        pos.saturating_sub(1)
+    };
+    data.selected_col = order[pos];
+    let frozen = data.frozen_index();
+    if Some(data.selected_col) != frozen {
+        let scroll_pos = pos - usize::from(frozen.is_some());
+        data.col_offset = data.col_offset.min(scroll_pos);
+    }
+}
+
+fn runner_row_count(state: &AppState) -> usize {
+    state.runner.result.as_ref().map_or(0, |r| r.rows.len())
+}
+
+fn runner_column_count(state: &AppState) -> usize {
+    state.runner.result.as_ref().map_or(0, |r| r.columns.len())
+}
+
+/// Engine of the connected profile; filters are compiled for its dialect.
+fn active_kind(state: &AppState) -> DbKind {
+    state
+        .connections
+        .active
+        .and_then(|id| state.connections.items.iter().find(|c| c.id == id))
+        .map(|c| c.kind)
+        .unwrap_or_default()
+}
+
+/// The Data screen's filter compiled for the connected engine; None when empty.
+fn compiled_data_filter(state: &AppState) -> Result<Option<CompiledFilter>, FilterError> {
+    if state.data.filter.is_empty() {
+        return Ok(None);
+    }
+    state
+        .data
+        .filter
+        .compile(active_kind(state), &state.data.filter_columns())
+        .map(Some)
+}
+
+fn load_data_page(state: &mut AppState, offset: u64) -> Vec<Command> {
+    let Some(table) = state.data.table.clone() else {
+        return vec![];
+    };
+    let filter = match compiled_data_filter(state) {
+        Ok(f) => f,
+        Err(e) => {
+            state.status.message = format!("Filter error: {}", e);
+            return vec![];
+        }
+    };
+    state.data.loading = true;
+    state.status.message = format!("Loading rows from {}…", table);
+    vec![state.sessions.command(DbCommand::LoadRows {
+        table,
+        order_by: state.data.pk_column.clone(),
+        filter,
+        offset,
+        limit: DATA_PAGE_SIZE,
+    })]
+}
+
+pub fn reduce_action(state: &mut AppState, action: Action) -> Vec<Command> {
+    // Ticks arrive under any modal.
+    if action == Action::Tick {
+        state.status.tick = state.status.tick.wrapping_add(1);
+        return vec![];
+    }
+
+    // --- Modal first: if add/edit connection modal is open, most actions operate on it ---
+    if state.screen == Screen::Connections && state.connections.adding.is_some() {
+        match action {
+            Action::CancelModal | Action::Back => {
+                state.connections.adding = None;
+                state.status.message = "Cancelled".to_string();
+                return vec![];
+            }
+
+            Action::NextField => {
+                if let Some(d) = state.connections.adding.as_mut() {
+                    d.step_field(true);
+                }
+                return vec![];
+            }
+
+            Action::PrevField => {
+                if let Some(d) = state.connections.adding.as_mut() {
+                    d.step_field(false);
+                }
+                return vec![];
+            }
+
+            Action::PageDown | Action::PageUp => {
+                if let Some(d) = state.connections.adding.as_mut() {
+                    d.turn_page(action == Action::PageDown);
+                }
+                return vec![];
+            }
+
+            // Selectors: ←/→ or Space cycles engines, SSL modes, environments
+            // and colours or flips the host key check and safety switches.
+            Action::Left | Action::Right | Action::InputChar(' ') => {
+                if let Some(d) = state.connections.adding.as_mut() {
+                    if d.field == NewConnectionDraft::KIND_FIELD {
+                        cycle_draft_kind(d, action != Action::Left);
+                    } else if d.field == NewConnectionDraft::SSH_CHECK_FIELD {
+                        d.ssh_skip_host_key_check = !d.ssh_skip_host_key_check;
+                    } else if d.field == NewConnectionDraft::SSL_MODE_FIELD {
+                        d.tls_mode = d.tls_mode.cycle(action != Action::Left);
+                    } else if d.field == NewConnectionDraft::ENVIRONMENT_FIELD {
+                        d.environment =
+                            cycle_option(&Environment::ALL, d.environment, action != Action::Left);
+                    } else if d.field == NewConnectionDraft::COLOR_FIELD {
+                        d.color = cycle_option(&LabelColor::ALL, d.color, action != Action::Left);
+                    } else if d.field == NewConnectionDraft::READ_ONLY_FIELD {
+                        d.read_only = !d.read_only;
+                    } else if d.field == NewConnectionDraft::PRODUCTION_FIELD {
+                        d.production = !d.production;
+                    } else if let (Action::InputChar(c), Some(f)) = (&action, draft_field_mut(d)) {
+                        f.push(*c);
+                    }
+                }
+                return vec![];
+            }
+
+            Action::Backspace => {
+                if let Some(f) = state.connections.adding.as_mut().and_then(draft_field_mut) {
+                    f.pop();
+                }
+                return vec![];
+            }
+
+            Action::InputChar(c) => {
+                if let Some(f) = state.connections.adding.as_mut().and_then(draft_field_mut)
+                    && !c.is_control()
+                {
+                    f.push(c);
+                }
+                return vec![];
+            }
+
+            Action::TestConnection => {
+                let Some(d) = state.connections.adding.as_ref() else {
+                    return vec![];
+                };
+                return match draft_item(d) {
+                    Ok(item) => {
+                        state.status.message = format!("Testing {}…", item.name);
+                        vec![state.sessions.command(DbCommand::TestConnection {
+                            target: Box::new(target(item)),
+                        })]
+                    }
+                    Err(message) => {
+                        state.status.message = message;
+                        vec![]
+                    }
+                };
+            }
+
+            Action::Confirm => {
+                // Enter = Save (add or edit)
+                let Some(d) = state.connections.adding.take() else {
+                    return vec![];
+                };
+
+                let item = match draft_item(&d) {
+                    Ok(item) => item,
+                    Err(message) => {
+                        state.status.message = message;
+                        state.connections.adding = Some(d);
+                        return vec![];
+                    }
+                };
+
+                // Store secret (password) in keyring; only persist non-secret fields to disk.
+                let secrets = crate::storage::secrets::ConnectionSecrets::default();
+                if let Err(e) = secrets.set_password(&item.id.to_string(), &item.password) {
+                    state.status.message = format!("Failed to store password in keyring: {}", e);
+                    return vec![];
+                }
+                if item.ssh.is_some()
+                    && let Err(e) =
+                        secrets.set_ssh_passphrase(&item.id.to_string(), &item.ssh_passphrase)
+                {
+                    state.status.message = format!("Failed to store passphrase in keyring: {}", e);
+                    return vec![];
+                }
+
+                // Upsert by id: if exists, replace; else append. The filter is
+                // dropped so the saved item is in view.
+                state.connections.filter.clear();
+                if let Some(idx) = state.connections.items.iter().position(|c| c.id == item.id) {
+                    state.connections.items[idx] = item;
+                    state.connections.select_item(idx);
+                    state.status.message = "Connection updated (saving…)".to_string();
+                } else {
+                    state.connections.items.push(item);
+                    let idx = state.connections.items.len() - 1;
+                    state.connections.select_item(idx);
                     state.status.message = "Connection added (saving…)".to_string();
                 }
 
@@ -138,281 +1640,1332 @@ pub fn reduce_action(state: &mut AppState, action: Action) -> Vec<Command> {
             // Ignore other actions while modal is open
             _ => return vec![],
         }
-    }
-
-    // --- Normal (non-modal) reducer ---
-    match action {
-        Action::GoConnections => {
-            state.screen = Screen::Connections;
+    }
+
+    // --- Modal: import prompt over Connections ---
+    if state.screen == Screen::Connections && state.connections.importing.is_some() {
+        return reduce_connection_import(state, action);
+    }
+
+    // --- Modal: destructive statement confirmation over the Runner ---
+    if state.screen == Screen::Runner && state.runner.destructive_confirm.is_some() {
+        return reduce_destructive_confirm(state, action);
+    }
+
+    // --- Modal: save-query prompt over the Runner ---
+    if state.screen == Screen::Runner && state.runner.save_prompt.is_some() {
+        return reduce_save_prompt(state, action);
+    }
+
+    // --- Modal: export dialog over the Data / Runner grids ---
+    if (state.screen == Screen::Data && state.data.export.is_some())
+        || (state.screen == Screen::Runner && state.runner.export.is_some())
+    {
+        return reduce_export(state, action);
+    }
+
+    // --- Modal: import dialog over Data ---
+    if state.screen == Screen::Data && state.data.import.is_some() {
+        return reduce_import(state, action);
+    }
+
+    // --- Modal: cell editor / review of staged changes over Data ---
+    if state.screen == Screen::Data && state.data.cell_editor.is_some() {
+        return reduce_cell_editor(state, action);
+    }
+    if state.screen == Screen::Data && state.data.review.is_some() {
+        return reduce_change_review(state, action);
+    }
+
+    // --- Modal: record view over the Data / Runner grids ---
+    if (state.screen == Screen::Data && state.data.detail.is_some())
+        || (state.screen == Screen::Runner && state.runner.detail.is_some())
+    {
+        return reduce_record_detail(state, action);
+    }
+
+    // --- Modal: tables referencing the open one, over Data ---
+    if state.screen == Screen::Data && state.data.reference_picker.is_some() {
+        return reduce_reference_picker(state, action);
+    }
+
+    // --- Modal: filter builder over Data ---
+    if state.screen == Screen::Data && state.data.filter_draft.is_some() {
+        return reduce_filter_builder(state, action);
+    }
+
+    // --- Modal: database picker over Schema ---
+    if state.screen == Screen::Schema && state.schema.picker.is_some() {
+        return reduce_database_picker(state, action);
+    }
+
+    // --- Modal: inline rename / filter inputs on Queries ---
+    if state.screen == Screen::Queries
+        && (state.queries.renaming.is_some() || state.queries.filtering)
+    {
+        return reduce_queries_input(state, action);
+    }
+
+    // --- Inline filter inputs on Connections / Schema ---
+    if (state.screen == Screen::Connections && state.connections.filtering)
+        || (state.screen == Screen::Schema && state.schema.filtering)
+    {
+        return reduce_filter_input(state, action);
+    }
+
+    // --- Normal (non-modal) reducer ---
+    match action {
+        Action::GoConnections => {
+            state.screen = Screen::Connections;
+            vec![]
+        }
+        Action::GoSchema => {
+            state.screen = Screen::Schema;
+            vec![]
+        }
+        Action::GoData => {
+            state.screen = Screen::Data;
+            vec![]
+        }
+        Action::GoQueries => {
+            state.screen = Screen::Queries;
+            vec![]
+        }
+        Action::GoRunner => {
+            state.screen = Screen::Runner;
+            state.runner.focus = RunnerFocus::Editor;
+            vec![]
+        }
+
+        Action::OpenAddConnection => {
+            state.connections.adding = Some(NewConnectionDraft::new());
+            state.status.message =
+                "Add connection: Tab/Shift+Tab move • Enter save • Esc cancel".to_string();
+            vec![]
+        }
+
+        Action::OpenConnectionImport => {
+            state.connections.importing = Some(String::new());
+            state.status.message = format!(
+                "Import: paste a DSN or give a file (blank for {})",
+                DEFAULT_SOURCE
+            );
+            vec![]
+        }
+
+        Action::EditSelectedConnection => {
+            let Some(item) = state.connections.selected_item() else {
+                state.status.message = "No connection selected".to_string();
+                return vec![];
+            };
+
+            state.connections.adding = Some(NewConnectionDraft::edit_from(item));
+            state.status.message =
+                "Edit connection: Tab/Shift+Tab move • Enter save • Esc cancel".to_string();
+            vec![]
+        }
+
+        Action::DeleteSelectedConnection => {
+            let Some(item) = state.connections.selected_item().cloned() else {
+                state.status.message = "No connection to delete".to_string();
+                return vec![];
+            };
+
+            state.connections.delete_confirm = Some(DeleteConnectionConfirm {
+                id: item.id,
+                name: item.name.clone(),
+            });
+
+            state.status.message = format!(
+                "Delete connection '{}'? (y/Enter confirm, n/Esc cancel)",
+                item.name
+            );
+
+            vec![]
+        }
+
+        Action::ConfirmDeleteConnection => {
+            let Some(confirm) = state.connections.delete_confirm.take() else {
+                return vec![];
+            };
+
+            // remove by id (safer than relying on selected index)
+            if let Some(idx) = state.connections.items.iter().position(|c| c.id == confirm.id) {
+                let removed = state.connections.items.remove(idx);
+
+                // keep selection in range
+                let rows = state.connections.rows().len();
+                if state.connections.selected >= rows {
+                    state.connections.selected = rows.saturating_sub(1);
+                }
+
+                // Best-effort: clear secrets from keyring (implemented as overwrite with empty string).
+                let secrets = crate::storage::secrets::ConnectionSecrets::default();
+                let id = removed.id.to_string();
+                let cleared = secrets
+                    .delete_password(&id)
+                    .and_then(|()| match removed.ssh {
+                        Some(_) => secrets.delete_ssh_passphrase(&id),
+                        None => Ok(()),
+                    });
+                if let Err(e) = cleared {
+                    state.status.message = format!(
+                        "Deleted connection '{}', but failed to clear keyring password: {}",
+                        removed.name, e
+                    );
+                } else {
+                    state.status.message = format!("Deleted connection '{}'", removed.name);
+                }
+
+                let profiles = build_profiles(&state.connections.items);
+                vec![Command::Storage(StorageCommand::SaveConnections {
+                    connections: profiles,
+                })]
+            } else {
+                state.status.message = "Connection already deleted".to_string();
+                vec![]
+            }
+        }
+
+        Action::CancelDeleteConnection => {
+            state.connections.delete_confirm = None;
+            state.status.message = "Delete cancelled".to_string();
+            vec![]
+        }
+
+
+
+        Action::Up => {
+            match state.screen {
+                Screen::Connections if state.connections.selected > 0 => {
+                    state.connections.selected -= 1;
+                }
+                Screen::Schema if state.schema.selected > 0 => {
+                    state.schema.selected -= 1;
+                    return load_selected_object(state);
+                }
+                Screen::Data => {
+                    state.data.selected_row = state.data.selected_row.saturating_sub(1);
+                }
+                Screen::Runner => {
+                    state.runner.selected_row = state.runner.selected_row.saturating_sub(1);
+                }
+                Screen::Queries if state.queries.selected > 0 => {
+                    state.queries.selected -= 1;
+                }
+                _ => {}
+            }
+            vec![]
+        }
+
+        Action::Down => {
+            match state.screen {
+                Screen::Connections
+                    if state.connections.selected + 1 < state.connections.rows().len() =>
+                {
+                    state.connections.selected += 1;
+                }
+                Screen::Schema if state.schema.selected + 1 < state.schema.rows().len() => {
+                    state.schema.selected += 1;
+                    return load_selected_object(state);
+                }
+                Screen::Data if state.data.selected_row + 1 < state.data.rows.len() => {
+                    state.data.selected_row += 1;
+                }
+                Screen::Runner if state.runner.selected_row + 1 < runner_row_count(state) => {
+                    state.runner.selected_row += 1;
+                    return fetch_ahead(state);
+                }
+                Screen::Queries if state.queries.selected + 1 < state.queries.visible().len() => {
+                    state.queries.selected += 1;
+                }
+                _ => {}
+            }
+            vec![]
+        }
+
+        Action::Left => {
+            match state.screen {
+                Screen::Schema => {
+                    state.schema.tab = state.schema.tab.cycle(false);
+                    state.schema.detail_scroll = 0;
+                }
+                Screen::Data => step_data_column(&mut state.data, false),
+                Screen::Runner => {
+                    state.runner.col_offset = state.runner.col_offset.saturating_sub(1)
+                }
+                _ => {}
+            }
+            vec![]
+        }
+
+        Action::Right => {
+            match state.screen {
+                Screen::Schema => {
+                    state.schema.tab = state.schema.tab.cycle(true);
+                    state.schema.detail_scroll = 0;
+                }
+                Screen::Data => step_data_column(&mut state.data, true),
+                Screen::Runner if state.runner.col_offset + 1 < runner_column_count(state) => {
+                    state.runner.col_offset += 1;
+                }
+                _ => {}
+            }
+            vec![]
+        }
+
+        Action::PageUp if state.screen == Screen::Schema => {
+            state.schema.detail_scroll = state.schema.detail_scroll.saturating_sub(10);
+            vec![]
+        }
+
+        Action::PageDown if state.screen == Screen::Schema => {
+            state.schema.detail_scroll = state.schema.detail_scroll.saturating_add(10);
+            vec![]
+        }
+
+        Action::PageUp if state.screen == Screen::Runner => {
+            state.runner.selected_row = state.runner.selected_row.saturating_sub(RESULT_PAGE);
+            vec![]
+        }
+
+        Action::PageDown if state.screen == Screen::Runner => {
+            let last = runner_row_count(state).saturating_sub(1);
+            state.runner.selected_row = (state.runner.selected_row + RESULT_PAGE).min(last);
+            fetch_ahead(state)
+        }
+
+        Action::PageUp => {
+            if state.screen != Screen::Data || state.data.loading || state.data.offset == 0 {
+                return vec![];
+            }
+            if has_pending_changes(state) {
+                return vec![];
+            }
+            let offset = state.data.offset.saturating_sub(DATA_PAGE_SIZE);
+            load_data_page(state, offset)
+        }
+
+        Action::PageDown => {
+            // A short page means we're already at the end.
+            if state.screen != Screen::Data
+                || state.data.loading
+                || (state.data.rows.len() as u64) < DATA_PAGE_SIZE
+                || has_pending_changes(state)
+            {
+                return vec![];
+            }
+            let offset = state.data.offset + DATA_PAGE_SIZE;
+            load_data_page(state, offset)
+        }
+
+        Action::OpenTableData => {
+            let Some(table) = state.schema.selected_relation().map(str::to_string) else {
+                state.status.message = "No table selected".to_string();
+                return vec![];
+            };
+            if has_pending_changes(state) {
+                return vec![];
+            }
+
+            // Freeze + order by the primary key when it's a single column we already know about.
+            let pk_column = if state.schema.columns_table.as_deref() == Some(table.as_str()) {
+                let mut pks = state
+                    .schema
+                    .columns
+                    .iter()
+                    .filter(|c| c.column_key.as_deref() == Some("PRI"));
+                match (pks.next(), pks.next()) {
+                    (Some(c), None) => Some(c.name.clone()),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
+            let column_info = if state.schema.columns_table.as_deref() == Some(table.as_str()) {
+                state.schema.columns.clone()
+            } else {
+                vec![]
+            };
+            let details = state.schema.details.as_ref().filter(|d| d.table == table);
+            let describe = (column_info.is_empty() || details.is_none()).then(|| {
+                state.sessions.command(DbCommand::DescribeDataTable {
+                    table: table.clone(),
+                })
+            });
+            state.data = DataState {
+                table: Some(table),
+                pk_column,
+                column_info,
+                key_columns: details.map(|d| d.key_columns()).unwrap_or_default(),
+                foreign_keys: details.map(|d| d.foreign_keys.clone()).unwrap_or_default(),
+                referenced_by: details.map(|d| d.referenced_by.clone()).unwrap_or_default(),
+                ..Default::default()
+            };
+            state.screen = Screen::Data;
+            let mut cmds = load_data_page(state, 0);
+            cmds.extend(describe);
+            cmds
+        }
+
+        Action::FollowReference => follow_reference(state),
+
+        Action::OpenReferencingTables => {
+            let data = &state.data;
+            if data.referenced_by.is_empty() {
+                state.status.message = match data.table.as_deref() {
+                    Some(t) => format!("No tables reference {}", t),
+                    None => "No table open".to_string(),
+                };
+                return vec![];
+            }
+            if data.rows.is_empty() {
+                state.status.message = "No row selected".to_string();
+                return vec![];
+            }
+            state.data.reference_picker = Some(0);
+            state.status.message = "Rows referencing this one: Enter open • Esc cancel".to_string();
+            vec![]
+        }
+
+        Action::OpenFilterBuilder => {
+            if state.data.table.is_none() {
+                state.status.message = "No table open".to_string();
+                return vec![];
+            }
+            if has_pending_changes(state) {
+                return vec![];
+            }
+            let mut draft = FilterDraft {
+                filter: state.data.filter.clone(),
+                ..Default::default()
+            };
+            if draft.row_count() == 0 {
+                draft.filter.conditions.push(Condition {
+                    column: state
+                        .data
+                        .filter_columns()
+                        .first()
+                        .map(|c| c.name.clone())
+                        .unwrap_or_default(),
+                    ..Default::default()
+                });
+                draft.field = 1;
+            }
+            state.data.filter_draft = Some(draft);
+            state.status.message =
+                "Filter: Tab field • ←/→ change • Ctrl+N condition • Ctrl+O sort • Ctrl+D remove • Enter apply"
+                    .to_string();
+            vec![]
+        }
+
+        Action::ClearRowFilter => {
+            if state.data.filter.is_empty() || has_pending_changes(state) {
+                return vec![];
+            }
+            state.data.filter = Default::default();
+            load_data_page(state, 0)
+        }
+
+        Action::OpenExport => {
+            let stem = match state.screen {
+                Screen::Data if !state.data.columns.is_empty() => state.data.table.clone(),
+                Screen::Runner if state.runner.result.is_some() => Some("result".to_string()),
+                _ => None,
+            };
+            let Some(stem) = stem else {
+                state.status.message = "Nothing to export".to_string();
+                return vec![];
+            };
+            let mut draft = ExportDraft::new(&stem);
+            if state.screen == Screen::Data {
+                draft.options.table = stem;
+            }
+            let draft = Some(draft);
+            if state.screen == Screen::Data {
+                state.data.export = draft;
+            } else {
+                state.runner.export = draft;
+            }
+            state.status.message =
+                "Export: Tab field • ←/→ change • Enter export • Esc cancel".to_string();
+            vec![]
+        }
+
+        Action::ToggleRowMark => {
+            if state.screen != Screen::Data || state.data.rows.is_empty() {
+                return vec![];
+            }
+            let row = state.data.selected_row.min(state.data.rows.len() - 1);
+            if !state.data.marked.remove(&row) {
+                state.data.marked.insert(row);
+            }
+            state.status.message = match state.data.marked.len() {
+                0 => "No rows marked".to_string(),
+                n => format!("{} rows marked • e: export them • Space: unmark", n),
+            };
+            vec![]
+        }
+
+        Action::OpenRecordDetail => {
+            let Some((_, rows)) = detail_record(state) else {
+                state.status.message = "No row to show".to_string();
+                return vec![];
+            };
+            let detail = Some(RecordDetail::default());
+            let selected = if state.screen == Screen::Data {
+                state.data.detail = detail;
+                state.data.selected_row
+            } else {
+                state.runner.detail = detail;
+                state.runner.selected_row
+            };
+            state.status.message = format!(
+                "Row {} of {} • ↑/↓ scroll • ←/→ previous/next row • Esc close",
+                selected + 1,
+                rows
+            );
+            vec![]
+        }
+
+        Action::OpenImport => {
+            if state.screen != Screen::Data || state.data.table.is_none() {
+                state.status.message = "Open a table to import into".to_string();
+                return vec![];
+            }
+            if has_pending_changes(state) {
+                return vec![];
+            }
+            state.data.import = Some(ImportDraft::default());
+            state.status.message =
+                "Import: Tab field • ←/→ change • Enter read file • Esc cancel".to_string();
+            vec![]
+        }
+
+        Action::InsertRow => {
+            if state.screen != Screen::Data || state.data.columns.is_empty() {
+                return vec![];
+            }
+            if !require_edit_key(state) {
+                return vec![];
+            }
+            let data = &mut state.data;
+            data.changes.new_from.get_or_insert(data.rows.len());
+            data.rows.push(vec![CellValue::Null; data.columns.len()]);
+            data.selected_row = data.rows.len() - 1;
+            state.status.message =
+                "New row: Enter edits a cell; columns left empty get their defaults".to_string();
+            vec![]
+        }
+
+        Action::DeleteRow => {
+            if state.screen != Screen::Data || state.data.rows.is_empty() {
+                return vec![];
+            }
+            if !require_edit_key(state) {
+                return vec![];
+            }
+            let row = state.data.selected_row.min(state.data.rows.len() - 1);
+            state.data.changes.toggle_delete(row);
+            state.status.message = pending_summary(&state.data);
+            vec![]
+        }
+
+        Action::ReviewChanges => {
+            let data = &state.data;
+            let Some(table) = data.table.as_deref().filter(|_| data.changes.count() > 0) else {
+                state.status.message = "No pending changes".to_string();
+                return vec![];
+            };
+            let statements = data.changes.statements(
+                active_kind(state),
+                table,
+                &data.edit_columns(),
+                &data.edit_key(),
+                &data.rows,
+            );
+            match statements {
+                Ok(statements) => {
+                    state.data.review = Some(ChangeReview {
+                        statements,
+                        ..Default::default()
+                    });
+                    state.status.message =
+                        "Review: Enter save in one transaction • Esc back".to_string();
+                }
+                Err(e) => state.status.message = format!("Edit error: {}", e),
+            }
+            vec![]
+        }
+
+        Action::DiscardChanges => {
+            let data = &mut state.data;
+            if data.changes == Default::default() {
+                state.status.message = "No pending changes".to_string();
+                return vec![];
+            }
+            let n = data.changes.count();
+            if let Some(first) = data.changes.new_from {
+                data.rows.truncate(first);
+            }
+            data.changes = Default::default();
+            data.selected_row = data.selected_row.min(data.rows.len().saturating_sub(1));
+            state.status.message = format!("Discarded {} changes", n);
+            vec![]
+        }
+
+        // Builder-only; ignored when the builder is closed.
+        Action::AddFilterCondition | Action::AddSortKey | Action::DeleteFilterRow => vec![],
+
+        Action::OpenDatabasePicker => {
+            if state.connections.active.is_none() {
+                state.status.message = "Not connected".to_string();
+                return vec![];
+            }
+            state.schema.picker = Some(DatabasePicker {
+                loading: true,
+                ..Default::default()
+            });
+            state.status.message = "Loading databases…".to_string();
+            vec![state.sessions.command(DbCommand::LoadSchemas)]
+        }
+
+        Action::Confirm => match state.screen {
+            Screen::Connections => {
+                // Enter on a group header folds/unfolds it.
+                if let Some(ConnectionRow::Group { name, .. }) = state.connections.selected_row() {
+                    let name = name.to_string();
+                    let collapsed = &mut state.connections.collapsed;
+                    if let Some(i) = collapsed.iter().position(|g| *g == name) {
+                        collapsed.remove(i);
+                    } else {
+                        collapsed.push(name);
+                    }
+                    return vec![];
+                }
+                let Some(item) = state.connections.selected_item().cloned() else {
+                    return vec![];
+                };
+                state.status.message = format!("Connecting to {}…", item.name);
+                vec![connect(state, item)]
+            }
+            Screen::Queries => {
+                let Some(q) = state.queries.selected_item().cloned() else {
+                    return vec![];
+                };
+                state.runner.editor.set_text(&q.sql);
+                state.runner.loaded_query = Some(q.id);
+                state.runner.focus = RunnerFocus::Editor;
+                state.screen = Screen::Runner;
+                state.status.message = format!("Opened '{}'", q.name);
+                vec![]
+            }
+            Screen::Schema => {
+                // Enter on a group header folds/unfolds it.
+                if let Some(SchemaRow::Group { kind, .. }) = state.schema.selected_row() {
+                    if let Some(i) = state.schema.collapsed.iter().position(|k| *k == kind) {
+                        state.schema.collapsed.remove(i);
+                    } else {
+                        state.schema.collapsed.push(kind);
+                    }
+                }
+                vec![]
+            }
+            Screen::Data => open_cell_editor(state),
+            _ => vec![],
+        },
+
+        Action::Back if state.screen == Screen::Data && !state.data.trail.is_empty() => {
+            if has_pending_changes(state) {
+                return vec![];
+            }
+            let mut prev = state.data.trail.pop().unwrap_or_default();
+            prev.trail = std::mem::take(&mut state.data.trail);
+            state.data = prev;
+            state.status.message = format!("Back to {}", state.data.breadcrumb());
+            vec![]
+        }
+
+        Action::Back => {
+            match state.screen {
+                Screen::Connections => { /* no-op */ }
+                _ => {
+                    if !state.status.connection_label.is_empty() {
+                        state.screen = Screen::Schema;
+                    } else {
+                        state.screen = Screen::Connections;
+                    }
+                }
+            }
+            vec![]
+        }
+
+        Action::Disconnect => vec![state.sessions.command(DbCommand::Disconnect)],
+        Action::ConnectInNewTab => {
+            if state.screen != Screen::Connections {
+                return vec![];
+            }
+            let Some(item) = state.connections.selected_item().cloned() else {
+                return vec![];
+            };
+            // An idle tab is reused rather than left empty behind the new one.
+            if state.connections.active.is_some() {
+                state.open_session();
+            }
+            state.status.message = format!("Connecting to {} in a new tab…", item.name);
+            vec![connect(state, item)]
+        }
+        Action::NextSession | Action::PrevSession => {
+            let n = state.sessions.tabs.len();
+            if n < 2 {
+                state.status.message =
+                    "Only one session is open (t on Connections opens another)".to_string();
+                return vec![];
+            }
+            let i = state.sessions.index(state.sessions.active).unwrap_or(0);
+            let next = if action == Action::NextSession {
+                (i + 1) % n
+            } else {
+                (i + n - 1) % n
+            };
+            state.switch_session(state.sessions.tabs[next].id);
+            if state.connections.active.is_none() {
+                state.screen = Screen::Connections;
+            }
+            state.status.message =
+                format!("Session {} of {}: {}", next + 1, n, session_name(state));
+            vec![]
+        }
+        Action::CloseSession => {
+            // A live session closes its tab once the worker has disconnected.
+            if state.connections.active.is_some() {
+                return vec![state.sessions.command(DbCommand::Disconnect)];
+            }
+            if state.sessions.tabs.len() < 2 {
+                return vec![];
+            }
+            state.close_session(state.sessions.active);
+            state.status.message = format!("Closed tab; now on {}", session_name(state));
+            vec![]
+        }
+        Action::Quit => vec![],
+        Action::Tick => vec![], // handled before the modals
+
+        // --- Runner editor ---
+        Action::InputChar(c) if state.screen == Screen::Runner => {
+            if !c.is_control() {
+                state.runner.editor.insert_char(c);
+            }
+            vec![]
+        }
+        Action::Backspace if state.screen == Screen::Runner => {
+            state.runner.editor.backspace();
             vec![]
         }
-        Action::GoSchema => {
-            state.screen = Screen::Schema;
+        Action::Newline => {
+            state.runner.editor.newline();
             vec![]
         }
-        Action::GoData => {
-            state.screen = Screen::Data;
+        Action::Delete => {
+            state.runner.editor.delete();
             vec![]
         }
-        Action::GoQueries => {
-            state.screen = Screen::Queries;
+        Action::MoveCursor { mv, select } => {
+            state.runner.editor.move_cursor(mv, select);
             vec![]
         }
-        Action::GoRunner => {
-            state.screen = Screen::Runner;
+        Action::SelectAll => {
+            state.runner.editor.select_all();
             vec![]
         }
-
-        Action::OpenAddConnection => {
-            state.connections.adding = Some(NewConnectionDraft::new());
-            state.status.message =
-                "Add connection: Tab/Shift+Tab move • Enter save • Esc cancel".to_string();
+        Action::Undo => {
+            if !state.runner.editor.undo() {
+                state.status.message = "Nothing to undo".to_string();
+            }
             vec![]
         }
-
-        Action::EditSelectedConnection => {
-            let Some(item) = state
-                .connections
-                .items
-                .get(state.connections.selected)
-            else {
-                state.status.message = "No connection selected".to_string();
-                return vec![];
-            };
-
-            state.connections.adding = Some(NewConnectionDraft::edit_from(item));
-            state.status.message =
-                "Edit connection: Tab/Shift+Tab move • Enter save • Esc cancel".to_string();
+        Action::Redo => {
+            if !state.runner.editor.redo() {
+                state.status.message = "Nothing to redo".to_string();
+            }
+            vec![]
+        }
+        Action::FocusEditor => {
+            state.runner.focus = RunnerFocus::Editor;
+            vec![]
+        }
+        Action::FocusResults => {
+            state.runner.focus = RunnerFocus::Results;
+            state.status.message = "i: edit • Ctrl+R/F5: run • arrows: scroll results".to_string();
             vec![]
         }
 
-        Action::DeleteSelectedConnection => {
-            if state.connections.items.is_empty() {
-                state.status.message = "No connection to delete".to_string();
+        Action::RunQuery => {
+            if state.runner.running {
+                state.status.message = "A query is already running".to_string();
                 return vec![];
             }
 
-            if state.connections.selected >= state.connections.items.len() {
-                state.connections.selected = state.connections.items.len().saturating_sub(1);
-            }
+            // Selection wins; otherwise the statement under the cursor.
+            let kind = active_kind(state);
+            let editor = &state.runner.editor;
+            let sql = editor
+                .selected_text()
+                .or_else(|| statement_at(&editor.text(), editor.cursor_offset(), kind))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default();
 
-            let Some(item) = state.connections.items.get(state.connections.selected).cloned() else {
-                state.status.message = "No connection to delete".to_string();
+            if sql.is_empty() {
+                state.status.message = "Nothing to run".to_string();
                 return vec![];
-            };
-
-            state.connections.delete_confirm = Some(DeleteConnectionConfirm {
-                id: item.id,
-                name: item.name.clone(),
-            });
-
-            state.status.message = format!(
-                "Delete connection '{}'? (y/Enter confirm, n/Esc cancel)",
-                item.name
-            );
+            }
 
-            vec![]
+            // On a production connection, destructive statements wait for
+            // the connection's name to be typed.
+            if let Some(item) = state.connections.active_item().filter(|c| c.production)
+                && classify(&sql, kind) == StatementClass::Destructive
+            {
+                let name = item.name.clone();
+                state.status.message = format!(
+                    "Destructive statement on a production connection: type '{}' to run it",
+                    name
+                );
+                state.runner.destructive_confirm = Some(DestructiveConfirm {
+                    sql,
+                    name,
+                    typed: String::new(),
+                });
+                return vec![];
+            }
+            run_statement(state, sql)
         }
 
-        Action::ConfirmDeleteConnection => {
-            let Some(confirm) = state.connections.delete_confirm.take() else {
+        Action::CancelQuery => {
+            let Some(task) = state.runner.task.as_mut() else {
+                // Otherwise stop an export or import still running.
+                if let Some(id) = state.runner.transfer {
+                    state.status.message = "Cancelling…".to_string();
+                    return vec![state.sessions.command(DbCommand::Cancel { id })];
+                }
+                state.status.message = "No query running".to_string();
                 return vec![];
             };
-
-            // remove by id (safer than relying on selected index)
-            if let Some(idx) = state.connections.items.iter().position(|c| c.id == confirm.id) {
-                let removed = state.connections.items.remove(idx);
-
-                // keep selection in range
-                if state.connections.selected >= state.connections.items.len() {
-                    state.connections.selected = state.connections.items.len().saturating_sub(1);
-                }
-
-                // Best-effort: clear secret from keyring (implemented as overwrite with empty string).
-                if let Err(e) = crate::storage::secrets::ConnectionSecrets::default()
-                    .delete_password(&removed.id.to_string())
-                {
-                    state.status.message = format!(
-                        "Deleted connection '{}', but failed to clear keyring password: {}",
-                        removed.name, e
-                    );
-                } else {
-                    state.status.message = format!("Deleted connection '{}'", removed.name);
-                }
-
-                let profiles = build_profiles(&state.connections.items);
-                vec![Command::Storage(StorageCommand::SaveConnections {
-                    connections: profiles,
-                })]
-            } else {
-                state.status.message = "Connection already deleted".to_string();
-                vec![]
+            if task.cancelling {
+                return vec![];
             }
+            task.cancelling = true;
+            state.status.message = if state.runner.running {
+                "Cancelling…".to_string()
+            } else {
+                "Stopping the fetch…".to_string()
+            };
+            vec![state.sessions.command(DbCommand::Cancel { id: task.id })]
         }
 
-        Action::CancelDeleteConnection => {
-            state.connections.delete_confirm = None;
-            state.status.message = "Delete cancelled".to_string();
+        // --- Query library ---
+        Action::SaveQuery => {
+            let sql = state.runner.editor.text();
+            if sql.trim().is_empty() {
+                state.status.message = "Nothing to save".to_string();
+                return vec![];
+            }
+
+            // Prefill from the loaded query so saving again overwrites it.
+            let existing = state
+                .runner
+                .loaded_query
+                .as_ref()
+                .and_then(|id| state.queries.items.iter().find(|q| &q.id == id));
+            state.runner.save_prompt = Some(match existing {
+                Some(q) => SaveQueryDraft {
+                    id: Some(q.id.clone()),
+                    name: q.name.clone(),
+                    tags: q.tags.join(", "),
+                    field: 0,
+                },
+                None => SaveQueryDraft::default(),
+            });
+            state.status.message =
+                "Save query: Tab switch field • Enter save • Esc cancel".to_string();
             vec![]
         }
 
-
-
-        Action::Up => {
+        Action::StartFilter => {
             match state.screen {
-                Screen::Connections => {
-                    if state.connections.selected > 0 {
-                        state.connections.selected -= 1;
-                    }
-                }
-                Screen::Schema => {
-                    let prev = state.schema.selected_table;
-                    if prev > 0 {
-                        state.schema.selected_table -= 1;
-                    }
-
-                    if state.schema.selected_table != prev {
-                        if let Some(table) = state
-                            .schema
-                            .tables
-                            .get(state.schema.selected_table)
-                            .cloned()
-                        {
-                            state.status.message = format!("Loading columns for {}…", table);
-                            return vec![Command::Db(DbCommand::LoadColumns { table })];
-                        }
-                    }
-                }
-                _ => {}
+                Screen::Connections => state.connections.filtering = true,
+                Screen::Schema => state.schema.filtering = true,
+                Screen::Queries => state.queries.filtering = true,
+                _ => return vec![],
             }
+            state.status.message = "Filter: type to narrow • Enter keep • Esc clear".to_string();
             vec![]
         }
 
-        Action::Down => {
-            match state.screen {
-                Screen::Connections => {
-                    if state.connections.selected + 1 < state.connections.items.len() {
-                        state.connections.selected += 1;
-                    }
-                }
-                Screen::Schema => {
-                    let prev = state.schema.selected_table;
-                    if state.schema.selected_table + 1 < state.schema.tables.len() {
-                        state.schema.selected_table += 1;
-                    }
+        Action::RenameSelectedQuery => {
+            let Some(q) = state.queries.selected_item() else {
+                state.status.message = "No query selected".to_string();
+                return vec![];
+            };
+            state.queries.renaming = Some(q.name.clone());
+            state.status.message = "Rename: Enter save • Esc cancel".to_string();
+            vec![]
+        }
 
-                    if state.schema.selected_table != prev {
-                        if let Some(table) = state
-                            .schema
-                            .tables
-                            .get(state.schema.selected_table)
-                            .cloned()
-                        {
-                            state.status.message = format!("Loading columns for {}…", table);
-                            return vec![Command::Db(DbCommand::LoadColumns { table })];
-                        }
-                    }
-                }
-                _ => {}
-            }
+        Action::DeleteSelectedQuery => {
+            let Some(q) = state.queries.selected_item().cloned() else {
+                state.status.message = "No query to delete".to_string();
+                return vec![];
+            };
+            state.queries.delete_confirm = Some(DeleteQueryConfirm {
+                id: q.id,
+                name: q.name.clone(),
+            });
+            state.status.message =
+                format!("Delete query '{}'? (y/Enter confirm, n/Esc cancel)", q.name);
             vec![]
         }
 
-        Action::Confirm => match state.screen {
-            Screen::Connections => {
-                let Some(item) = state
-                    .connections
-                    .items
-                    .get(state.connections.selected)
-                    .cloned()
-                else {
-                    return vec![];
-                };
-                state.status.message = format!("Connecting to {}…", item.name);
-                vec![Command::Db(DbCommand::Connect {
-                    name: item.name,
-                    host: item.host,
-                    port: item.port,
-                    user: item.user,
-                    password: item.password,
-                    db: item.db,
-                })]
-            }
-            Screen::Schema => vec![],
-            _ => vec![],
-        },
+        Action::ConfirmDeleteQuery => {
+            let Some(confirm) = state.queries.delete_confirm.take() else {
+                return vec![];
+            };
+            let Some(idx) = state.queries.items.iter().position(|q| q.id == confirm.id) else {
+                state.status.message = "Query already deleted".to_string();
+                return vec![];
+            };
 
-        Action::Back => {
-            match state.screen {
-                Screen::Connections => { /* no-op */ }
-                _ => {
-                    if !state.status.connection_label.is_empty() {
-                        state.screen = Screen::Schema;
-                    } else {
-                        state.screen = Screen::Connections;
-                    }
-                }
+            state.queries.items.remove(idx);
+            let visible = state.queries.visible().len();
+            if state.queries.selected >= visible {
+                state.queries.selected = visible.saturating_sub(1);
             }
-            vec![]
+            if state.runner.loaded_query.as_deref() == Some(confirm.id.as_str()) {
+                state.runner.loaded_query = None;
+            }
+            state.status.message = format!("Deleted query '{}'", confirm.name);
+            vec![save_queries(state)]
         }
 
-        Action::Disconnect => vec![Command::Db(DbCommand::Disconnect)],
-        Action::Quit => vec![],
+        Action::CancelDeleteQuery => {
+            state.queries.delete_confirm = None;
+            state.status.message = "Delete cancelled".to_string();
+            vec![]
+        }
 
         // These are modal-only, ignore when not in modal
         Action::CancelModal
+        | Action::TestConnection
         | Action::NextField
         | Action::PrevField
         | Action::Backspace
         | Action::InputChar(_) => vec![],
 
-        Action::Left | Action::Right | Action::ConnectSelected => vec![],
+        Action::ConnectSelected => vec![],
     }
 }
 
+/// Reduces an event for a session in a background tab: its state is swapped
+/// in for the duration, while the screen stays put and any message is
+/// prefixed with the tab's name so it isn't mistaken for the visible one.
+fn reduce_background(state: &mut AppState, session: SessionId, evt: DbEvent) -> Vec<Command> {
+    let visible = state.sessions.active;
+    if !state.switch_session(session) {
+        return vec![]; // tab already closed
+    }
+    let name = session_name(state);
+    let screen = state.screen;
+    let message = state.status.message.clone();
+    let cmds = reduce_event(state, Event::Db(session, evt));
+    if state.status.message != message {
+        state.status.message = format!("[{}] {}", name, state.status.message);
+    }
+    state.screen = screen;
+    state.switch_session(visible);
+    cmds
+}
+
 pub fn reduce_event(state: &mut AppState, event: Event) -> Vec<Command> {
     match event {
-        Event::Db(db) => match db {
-            DbEvent::Connected { display } => {
+        Event::Db(session, evt) if session != state.sessions.active => {
+            reduce_background(state, session, evt)
+        }
+        Event::Db(_, evt) => match evt {
+            DbEvent::Connected { id, display } => {
+                state.connections.active = Some(id);
                 state.status.connection_label = display;
                 state.status.message = "Connected".to_string();
                 state.screen = Screen::Schema;
-                vec![Command::Db(DbCommand::LoadTables)]
+                vec![state.sessions.command(DbCommand::LoadObjects)]
             }
             DbEvent::Disconnected => {
+                state.connections.active = None;
                 state.status.connection_label.clear();
                 state.status.message = "Disconnected".to_string();
-                state.schema.tables.clear();
-                state.schema.selected_table = 0;
-                state.schema.columns.clear();
-                state.schema.columns_table = None;
+                state.schema = SchemaState::default();
+                state.data = DataState::default();
+                state.runner.running = false;
+                state.runner.task = None;
                 state.screen = Screen::Connections;
+                if state.sessions.tabs.len() > 1 {
+                    state.close_session(state.sessions.active);
+                    if state.connections.active.is_some() {
+                        state.screen = Screen::Schema;
+                    }
+                }
                 vec![]
             }
-            DbEvent::TablesLoaded { tables } => {
-                state.schema.tables = tables;
-                state.schema.selected_table = 0;
-                state.schema.columns.clear();
-                state.schema.columns_table = None;
+            DbEvent::SchemasLoaded { schemas, current } => {
+                // Ignore a late answer once the picker has been dismissed.
+                let Some(p) = state.schema.picker.as_mut() else {
+                    return vec![];
+                };
+                p.selected = current
+                    .as_ref()
+                    .and_then(|c| schemas.iter().position(|s| s == c))
+                    .unwrap_or(0);
+                p.items = schemas;
+                p.current = current;
+                p.loading = false;
+                state.status.message = "↑/↓ choose • Enter switch • Esc cancel".to_string();
+                vec![]
+            }
+            DbEvent::SchemaChanged { schema, display } => {
+                state.status.connection_label = display;
+                state.status.message = format!("Using {}", schema);
+                state.schema = SchemaState {
+                    tab: state.schema.tab,
+                    ..Default::default()
+                };
+                state.data = DataState::default();
+                vec![state.sessions.command(DbCommand::LoadObjects)]
+            }
+            DbEvent::ObjectsLoaded { mut objects } => {
+                objects.sort();
+                state.schema = SchemaState {
+                    objects,
+                    tab: state.schema.tab,
+                    picker: state.schema.picker.take(),
+                    ..Default::default()
+                };
                 state.status.message = "Tables loaded".to_string();
 
-                if let Some(table) = state.schema.tables.get(0).cloned() {
-                    state.status.message = format!("Loading columns for {}…", table);
-                    return vec![Command::Db(DbCommand::LoadColumns { table })];
+                // Land on the first object rather than its group header.
+                if state.schema.rows().len() > 1 {
+                    state.schema.selected = 1;
                 }
-
+                load_selected_object(state)
+            }
+            DbEvent::DefinitionLoaded { object, definition } => {
+                state.schema.definition = Some((object, definition));
+                state.status.message = "Definition loaded".to_string();
                 vec![]
             }
-            DbEvent::ColumnsLoaded { table, columns } => {
-                state.schema.columns_table = Some(table);
-                state.schema.columns = columns;
+            DbEvent::TableDescribed { details } => {
+                state.schema.columns_table = Some(details.table.clone());
+                state.schema.columns = details.columns.iter().map(|c| c.info()).collect();
+                state.schema.details = Some(details);
+                state.schema.detail_scroll = 0;
                 state.status.message = "Columns loaded".to_string();
                 vec![]
             }
+            DbEvent::DataTableDescribed { details } => {
+                let data = &mut state.data;
+                if data.table.as_deref() != Some(details.table.as_str()) {
+                    return vec![];
+                }
+                if data.column_info.is_empty() {
+                    data.column_info = details.columns.iter().map(|c| c.info()).collect();
+                }
+                // A table opened through a reference loads its rows now.
+                let load = data.columns.is_empty() && !data.loading;
+                let mut pks = details
+                    .columns
+                    .iter()
+                    .filter(|c| c.column_key.as_deref() == Some("PRI"));
+                if load && let (Some(c), None) = (pks.next(), pks.next()) {
+                    data.pk_column = Some(c.name.clone());
+                }
+                data.key_columns = details.key_columns();
+                data.foreign_keys = details.foreign_keys;
+                data.referenced_by = details.referenced_by;
+                if load {
+                    return load_data_page(state, 0);
+                }
+                vec![]
+            }
+            DbEvent::RowsLoaded {
+                table,
+                columns,
+                rows,
+                offset,
+                total_estimate,
+            } => {
+                // Ignore pages for a table we've since navigated away from.
+                if state.data.table.as_deref() != Some(table.as_str()) {
+                    return vec![];
+                }
+                state.data.loading = false;
+                state.data.columns = columns;
+                state.data.rows = rows;
+                state.data.offset = offset;
+                state.data.total_estimate = total_estimate;
+                state.data.selected_row = 0;
+                if state.data.col_offset >= state.data.scrollable_count() {
+                    state.data.col_offset = 0;
+                }
+                if state.data.selected_col >= state.data.columns.len() {
+                    state.data.selected_col = 0;
+                }
+                // Edits and marks belong to the page they were made on.
+                state.data.changes = Default::default();
+                state.data.marked.clear();
+                state.data.cell_editor = None;
+                state.data.review = None;
+                state.status.message = format!("Loaded {}", table);
+                vec![]
+            }
+            DbEvent::RowsFailed { table, message } => {
+                if state.data.table.as_deref() == Some(table.as_str()) {
+                    state.data.loading = false;
+                }
+                state.status.message = format!("DB error: {}", message);
+                vec![]
+            }
+            DbEvent::QueryRows { id, columns, rows } => {
+                if !is_current_query(state, id) {
+                    return vec![];
+                }
+                let runner = &mut state.runner;
+                if runner.running {
+                    // The first rows replace the last result.
+                    let task = runner.task.as_ref();
+                    runner.running = false;
+                    runner.result = Some(QueryResult {
+                        sql: task.map(|t| t.sql.clone()).unwrap_or_default(),
+                        columns,
+                        rows: Default::default(),
+                        rows_affected: 0,
+                        elapsed: task.map(|t| t.started.elapsed()).unwrap_or_default(),
+                    });
+                    runner.error = None;
+                    runner.selected_row = 0;
+                    runner.col_offset = 0;
+                    runner.detail = None;
+                }
+                let Some(result) = runner.result.as_mut() else {
+                    return vec![];
+                };
+                let dropped = result.rows.push(rows);
+                runner.selected_row = runner.selected_row.saturating_sub(dropped);
+                if !runner.task.as_ref().is_some_and(|t| t.cancelling) {
+                    state.status.message = match result.rows.dropped_label() {
+                        None => format!("{} rows fetched… (Ctrl+X: stop)", result.rows.fetched()),
+                        Some(dropped) => format!(
+                            "{} rows fetched, {} to save memory… (Ctrl+X: stop)",
+                            result.rows.fetched(),
+                            dropped
+                        ),
+                    };
+                }
+                vec![]
+            }
+            DbEvent::QueryFinished { id, result } => {
+                if !is_current_query(state, id) {
+                    return vec![];
+                }
+                let streamed = !state.runner.running;
+                state.runner.running = false;
+                state.runner.task = None;
+                state.runner.error = None;
+                match state.runner.result.as_mut() {
+                    Some(shown) if streamed => {
+                        shown.rows_affected = result.rows_affected;
+                        shown.elapsed = result.elapsed;
+                        state.status.message = format!(
+                            "{} row(s) in {} ms",
+                            shown.rows.fetched(),
+                            result.elapsed.as_millis()
+                        );
+                        if let Some(dropped) = shown.rows.dropped_label() {
+                            state.status.message += &format!("; {} to save memory", dropped);
+                        }
+                    }
+                    _ => {
+                        state.status.message = if result.columns.is_empty() {
+                            format!(
+                                "{} row(s) affected in {} ms",
+                                result.rows_affected,
+                                result.elapsed.as_millis()
+                            )
+                        } else {
+                            format!(
+                                "{} row(s) in {} ms",
+                                result.rows.fetched(),
+                                result.elapsed.as_millis()
+                            )
+                        };
+                        state.runner.result = Some(result);
+                        state.runner.selected_row = 0;
+                        state.runner.col_offset = 0;
+                    }
+                }
+                vec![]
+            }
+            DbEvent::QueryFailed { id, message } => {
+                if !is_current_query(state, id) {
+                    return vec![];
+                }
+                let streamed = !state.runner.running;
+                state.runner.running = false;
+                // The server's own wording for a killed statement varies.
+                let cancelled = state.runner.task.take().is_some_and(|t| t.cancelling);
+                match state.runner.result.as_ref() {
+                    // Rows read before a stop stay in the grid.
+                    Some(shown) if cancelled && streamed => {
+                        state.status.message =
+                            format!("Fetch stopped after {} rows", shown.rows.fetched());
+                    }
+                    _ if cancelled => {
+                        state.runner.error = Some("Query cancelled".to_string());
+                        state.status.message = "Query cancelled".to_string();
+                    }
+                    _ => {
+                        state.runner.error = Some(message);
+                        state.status.message = "Query failed".to_string();
+                    }
+                }
+                vec![]
+            }
+            DbEvent::QueryExpired { id } => {
+                if !is_current_query(state, id) {
+                    return vec![];
+                }
+                // Rows already read stay; the rest needs the statement again.
+                state.runner.running = false;
+                state.runner.task = None;
+                state.runner.expired = true;
+                state.status.message = format!(
+                    "Result stream expired after {} rows while paused; re-run to continue",
+                    state.runner.result.as_ref().map_or(0, |r| r.rows.fetched())
+                );
+                vec![]
+            }
+            DbEvent::ExportProgress { rows } => {
+                state.status.message = format!("Exporting… {} rows written (Ctrl+X: cancel)", rows);
+                vec![]
+            }
+            DbEvent::ExportFinished { path, rows } => {
+                state.runner.transfer = None;
+                state.status.message = format!("Exported {} rows to {}", rows, path.display());
+                vec![]
+            }
+            DbEvent::ExportFailed { id, message } => {
+                if state.runner.transfer == Some(id) {
+                    state.runner.transfer = None;
+                }
+                state.status.message = message;
+                vec![]
+            }
+            DbEvent::ImportPreviewed { preview } => {
+                // Ignore a late answer once the dialog has been dismissed.
+                let Some(d) = state.data.import.as_mut() else {
+                    return vec![];
+                };
+                if state.data.table.as_deref() != Some(preview.table.table.as_str()) {
+                    return vec![];
+                }
+                d.mapping = preview.auto_map(d.options.header);
+                d.selected = 0;
+                d.preview = Some(preview);
+                state.status.message =
+                    "Map columns: ↑/↓ column • ←/→ file column • Enter import • Esc back"
+                        .to_string();
+                vec![]
+            }
+            DbEvent::ImportProgress {
+                rows,
+                bytes,
+                total_bytes,
+            } => {
+                if let Some(d) = state.data.import.as_mut() {
+                    d.progress = Some(ImportProgress {
+                        rows,
+                        ratio: if total_bytes == 0 {
+                            1.0
+                        } else {
+                            (bytes as f64 / total_bytes as f64).min(1.0)
+                        },
+                    });
+                }
+                state.status.message = format!("Importing… {} rows", rows);
+                vec![]
+            }
+            DbEvent::ImportFinished {
+                table,
+                rows,
+                dry_run,
+            } => {
+                state.runner.transfer = None;
+                state.data.import = None;
+                if dry_run {
+                    state.status.message = format!(
+                        "Dry run: {} rows would be imported; nothing was written",
+                        rows
+                    );
+                    return vec![];
+                }
+                // Show the new rows.
+                let cmds = if state.data.table.as_deref() == Some(table.as_str()) {
+                    load_data_page(state, state.data.offset)
+                } else {
+                    vec![]
+                };
+                state.status.message = format!("Imported {} rows into {}", rows, table);
+                cmds
+            }
+            DbEvent::ImportFailed { id, message } => {
+                if state.runner.transfer == Some(id) {
+                    state.runner.transfer = None;
+                    // The dialog stays open so the mapping can be fixed.
+                    if let Some(d) = state.data.import.as_mut() {
+                        d.progress = None;
+                    }
+                }
+                state.status.message = message;
+                vec![]
+            }
+            DbEvent::ChangesApplied { table, count } => {
+                state.data.review = None;
+                state.data.changes = Default::default();
+                let cmds = if state.data.table.as_deref() == Some(table.as_str()) {
+                    load_data_page(state, state.data.offset)
+                } else {
+                    vec![]
+                };
+                state.status.message = format!("Saved {} changes to {}", count, table);
+                cmds
+            }
+            DbEvent::ChangesFailed { message } => {
+                // The review stays open and the changes staged for another try.
+                if let Some(r) = state.data.review.as_mut() {
+                    r.saving = false;
+                }
+                state.status.message = message;
+                vec![]
+            }
             DbEvent::Error { message } => {
                 state.status.message = format!("DB error: {}", message);
                 vec![]
             }
+
+            DbEvent::ConnectionTested { result } => {
+                state.status.message = match result {
+                    Ok(elapsed) => format!("Connection OK ({} ms)", elapsed.as_millis()),
+                    Err(message) => format!("Connection failed: {}", message),
+                };
+                vec![]
+            }
         },
 
         Event::Storage(se) => match se {
@@ -420,6 +2973,13 @@ pub fn reduce_event(state: &mut AppState, event: Event) -> Vec<Command> {
                 state.status.message = "Connections saved".to_string();
                 vec![]
             }
+            StorageEvent::QueriesSaved => {
+                state.status.message = "Queries saved".to_string();
+                vec![]
+            }
+            StorageEvent::ConnectionsImported { connections } => {
+                import_connections(state, connections)
+            }
             StorageEvent::Error { message } => {
                 state.status.message = format!("Storage error: {}", message);
                 vec![]
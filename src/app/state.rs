@@ -1,6 +1,29 @@
+use std::{collections::BTreeSet, time::Instant};
 use ulid::Ulid;
 
-use super::screen::Screen;
+use super::{
+    command::{Command, DbCommand},
+    editor::TextBuffer,
+    screen::Screen,
+};
+use crate::{
+    db::{
+        edit::{EditStatement, PendingChanges, row_key},
+        export::{ExportFormat, ExportOptions},
+        import::{ImportFormat, ImportOptions, ImportPreview},
+        introstect::{ForeignKeyInfo, ObjectKind, ReferencingKey, SchemaObject, TableDetails},
+        types::{CellValue, QueryResult, ResultColumn},
+    },
+    domain::{
+        connection::{
+            ConnectionLabels, ConnectionOptions, DbKind, Environment, LabelColor, SshTunnel,
+            SslMode, TlsSettings,
+        },
+        filter::{RowFilter, rank},
+        querylib::SavedQuery,
+    },
+    storage::model::ConnectionProfile,
+};
 
 #[derive(Debug)]
 pub struct AppState {
@@ -12,6 +35,8 @@ pub struct AppState {
     pub data: DataState,
     pub queries: QueriesState,
     pub runner: RunnerState,
+
+    pub sessions: SessionTabs,
 }
 
 impl AppState {
@@ -24,6 +49,140 @@ impl AppState {
             data: DataState::default(),
             queries: QueriesState::default(),
             runner: RunnerState::default(),
+            sessions: SessionTabs::default(),
+        }
+    }
+
+    /// Parks the active session in its tab and brings `id` forward in its
+    /// place. False if no tab has that id.
+    pub fn switch_session(&mut self, id: SessionId) -> bool {
+        let (Some(from), Some(to)) = (
+            self.sessions.index(self.sessions.active),
+            self.sessions.index(id),
+        ) else {
+            return false;
+        };
+        self.swap_session(from);
+        self.swap_session(to);
+        self.sessions.active = id;
+        true
+    }
+
+    /// Opens an empty tab and switches to it.
+    pub fn open_session(&mut self) -> SessionId {
+        self.sessions.opened += 1;
+        let id = self.sessions.opened;
+        self.sessions.tabs.push(Session::new(id));
+        self.switch_session(id);
+        id
+    }
+
+    /// Drops a tab, switching to its neighbour first if it is the active
+    /// one. The last tab is never closed.
+    pub fn close_session(&mut self, id: SessionId) {
+        let Some(idx) = self.sessions.index(id) else {
+            return;
+        };
+        if self.sessions.tabs.len() < 2 {
+            return;
+        }
+        if id == self.sessions.active {
+            let next = if idx + 1 < self.sessions.tabs.len() {
+                idx + 1
+            } else {
+                idx - 1
+            };
+            self.switch_session(self.sessions.tabs[next].id);
+        }
+        self.sessions.tabs.remove(idx);
+    }
+
+    /// One title per tab: the profile each session is connected to.
+    pub fn session_titles(&self) -> Vec<String> {
+        self.sessions
+            .tabs
+            .iter()
+            .map(|t| {
+                let profile = if t.id == self.sessions.active {
+                    self.connections.active
+                } else {
+                    t.profile
+                };
+                profile
+                    .and_then(|id| self.connections.name_of(id))
+                    .unwrap_or("(not connected)")
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// Exchanges the live per-session state with what tab `idx` holds.
+    fn swap_session(&mut self, idx: usize) {
+        let tab = &mut self.sessions.tabs[idx];
+        std::mem::swap(&mut self.schema, &mut tab.schema);
+        std::mem::swap(&mut self.data, &mut tab.data);
+        std::mem::swap(&mut self.runner, &mut tab.runner);
+        std::mem::swap(&mut self.connections.active, &mut tab.profile);
+        std::mem::swap(&mut self.status.connection_label, &mut tab.label);
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies one live connection; commands and events carry it so the
+/// worker and reducer keep each session's state apart.
+pub type SessionId = u64;
+
+/// Open tabs, one per session. The active session's state lives in the
+/// `AppState` fields themselves; its tab holds empty placeholders until it
+/// is switched away from.
+#[derive(Debug)]
+pub struct SessionTabs {
+    pub active: SessionId,
+    pub tabs: Vec<Session>,
+    pub opened: SessionId, // highest id handed out so far
+}
+
+impl SessionTabs {
+    /// Addresses `cmd` to the active session's worker.
+    pub fn command(&self, cmd: DbCommand) -> Command {
+        Command::Db(self.active, cmd)
+    }
+
+    pub fn index(&self, id: SessionId) -> Option<usize> {
+        self.tabs.iter().position(|t| t.id == id)
+    }
+}
+
+impl Default for SessionTabs {
+    fn default() -> Self {
+        Self {
+            active: 0,
+            tabs: vec![Session::new(0)],
+            opened: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Session {
+    pub id: SessionId,
+    pub profile: Option<Ulid>, // parked `connections.active`
+    pub label: String,         // parked `status.connection_label`
+    pub schema: SchemaState,
+    pub data: DataState,
+    pub runner: RunnerState,
+}
+
+impl Session {
+    fn new(id: SessionId) -> Self {
+        Self {
+            id,
+            ..Self::default()
         }
     }
 }
@@ -32,37 +191,173 @@ impl AppState {
 pub struct StatusState {
     pub connection_label: String,
     pub message: String,
+    pub tick: usize, // advanced by `Action::Tick`, drives spinners
+}
+
+/// One visible line of the Connections screen's profile tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionRow<'a> {
+    Group {
+        name: &'a str,
+        count: usize,
+        collapsed: bool,
+    },
+    Item(usize), // index into `items`
 }
 
 #[derive(Debug, Default)]
 pub struct ConnectionsState {
-    pub selected: usize,
+    pub selected: usize, // index into `rows()`, not `items`
     pub items: Vec<ConnectionItem>,
+    pub collapsed: Vec<String>, // group names folded shut
+    pub filter: String,
+    pub filtering: bool, // filter input has focus
     pub adding: Option<NewConnectionDraft>,
     pub delete_confirm: Option<DeleteConnectionConfirm>,
+    pub importing: Option<String>, // source typed into the import prompt
+    pub active: Option<Ulid>,      // id of the connected profile
+}
+
+impl ConnectionsState {
+    /// Indices into `items` fuzzy-matching the filter by name, best first,
+    /// then any others whose group or a tag contains it.
+    pub fn visible(&self) -> Vec<usize> {
+        let mut hits = rank(&self.filter, self.items.iter().map(|c| c.name.as_str()));
+        let needle = self.filter.to_lowercase();
+        for (i, c) in self.items.iter().enumerate() {
+            let mut labels = c.labels.group.iter().chain(&c.labels.tags);
+            if !hits.contains(&i) && labels.any(|l| l.to_lowercase().contains(&needle)) {
+                hits.push(i);
+            }
+        }
+        hits
+    }
+
+    /// Ungrouped profiles first, then a header per group (by name) followed
+    /// by its profiles unless the group is collapsed. While a filter is set,
+    /// only matching profiles are listed and every group is open.
+    pub fn rows(&self) -> Vec<ConnectionRow<'_>> {
+        let visible = self.visible();
+        let group_of = |i: usize| self.items[i].labels.group.as_deref();
+        let mut rows: Vec<ConnectionRow> = visible
+            .iter()
+            .filter(|&&i| group_of(i).is_none())
+            .map(|&i| ConnectionRow::Item(i))
+            .collect();
+        let mut groups: Vec<&str> = visible.iter().filter_map(|&i| group_of(i)).collect();
+        groups.sort_by_key(|g| g.to_lowercase());
+        groups.dedup();
+        for name in groups {
+            let members: Vec<usize> = visible
+                .iter()
+                .copied()
+                .filter(|&i| group_of(i) == Some(name))
+                .collect();
+            let collapsed = self.filter.is_empty() && self.collapsed.iter().any(|g| g == name);
+            rows.push(ConnectionRow::Group {
+                name,
+                count: members.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(members.into_iter().map(ConnectionRow::Item));
+            }
+        }
+        rows
+    }
+
+    pub fn selected_row(&self) -> Option<ConnectionRow<'_>> {
+        self.rows().get(self.selected).copied()
+    }
+
+    pub fn selected_item(&self) -> Option<&ConnectionItem> {
+        match self.selected_row()? {
+            ConnectionRow::Item(i) => self.items.get(i),
+            ConnectionRow::Group { .. } => None,
+        }
+    }
+
+    /// Point the selection at `items[idx]`, opening its group if needed.
+    pub fn select_item(&mut self, idx: usize) {
+        if let Some(group) = self.items.get(idx).and_then(|c| c.labels.group.as_ref()) {
+            self.collapsed.retain(|g| g != group);
+        }
+        self.selected = self
+            .rows()
+            .iter()
+            .position(|r| *r == ConnectionRow::Item(idx))
+            .unwrap_or(0);
+    }
+
+    /// The profile the active session is connected to.
+    pub fn active_item(&self) -> Option<&ConnectionItem> {
+        let id = self.active?;
+        self.items.iter().find(|c| c.id == id)
+    }
+
+    pub fn name_of(&self, id: Ulid) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|c| c.id == id)
+            .map(|c| c.name.as_str())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ConnectionItem {
     pub id: Ulid,
+    pub kind: DbKind,
     pub name: String,
     pub host: String,
     pub port: u16,
     pub user: String,
     pub password: String,
     pub db: String,
+    pub statement_timeout_secs: Option<u64>, // None = no limit
+    pub ssh: Option<SshTunnel>,
+    pub ssh_passphrase: String, // unlocks `ssh.key_file`; blank if it has none
+    pub tls: TlsSettings,
+    pub options: ConnectionOptions,
+    pub labels: ConnectionLabels,
+    pub production: bool, // confirm destructive statements by name
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct NewConnectionDraft {
     pub id: Ulid,
+    pub kind: DbKind,
     pub name: String,
     pub host: String,
     pub port: String,
     pub user: String,
     pub password: String,
     pub database: String,
-    pub field: usize, // 0..5
+    pub timeout: String,  // statement timeout in seconds; blank for none
+    pub ssh_host: String, // blank for no tunnel
+    pub ssh_port: String,
+    pub ssh_user: String,
+    pub ssh_key: String, // blank to use ssh-agent
+    pub ssh_passphrase: String,
+    pub ssh_skip_host_key_check: bool,
+    pub tls_mode: SslMode,
+    pub tls_ca: String, // blank for the built-in roots
+    pub tls_cert: String,
+    pub tls_key: String,
+    pub charset: String, // blank for the driver's default, as are the rest
+    pub collation: String,
+    pub timezone: String,
+    pub socket: String,
+    pub connect_timeout: String,
+    pub pool_size: String,
+    pub group: String, // blank for none
+    pub tags: String,  // comma-separated
+    pub environment: Option<Environment>,
+    /// `None` follows the environment's colour.
+    pub color: Option<LabelColor>,
+    pub read_only: bool,
+    pub production: bool,
+    pub page: usize,  // index into `NewConnectionDraft::PAGES`
+    pub field: usize, // one of `NewConnectionDraft::fields()`
     pub is_edit: bool,
 }
 
@@ -73,25 +368,204 @@ pub struct DeleteConnectionConfirm {
 }
 
 impl NewConnectionDraft {
+    // Field indices, page by page; `fields()` lists each page's in tab order.
+    pub const NAME_FIELD: usize = 0;
+    pub const KIND_FIELD: usize = 1;
+    pub const HOST_FIELD: usize = 2;
+    pub const PORT_FIELD: usize = 3;
+    pub const USER_FIELD: usize = 4;
+    pub const PASSWORD_FIELD: usize = 5;
+    pub const DATABASE_FIELD: usize = 6;
+    pub const TIMEOUT_FIELD: usize = 7;
+    pub const SSH_HOST_FIELD: usize = 8;
+    pub const SSH_PORT_FIELD: usize = 9;
+    pub const SSH_USER_FIELD: usize = 10;
+    pub const SSH_KEY_FIELD: usize = 11;
+    pub const SSH_PASSPHRASE_FIELD: usize = 12;
+    pub const SSH_CHECK_FIELD: usize = 13;
+    pub const SSL_MODE_FIELD: usize = 14;
+    pub const TLS_CA_FIELD: usize = 15;
+    pub const TLS_CERT_FIELD: usize = 16;
+    pub const TLS_KEY_FIELD: usize = 17;
+    pub const CHARSET_FIELD: usize = 18;
+    pub const COLLATION_FIELD: usize = 19;
+    pub const TIMEZONE_FIELD: usize = 20;
+    pub const SOCKET_FIELD: usize = 21;
+    pub const CONNECT_TIMEOUT_FIELD: usize = 22;
+    pub const POOL_SIZE_FIELD: usize = 23;
+    pub const GROUP_FIELD: usize = 24;
+    pub const TAGS_FIELD: usize = 25;
+    pub const ENVIRONMENT_FIELD: usize = 26;
+    pub const COLOR_FIELD: usize = 27;
+    pub const READ_ONLY_FIELD: usize = 28;
+    pub const PRODUCTION_FIELD: usize = 29;
+
+    pub const CONNECTION_PAGE: usize = 0;
+    pub const SSH_PAGE: usize = 1;
+    pub const TLS_PAGE: usize = 2;
+    pub const OPTIONS_PAGE: usize = 3;
+    pub const LABELS_PAGE: usize = 4;
+
+    pub const PAGES: [&'static str; 5] = [
+        "Connection",
+        "SSH tunnel",
+        "TLS",
+        "Options",
+        "Labels & safety",
+    ];
+
+    /// Field indices shown on the current page for the current kind, in tab
+    /// order. File-backed kinds only need a name and the file path (kept in
+    /// `database`).
+    pub fn fields(&self) -> &'static [usize] {
+        match self.page {
+            Self::SSH_PAGE => &[
+                Self::SSH_HOST_FIELD,
+                Self::SSH_PORT_FIELD,
+                Self::SSH_USER_FIELD,
+                Self::SSH_KEY_FIELD,
+                Self::SSH_PASSPHRASE_FIELD,
+                Self::SSH_CHECK_FIELD,
+            ],
+            Self::TLS_PAGE => &[
+                Self::SSL_MODE_FIELD,
+                Self::TLS_CA_FIELD,
+                Self::TLS_CERT_FIELD,
+                Self::TLS_KEY_FIELD,
+            ],
+            // Postgres takes no charset or collation.
+            Self::OPTIONS_PAGE if self.kind == DbKind::Postgres => &[
+                Self::TIMEZONE_FIELD,
+                Self::SOCKET_FIELD,
+                Self::CONNECT_TIMEOUT_FIELD,
+                Self::POOL_SIZE_FIELD,
+            ],
+            Self::OPTIONS_PAGE => &[
+                Self::CHARSET_FIELD,
+                Self::COLLATION_FIELD,
+                Self::TIMEZONE_FIELD,
+                Self::SOCKET_FIELD,
+                Self::CONNECT_TIMEOUT_FIELD,
+                Self::POOL_SIZE_FIELD,
+            ],
+            Self::LABELS_PAGE => &[
+                Self::GROUP_FIELD,
+                Self::TAGS_FIELD,
+                Self::ENVIRONMENT_FIELD,
+                Self::COLOR_FIELD,
+                Self::READ_ONLY_FIELD,
+                Self::PRODUCTION_FIELD,
+            ],
+            _ if self.kind.is_file() => &[
+                Self::NAME_FIELD,
+                Self::KIND_FIELD,
+                Self::DATABASE_FIELD,
+                Self::TIMEOUT_FIELD,
+            ],
+            _ => &[
+                Self::NAME_FIELD,
+                Self::KIND_FIELD,
+                Self::HOST_FIELD,
+                Self::PORT_FIELD,
+                Self::USER_FIELD,
+                Self::PASSWORD_FIELD,
+                Self::DATABASE_FIELD,
+                Self::TIMEOUT_FIELD,
+            ],
+        }
+    }
+
+    /// Pages the current kind has, as indices into `PAGES`; file-backed
+    /// kinds have no network to tunnel, encrypt or tune.
+    pub fn pages(&self) -> &'static [usize] {
+        if self.kind.is_file() {
+            &[Self::CONNECTION_PAGE, Self::LABELS_PAGE]
+        } else {
+            &[
+                Self::CONNECTION_PAGE,
+                Self::SSH_PAGE,
+                Self::TLS_PAGE,
+                Self::OPTIONS_PAGE,
+                Self::LABELS_PAGE,
+            ]
+        }
+    }
+
+    /// Flip to the next (or previous) page, landing on its first field.
+    pub fn turn_page(&mut self, forward: bool) {
+        let pages = self.pages();
+        let n = pages.len();
+        let i = pages.iter().position(|p| *p == self.page).unwrap_or(0);
+        self.page = pages[(i + if forward { 1 } else { n - 1 }) % n];
+        self.field = self.fields()[0];
+    }
+
+    /// Step `field` to the next (or previous) visible field, wrapping.
+    pub fn step_field(&mut self, forward: bool) {
+        let fields = self.fields();
+        let n = fields.len();
+        let i = fields.iter().position(|f| *f == self.field).unwrap_or(0);
+        let next = if forward { i + 1 } else { i + n - 1 };
+        self.field = fields[next % n];
+    }
+
     pub fn new() -> Self {
         Self {
             id: Ulid::new(),
-            port: "3306".to_string(),
+            port: DbKind::default().default_port().to_string(),
+            ssh_port: SshTunnel::DEFAULT_PORT.to_string(),
             is_edit: false,
             ..Default::default()
         }
     }
 
     pub fn edit_from(item: &ConnectionItem) -> Self {
+        let ssh = item.ssh.as_ref();
         Self {
             id: item.id,
+            kind: item.kind,
             name: item.name.clone(),
             host: item.host.clone(),
             port: item.port.to_string(),
             user: item.user.clone(),
             password: item.password.clone(),
             database: item.db.clone(),
-            field: 0,
+            timeout: item
+                .statement_timeout_secs
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            ssh_host: ssh.map(|s| s.host.clone()).unwrap_or_default(),
+            ssh_port: ssh.map_or(SshTunnel::DEFAULT_PORT, |s| s.port).to_string(),
+            ssh_user: ssh.map(|s| s.user.clone()).unwrap_or_default(),
+            ssh_key: ssh.and_then(|s| s.key_file.clone()).unwrap_or_default(),
+            ssh_passphrase: item.ssh_passphrase.clone(),
+            ssh_skip_host_key_check: ssh.is_some_and(|s| s.skip_host_key_check),
+            tls_mode: item.tls.mode,
+            tls_ca: item.tls.ca.clone().unwrap_or_default(),
+            tls_cert: item.tls.cert.clone().unwrap_or_default(),
+            tls_key: item.tls.key.clone().unwrap_or_default(),
+            charset: item.options.charset.clone().unwrap_or_default(),
+            collation: item.options.collation.clone().unwrap_or_default(),
+            timezone: item.options.timezone.clone().unwrap_or_default(),
+            socket: item.options.socket.clone().unwrap_or_default(),
+            connect_timeout: item
+                .options
+                .connect_timeout_secs
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            pool_size: item
+                .options
+                .pool_size
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            group: item.labels.group.clone().unwrap_or_default(),
+            tags: item.labels.tags.join(", "),
+            environment: item.labels.environment,
+            color: item.labels.color,
+            read_only: item.options.read_only,
+            production: item.production,
+            page: Self::CONNECTION_PAGE,
+            field: Self::NAME_FIELD,
             is_edit: true,
         }
     }
@@ -101,17 +575,53 @@ impl ConnectionItem {
     pub fn new(name: &str, host: &str, port: u16, user: &str, password: &str, db: &str) -> Self {
         Self {
             id: Ulid::new(),
+            kind: DbKind::default(),
             name: name.to_string(),
             host: host.to_string(),
             port,
             user: user.to_string(),
             password: password.to_string(),
             db: db.to_string(),
+            statement_timeout_secs: None,
+            ssh: None,
+            ssh_passphrase: String::new(),
+            tls: TlsSettings::default(),
+            options: ConnectionOptions::default(),
+            labels: ConnectionLabels::default(),
+            production: false,
+        }
+    }
+
+    /// A stored profile together with its secrets from the keyring.
+    pub fn from_profile(
+        id: Ulid,
+        profile: ConnectionProfile,
+        password: String,
+        ssh_passphrase: String,
+    ) -> Self {
+        Self {
+            id,
+            kind: profile.kind,
+            name: profile.name,
+            host: profile.host,
+            port: profile.port,
+            user: profile.user,
+            password,
+            db: profile.database.unwrap_or_default(),
+            statement_timeout_secs: profile.statement_timeout_secs,
+            ssh: profile.ssh,
+            ssh_passphrase,
+            tls: profile.tls,
+            options: profile.options,
+            labels: profile.labels,
+            production: profile.production,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_id(
         id: Ulid,
+        kind: DbKind,
         name: &str,
         host: &str,
         port: u16,
@@ -121,12 +631,20 @@ impl ConnectionItem {
     ) -> Self {
         Self {
             id,
+            kind,
             name: name.to_string(),
             host: host.to_string(),
             port,
             user: user.to_string(),
             password: password.to_string(),
             db: db.to_string(),
+            statement_timeout_secs: None,
+            ssh: None,
+            ssh_passphrase: String::new(),
+            tls: TlsSettings::default(),
+            options: ConnectionOptions::default(),
+            labels: ConnectionLabels::default(),
+            production: false,
         }
     }
 }
@@ -139,27 +657,603 @@ pub struct ColumnInfo {
     pub column_key: Option<String>, // "PRI", "MUL", "UNI", etc.
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaTab {
+    #[default]
+    Columns,
+    Indexes,
+    ForeignKeys,
+    Ddl,
+}
+
+impl SchemaTab {
+    pub const ALL: [SchemaTab; 4] = [
+        SchemaTab::Columns,
+        SchemaTab::Indexes,
+        SchemaTab::ForeignKeys,
+        SchemaTab::Ddl,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SchemaTab::Columns => "Columns",
+            SchemaTab::Indexes => "Indexes",
+            SchemaTab::ForeignKeys => "Foreign Keys",
+            SchemaTab::Ddl => "DDL",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|t| *t == self).unwrap_or(0)
+    }
+
+    /// Next tab in `ALL`, wrapping.
+    pub fn cycle(self, forward: bool) -> SchemaTab {
+        let n = Self::ALL.len();
+        let i = self.index();
+        let next = if forward { i + 1 } else { i + n - 1 };
+        Self::ALL[next % n]
+    }
+}
+
+/// One visible line of the Schema screen's object tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaRow<'a> {
+    Group {
+        kind: ObjectKind,
+        count: usize,
+        collapsed: bool,
+    },
+    Object(&'a SchemaObject),
+}
+
 #[derive(Debug, Default)]
 pub struct SchemaState {
-    pub tables: Vec<String>,
-    pub selected_table: usize,
+    pub objects: Vec<SchemaObject>, // sorted by (kind, name)
+    pub collapsed: Vec<ObjectKind>,
+    pub selected: usize, // index into `rows()`
+    pub filter: String,
+    pub filtering: bool, // filter input has focus
 
     // new:
     pub columns_table: Option<String>, // which table these columns belong to
     pub columns: Vec<ColumnInfo>,
+
+    // detail pane: full description of `columns_table`
+    pub details: Option<TableDetails>,
+    pub tab: SchemaTab,
+    pub detail_scroll: u16,
+
+    // detail pane for routines, triggers and events
+    pub definition: Option<(SchemaObject, String)>,
+
+    // modal: switch to another database/schema on the same connection
+    pub picker: Option<DatabasePicker>,
 }
 
+#[derive(Debug, Default)]
+pub struct DatabasePicker {
+    pub items: Vec<String>,
+    pub current: Option<String>,
+    pub selected: usize,
+    pub loading: bool,
+}
+
+impl SchemaState {
+    /// Group headers (only for kinds that have objects) followed by their
+    /// objects unless the group is collapsed. While a filter is set, only
+    /// matching objects are listed, best first, and every group is open.
+    pub fn rows(&self) -> Vec<SchemaRow<'_>> {
+        let mut rows = Vec::new();
+        for kind in ObjectKind::ALL {
+            let group: Vec<&SchemaObject> =
+                self.objects.iter().filter(|o| o.kind == kind).collect();
+            let matched = rank(&self.filter, group.iter().map(|o| o.name.as_str()));
+            if matched.is_empty() {
+                continue;
+            }
+            let collapsed = self.filter.is_empty() && self.collapsed.contains(&kind);
+            rows.push(SchemaRow::Group {
+                kind,
+                count: matched.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(matched.into_iter().map(|i| SchemaRow::Object(group[i])));
+            }
+        }
+        rows
+    }
+
+    pub fn selected_row(&self) -> Option<SchemaRow<'_>> {
+        self.rows().get(self.selected).copied()
+    }
+
+    pub fn selected_object(&self) -> Option<&SchemaObject> {
+        match self.selected_row()? {
+            SchemaRow::Object(o) => Some(o),
+            SchemaRow::Group { .. } => None,
+        }
+    }
+
+    /// The selected table or view, i.e. something with rows to browse.
+    pub fn selected_relation(&self) -> Option<&str> {
+        self.selected_object()
+            .filter(|o| o.kind.is_relation())
+            .map(|o| o.name.as_str())
+    }
+}
+
+pub const DATA_PAGE_SIZE: u64 = 100;
+
 #[derive(Debug, Default)]
 pub struct DataState {
-    pub title: String,
+    pub table: Option<String>,
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<CellValue>>,
+
+    // paging
+    pub offset: u64,
+    pub total_estimate: Option<u64>,
+    pub loading: bool,
+
+    // cursor + horizontal scroll
+    pub selected_row: usize,
+    pub col_offset: usize,         // first scrollable column shown
+    pub pk_column: Option<String>, // frozen on the left, also used for ORDER BY
+    pub key_columns: Vec<String>,  // identify a row for edits; see `TableDetails::key_columns`
+
+    // structured WHERE / ORDER BY, and the builder modal editing it
+    pub column_info: Vec<ColumnInfo>, // table's columns with declared types
+    pub filter: RowFilter,
+    pub filter_draft: Option<FilterDraft>,
+
+    pub export: Option<ExportDraft>,
+    pub import: Option<ImportDraft>,
+    pub marked: BTreeSet<usize>, // rows on the page to export instead of all shown
+
+    // cell cursor and edits staged on the page
+    pub selected_col: usize, // index into `columns`
+    pub changes: PendingChanges,
+    pub cell_editor: Option<CellEditor>,
+    pub review: Option<ChangeReview>,
+
+    pub detail: Option<RecordDetail>,
+
+    // foreign keys to and from the table, and the tables left by following them
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    pub referenced_by: Vec<ReferencingKey>,
+    pub reference_picker: Option<usize>, // selected entry of `referenced_by`
+    pub trail: Vec<DataState>,           // oldest first
+}
+
+/// The selected row shown vertically, one field per line, over the Data and
+/// Runner grids.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecordDetail {
+    pub scroll: usize, // first line shown
+}
+
+impl RecordDetail {
+    /// Lines the record takes: a field whose value fits on one line shares
+    /// it with the name, longer values go on their own lines below.
+    pub fn height(row: &[CellValue]) -> usize {
+        row.iter()
+            .map(|v| match v.detail_lines().len() {
+                1 => 1,
+                n => n + 1,
+            })
+            .sum()
+    }
+}
+
+/// The cell editor opened with Enter on the Data grid.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CellEditor {
+    pub row: usize,
+    pub col: usize,
+    pub value: Option<String>, // None is NULL
+}
+
+/// The statements a save will run, shown for review before they do.
+#[derive(Debug, Default, Clone)]
+pub struct ChangeReview {
+    pub statements: Vec<EditStatement>,
+    pub scroll: usize,
+    pub saving: bool,
+}
+
+/// The export dialog over the Data and Runner grids.
+#[derive(Debug, Default, Clone)]
+pub struct ExportDraft {
+    pub path: String,
+    pub options: ExportOptions,
+    pub all_rows: bool, // stream the full result instead of the rows on screen
+    pub field: usize,   // one of `ExportDraft::fields()`
+}
+
+impl ExportDraft {
+    // The last three only apply to SQL.
+    pub const PATH_FIELD: usize = 0;
+    pub const FORMAT_FIELD: usize = 1;
+    pub const ROWS_FIELD: usize = 2;
+    pub const HEADER_FIELD: usize = 3;
+    pub const DELIMITER_FIELD: usize = 4;
+    pub const NULL_FIELD: usize = 5;
+    pub const QUOTE_FIELD: usize = 6;
+    pub const TABLE_FIELD: usize = 7;
+    pub const STATEMENT_FIELD: usize = 8;
+    pub const BATCH_FIELD: usize = 9;
+
+    /// A draft writing CSV to `<stem>.csv` in the working directory.
+    pub fn new(stem: &str) -> Self {
+        let options = ExportOptions::default();
+        Self {
+            path: format!("{}.{}", stem, options.format.extension()),
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Field indices that apply to the chosen format, in tab order.
+    pub fn fields(&self) -> &'static [usize] {
+        match self.options.format {
+            ExportFormat::Csv => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::ROWS_FIELD,
+                Self::HEADER_FIELD,
+                Self::DELIMITER_FIELD,
+                Self::NULL_FIELD,
+                Self::QUOTE_FIELD,
+            ],
+            ExportFormat::Tsv => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::ROWS_FIELD,
+                Self::HEADER_FIELD,
+                Self::NULL_FIELD,
+                Self::QUOTE_FIELD,
+            ],
+            ExportFormat::Markdown => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::ROWS_FIELD,
+                Self::NULL_FIELD,
+            ],
+            ExportFormat::Json | ExportFormat::Ndjson => {
+                &[Self::PATH_FIELD, Self::FORMAT_FIELD, Self::ROWS_FIELD]
+            }
+            ExportFormat::Sql => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::ROWS_FIELD,
+                Self::TABLE_FIELD,
+                Self::STATEMENT_FIELD,
+                Self::BATCH_FIELD,
+            ],
+        }
+    }
+
+    /// Step `field` to the next (or previous) applicable field, wrapping.
+    pub fn step_field(&mut self, forward: bool) {
+        let fields = self.fields();
+        let n = fields.len();
+        let i = fields.iter().position(|f| *f == self.field).unwrap_or(0);
+        let next = if forward { i + 1 } else { i + n - 1 };
+        self.field = fields[next % n];
+    }
+
+    /// Switch format, keeping the file extension in step when it was the default.
+    pub fn set_format(&mut self, format: ExportFormat) {
+        let old = format!(".{}", self.options.format.extension());
+        if let Some(stem) = self.path.strip_suffix(&old) {
+            self.path = format!("{}.{}", stem, format.extension());
+        }
+        self.options.format = format;
+    }
+}
+
+/// The import dialog over the Data grid: file options first, then, once the
+/// file has been read, the column mapping and a preview.
+#[derive(Debug, Default, Clone)]
+pub struct ImportDraft {
+    pub path: String,
+    pub options: ImportOptions,
+    pub field: usize, // one of `ImportDraft::fields()`
+
+    // mapping page
+    pub preview: Option<ImportPreview>,
+    pub mapping: Vec<Option<usize>>, // file column feeding each table column
+    pub selected: usize,             // table column
+
+    pub progress: Option<ImportProgress>, // set while the import runs
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ImportProgress {
+    pub rows: u64,
+    pub ratio: f64, // of the file read, 0.0–1.0
+}
+
+impl ImportDraft {
+    pub const PATH_FIELD: usize = 0;
+    pub const FORMAT_FIELD: usize = 1;
+    pub const HEADER_FIELD: usize = 2;
+    pub const DELIMITER_FIELD: usize = 3;
+    pub const NULL_FIELD: usize = 4;
+    pub const MODE_FIELD: usize = 5;
+    pub const BATCH_FIELD: usize = 6;
+    pub const DRY_RUN_FIELD: usize = 7;
+
+    /// Field indices that apply to the chosen format, in tab order.
+    pub fn fields(&self) -> &'static [usize] {
+        match self.options.format {
+            ImportFormat::Csv => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::HEADER_FIELD,
+                Self::DELIMITER_FIELD,
+                Self::NULL_FIELD,
+                Self::MODE_FIELD,
+                Self::BATCH_FIELD,
+                Self::DRY_RUN_FIELD,
+            ],
+            ImportFormat::Tsv => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::HEADER_FIELD,
+                Self::NULL_FIELD,
+                Self::MODE_FIELD,
+                Self::BATCH_FIELD,
+                Self::DRY_RUN_FIELD,
+            ],
+            ImportFormat::Ndjson => &[
+                Self::PATH_FIELD,
+                Self::FORMAT_FIELD,
+                Self::MODE_FIELD,
+                Self::BATCH_FIELD,
+                Self::DRY_RUN_FIELD,
+            ],
+        }
+    }
+
+    /// Step `field` to the next (or previous) applicable field, wrapping.
+    pub fn step_field(&mut self, forward: bool) {
+        let fields = self.fields();
+        let n = fields.len();
+        let i = fields.iter().position(|f| *f == self.field).unwrap_or(0);
+        let next = if forward { i + 1 } else { i + n - 1 };
+        self.field = fields[next % n];
+    }
+}
+
+/// The Data screen's filter builder. Rows are the conditions followed by the
+/// sort keys; fields are join, column, operator, value for a condition and
+/// column, direction for a sort key.
+#[derive(Debug, Default, Clone)]
+pub struct FilterDraft {
+    pub filter: RowFilter,
+    pub row: usize,
+    pub field: usize,
+}
+
+impl FilterDraft {
+    pub const VALUE_FIELD: usize = 3;
+
+    pub fn row_count(&self) -> usize {
+        self.filter.conditions.len() + self.filter.sort.len()
+    }
+
+    pub fn on_condition(&self) -> bool {
+        self.row < self.filter.conditions.len()
+    }
+
+    pub fn field_count(&self) -> usize {
+        if self.on_condition() { 4 } else { 2 }
+    }
+}
+
+impl DataState {
+    pub fn frozen_index(&self) -> Option<usize> {
+        let pk = self.pk_column.as_deref()?;
+        self.columns.iter().position(|c| c.name == pk)
+    }
+
+    /// Columns that scroll horizontally (everything except the frozen one).
+    pub fn scrollable_count(&self) -> usize {
+        self.columns.len() - usize::from(self.frozen_index().is_some())
+    }
+
+    /// Column indices left to right as the grid shows them: the frozen one
+    /// first, then the rest.
+    pub fn column_order(&self) -> Vec<usize> {
+        let frozen = self.frozen_index();
+        frozen
+            .into_iter()
+            .chain((0..self.columns.len()).filter(|&i| Some(i) != frozen))
+            .collect()
+    }
+
+    /// Tables on the back stack then this one, e.g. "authors › books".
+    pub fn breadcrumb(&self) -> String {
+        self.trail
+            .iter()
+            .chain(std::iter::once(self))
+            .filter_map(|d| d.table.as_deref())
+            .collect::<Vec<_>>()
+            .join(" › ")
+    }
+
+    /// A row as the grid shows it, with staged values in place.
+    pub fn record(&self, row: usize) -> Option<Vec<CellValue>> {
+        let mut values = self.rows.get(row)?.clone();
+        for (col, v) in values.iter_mut().enumerate() {
+            if let Some(staged) = self.changes.cell(row, col) {
+                *v = staged.clone();
+            }
+        }
+        Some(values)
+    }
+
+    /// The described column for each result column, by name; columns that
+    /// weren't described get their driver type and no key.
+    pub fn edit_columns(&self) -> Vec<ColumnInfo> {
+        self.columns
+            .iter()
+            .map(|c| {
+                self.column_info
+                    .iter()
+                    .find(|i| i.name == c.name)
+                    .cloned()
+                    .unwrap_or_else(|| ColumnInfo {
+                        name: c.name.clone(),
+                        data_type: c.type_name.to_lowercase(),
+                        is_nullable: true,
+                        column_key: None,
+                    })
+            })
+            .collect()
+    }
+
+    /// Result columns identifying a row; empty when the table has no
+    /// primary or unique key, which makes the grid read-only.
+    pub fn edit_key(&self) -> Vec<usize> {
+        row_key(&self.edit_columns(), &self.key_columns)
+    }
+
+    /// Columns the filter builder offers: the described ones when known,
+    /// otherwise the result columns with their driver type names.
+    pub fn filter_columns(&self) -> Vec<ColumnInfo> {
+        if !self.column_info.is_empty() {
+            return self.column_info.clone();
+        }
+        self.columns
+            .iter()
+            .map(|c| ColumnInfo {
+                name: c.name.clone(),
+                data_type: c.type_name.to_lowercase(),
+                is_nullable: true,
+                column_key: None,
+            })
+            .collect()
+    }
+
+    /// "rows x–y of ~N" for the status bar; empty until a page has loaded.
+    pub fn range_label(&self) -> String {
+        if self.table.is_none() {
+            return String::new();
+        }
+        // Rows added on the grid aren't in the table yet.
+        let loaded = self.changes.new_from.unwrap_or(self.rows.len());
+        if loaded == 0 {
+            return "no rows".to_string();
+        }
+
+        let first = self.offset + 1;
+        let last = self.offset + loaded as u64;
+        match self.total_estimate {
+            Some(n) => format!("rows {}–{} of ~{}", first, last, n.max(last)),
+            None => format!("rows {}–{}", first, last),
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct QueriesState {
-    pub title: String,
+    pub items: Vec<SavedQuery>,
+    pub selected: usize, // index into `visible()`, not `items`
+    pub filter: String,
+    pub filtering: bool, // filter input has focus
+    pub renaming: Option<String>,
+    pub delete_confirm: Option<DeleteQueryConfirm>,
+}
+
+impl QueriesState {
+    /// Indices into `items` that pass the current filter: fuzzy name matches,
+    /// best first, then queries whose tags or SQL contain the filter.
+    pub fn visible(&self) -> Vec<usize> {
+        let mut visible = rank(&self.filter, self.items.iter().map(|q| q.name.as_str()));
+        let rest: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(i, q)| q.matches(&self.filter) && !visible.contains(i))
+            .map(|(i, _)| i)
+            .collect();
+        visible.extend(rest);
+        visible
+    }
+
+    pub fn selected_item(&self) -> Option<&SavedQuery> {
+        let idx = *self.visible().get(self.selected)?;
+        self.items.get(idx)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeleteQueryConfirm {
+    pub id: String,
+    pub name: String,
+}
+
+/// Name/tags prompt shown when saving the Runner buffer to the library.
+#[derive(Debug, Default, Clone)]
+pub struct SaveQueryDraft {
+    pub id: Option<String>, // Some = overwrite an existing saved query
+    pub name: String,
+    pub tags: String, // comma-separated
+    pub field: usize, // 0 = name, 1 = tags
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RunnerFocus {
+    #[default]
+    Editor,
+    Results,
+}
+
+/// A Runner statement running on the worker, which answers with its id.
+/// Once its rows start streaming it stays in flight until the last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTask {
+    pub id: u64,
+    pub sql: String,
+    pub started: Instant,
+    pub cancelling: bool,
 }
 
 #[derive(Debug, Default)]
 pub struct RunnerState {
-    pub title: String,
+    pub editor: TextBuffer,
+    pub focus: RunnerFocus,
+    pub running: bool,
+    pub task: Option<QueryTask>, // the statement in flight
+    pub tasks_started: u64,      // source of task ids
+    pub transfer: Option<u64>,   // task id of the export or import in flight
+
+    // last result (or error) + grid cursor
+    pub result: Option<QueryResult>,
+    pub error: Option<String>,
+    pub expired: bool, // the stream behind `result` broke while paused
+    pub selected_row: usize,
+    pub col_offset: usize,
+
+    // query library link
+    pub loaded_query: Option<String>, // id of the saved query in the editor
+    pub save_prompt: Option<SaveQueryDraft>,
+
+    pub export: Option<ExportDraft>,
+    pub detail: Option<RecordDetail>,
+
+    // modal: destructive statement held back on a production connection
+    pub destructive_confirm: Option<DestructiveConfirm>,
+}
+
+/// A destructive statement that runs once the connection's name is typed.
+#[derive(Debug, Clone)]
+pub struct DestructiveConfirm {
+    pub sql: String,
+    pub name: String,  // connection name to type
+    pub typed: String, // what has been typed so far
 }
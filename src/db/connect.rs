@@ -0,0 +1,209 @@
+use super::{driver::Driver, mysql::MySqlDriver, postgres::PgDriver, sqlite::SqliteDriver};
+use crate::domain::connection::{ConnectionOptions, DbKind, SslMode, TlsSettings};
+use sqlx::{
+    Database, Executor,
+    mysql::{MySql, MySqlConnectOptions, MySqlSslMode},
+    pool::PoolOptions,
+    postgres::{PgConnectOptions, PgSslMode, Postgres},
+    sqlite::SqliteConnectOptions,
+};
+use std::{path::PathBuf, time::Duration};
+
+/// Open a pool for `kind` and wrap it in the matching driver. For file-backed
+/// kinds `db` is the database file path and the network arguments are ignored;
+/// for server kinds an empty `db` leaves the choice to the server (no default
+/// schema on MySQL, the user's own database on Postgres).
+#[allow(clippy::too_many_arguments)]
+pub async fn connect(
+    kind: DbKind,
+    host: &str,
+    port: u16,
+    user: &str,
+    password: &str,
+    db: &str,
+    tls: &TlsSettings,
+    options: &ConnectionOptions,
+) -> Result<Box<dyn Driver>, sqlx::Error> {
+    match kind {
+        DbKind::Mysql => {
+            let opts = mysql_options(host, port, user, password, db, tls, options);
+            let mut session = Vec::new();
+            if options.read_only {
+                session.push("SET SESSION TRANSACTION READ ONLY".to_string());
+            }
+            let pool = on_connect(pool_options::<MySql>(options), session)
+                .connect_with(opts)
+                .await?;
+            let schema = (!db.is_empty()).then(|| db.to_string());
+            Ok(Box::new(MySqlDriver::new(pool, schema)))
+        }
+        DbKind::Postgres => {
+            let opts = pg_options(host, port, user, password, db, tls, options);
+            let mut session = Vec::new();
+            if let Some(tz) = options.timezone.as_deref() {
+                // sqlx pins `TimeZone` to UTC in the startup packet, so it
+                // can only be changed once connected.
+                session.push(format!("SET TIME ZONE '{}'", tz.replace('\'', "''")));
+            }
+            if options.read_only {
+                session.push("SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY".to_string());
+            }
+            let pool = on_connect(pool_options::<Postgres>(options), session)
+                .connect_with(opts)
+                .await?;
+            let schema: Option<String> = sqlx::query_scalar("SELECT current_schema()::text")
+                .fetch_one(&pool)
+                .await?;
+            Ok(Box::new(PgDriver::new(
+                pool,
+                schema.unwrap_or_else(|| "public".to_string()),
+            )))
+        }
+        DbKind::Sqlite => {
+            // Don't create the file: a typo in the path should be an error, not
+            // an empty database.
+            let opts = SqliteConnectOptions::new()
+                .filename(expand_home(db))
+                .create_if_missing(false)
+                .read_only(options.read_only);
+            let pool = pool_options(options).connect_with(opts).await?;
+            Ok(Box::new(SqliteDriver::new(pool)))
+        }
+    }
+}
+
+/// Pool size and connect timeout, the same for every engine.
+pub(crate) fn pool_options<DB: Database>(options: &ConnectionOptions) -> PoolOptions<DB> {
+    PoolOptions::new()
+        .max_connections(
+            options
+                .pool_size
+                .unwrap_or(ConnectionOptions::DEFAULT_POOL_SIZE),
+        )
+        .acquire_timeout(Duration::from_secs(
+            options
+                .connect_timeout_secs
+                .unwrap_or(ConnectionOptions::DEFAULT_CONNECT_TIMEOUT_SECS),
+        ))
+}
+
+/// Run `statements` on every connection the pool opens, e.g. to set up the
+/// session.
+fn on_connect<DB: Database>(pool: PoolOptions<DB>, statements: Vec<String>) -> PoolOptions<DB>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    if statements.is_empty() {
+        return pool;
+    }
+    pool.after_connect(move |conn, _| {
+        let statements = statements.clone();
+        Box::pin(async move {
+            for sql in &statements {
+                conn.execute(sql.as_str()).await?;
+            }
+            Ok(())
+        })
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mysql_options(
+    host: &str,
+    port: u16,
+    user: &str,
+    password: &str,
+    db: &str,
+    tls: &TlsSettings,
+    options: &ConnectionOptions,
+) -> MySqlConnectOptions {
+    let mut opts = MySqlConnectOptions::new()
+        .host(host)
+        .port(port)
+        .username(user)
+        .password(password)
+        .ssl_mode(match tls.mode {
+            SslMode::Disabled => MySqlSslMode::Disabled,
+            SslMode::Preferred => MySqlSslMode::Preferred,
+            SslMode::Required => MySqlSslMode::Required,
+            SslMode::VerifyCa => MySqlSslMode::VerifyCa,
+            SslMode::VerifyIdentity => MySqlSslMode::VerifyIdentity,
+        });
+    if !db.is_empty() {
+        opts = opts.database(db);
+    }
+    if let Some(ca) = tls.ca.as_deref() {
+        opts = opts.ssl_ca(expand_home(ca));
+    }
+    if let Some(cert) = tls.cert.as_deref() {
+        opts = opts.ssl_client_cert(expand_home(cert));
+    }
+    if let Some(key) = tls.key.as_deref() {
+        opts = opts.ssl_client_key(expand_home(key));
+    }
+    if let Some(socket) = options.socket.as_deref() {
+        opts = opts.socket(expand_home(socket));
+    }
+    if let Some(charset) = options.charset.as_deref() {
+        opts = opts.charset(charset);
+    }
+    if let Some(collation) = options.collation.as_deref() {
+        opts = opts.collation(collation);
+    }
+    if let Some(tz) = options.timezone.as_deref() {
+        opts = opts.timezone(tz.to_string());
+    }
+    opts
+}
+
+/// Postgres has no charset or collation to pick, as sqlx always talks UTF-8,
+/// and its time zone is set by `connect`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pg_options(
+    host: &str,
+    port: u16,
+    user: &str,
+    password: &str,
+    db: &str,
+    tls: &TlsSettings,
+    options: &ConnectionOptions,
+) -> PgConnectOptions {
+    let mut opts = PgConnectOptions::new()
+        .host(host)
+        .port(port)
+        .username(user)
+        .password(password)
+        .ssl_mode(match tls.mode {
+            SslMode::Disabled => PgSslMode::Disable,
+            SslMode::Preferred => PgSslMode::Prefer,
+            SslMode::Required => PgSslMode::Require,
+            SslMode::VerifyCa => PgSslMode::VerifyCa,
+            SslMode::VerifyIdentity => PgSslMode::VerifyFull,
+        });
+    if !db.is_empty() {
+        opts = opts.database(db);
+    }
+    if let Some(ca) = tls.ca.as_deref() {
+        opts = opts.ssl_root_cert(expand_home(ca));
+    }
+    if let Some(cert) = tls.cert.as_deref() {
+        opts = opts.ssl_client_cert(expand_home(cert));
+    }
+    if let Some(key) = tls.key.as_deref() {
+        opts = opts.ssl_client_key(expand_home(key));
+    }
+    if let Some(socket) = options.socket.as_deref() {
+        opts = opts.socket(expand_home(socket));
+    }
+    opts
+}
+
+/// Expand a leading `~/` to the user's home directory.
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(dirs) = directories::BaseDirs::new()
+    {
+        return dirs.home_dir().join(rest);
+    }
+    PathBuf::from(path)
+}
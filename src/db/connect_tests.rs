@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::connect::{mysql_options, pg_options, pool_options};
+    use crate::domain::connection::{ConnectionOptions, SslMode, TlsSettings};
+    use sqlx::{Sqlite, mysql::MySqlSslMode};
+    use std::time::Duration;
+
+    #[test]
+    fn mysql_options_carry_ssl_mode_and_skip_an_empty_database() {
+        let tls = TlsSettings {
+            mode: SslMode::VerifyIdentity,
+            ca: Some("/etc/ssl/rds-ca.pem".into()),
+            ..TlsSettings::default()
+        };
+        let opts = mysql_options(
+            "db.internal",
+            3307,
+            "app",
+            "p@ss:word/",
+            "",
+            &tls,
+            &ConnectionOptions::default(),
+        );
+        assert!(matches!(opts.get_ssl_mode(), MySqlSslMode::VerifyIdentity));
+        assert_eq!((opts.get_host(), opts.get_port()), ("db.internal", 3307));
+        assert_eq!(opts.get_username(), "app");
+        assert_eq!(opts.get_database(), None);
+
+        let defaults = (TlsSettings::default(), ConnectionOptions::default());
+        let opts = mysql_options("h", 3306, "u", "", "shop", &defaults.0, &defaults.1);
+        assert!(matches!(opts.get_ssl_mode(), MySqlSslMode::Preferred));
+        assert_eq!(opts.get_database(), Some("shop"));
+    }
+
+    #[test]
+    fn pg_options_map_modes_onto_libpq_names() {
+        // `PgSslMode` has no `PartialEq`, so compare by name.
+        let expected = [
+            (SslMode::Disabled, "Disable"),
+            (SslMode::Preferred, "Prefer"),
+            (SslMode::Required, "Require"),
+            (SslMode::VerifyCa, "VerifyCa"),
+            (SslMode::VerifyIdentity, "VerifyFull"),
+        ];
+        for (mode, pg) in expected {
+            let tls = TlsSettings {
+                mode,
+                ..TlsSettings::default()
+            };
+            let opts = pg_options(
+                "h",
+                5432,
+                "u",
+                "",
+                "app",
+                &tls,
+                &ConnectionOptions::default(),
+            );
+            assert_eq!(format!("{:?}", opts.get_ssl_mode()), pg, "{}", mode);
+            assert_eq!(opts.get_database(), Some("app"));
+        }
+    }
+
+    #[test]
+    fn mysql_options_apply_charset_and_socket() {
+        let options = ConnectionOptions {
+            charset: Some("latin1".into()),
+            collation: Some("latin1_swedish_ci".into()),
+            socket: Some("/run/mysqld/mysqld.sock".into()),
+            ..ConnectionOptions::default()
+        };
+        let opts = mysql_options("h", 3306, "u", "", "", &TlsSettings::default(), &options);
+        assert_eq!(opts.get_charset(), "latin1");
+        assert_eq!(opts.get_collation(), Some("latin1_swedish_ci"));
+        assert_eq!(
+            opts.get_socket().and_then(|p| p.to_str()),
+            Some("/run/mysqld/mysqld.sock")
+        );
+    }
+
+    #[test]
+    fn pool_options_default_to_five_connections() {
+        let pool = pool_options::<Sqlite>(&ConnectionOptions::default());
+        assert_eq!(pool.get_max_connections(), 5);
+        assert_eq!(pool.get_acquire_timeout(), Duration::from_secs(30));
+
+        let options = ConnectionOptions {
+            connect_timeout_secs: Some(3),
+            pool_size: Some(12),
+            ..ConnectionOptions::default()
+        };
+        let pool = pool_options::<Sqlite>(&options);
+        assert_eq!(pool.get_max_connections(), 12);
+        assert_eq!(pool.get_acquire_timeout(), Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,269 @@
+use super::{
+    edit::EditStatement,
+    introstect::{SchemaObject, TableDetails},
+    types::{CellValue, QueryResult, ResultColumn, result_columns},
+};
+use crate::domain::{connection::DbKind, filter::CompiledFilter};
+use async_trait::async_trait;
+use futures_util::{Stream, TryStreamExt};
+use sqlx::{Either, Row};
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+/// `SELECT * FROM table [WHERE …] [ORDER BY …]` for a page fetch, without
+/// LIMIT/OFFSET; the filter's sort wins over `order_by`.
+pub(crate) fn page_select(
+    quote: impl Fn(&str) -> String,
+    from: &str,
+    order_by: Option<&str>,
+    filter: Option<&CompiledFilter>,
+) -> String {
+    let mut sql = format!("SELECT * FROM {}", from);
+    if let Some(f) = filter.filter(|f| !f.where_sql.is_empty()) {
+        sql.push_str(&format!(" WHERE {}", f.where_sql));
+    }
+    match (filter.filter(|f| !f.order_sql.is_empty()), order_by) {
+        (Some(f), _) => sql.push_str(&format!(" ORDER BY {}", f.order_sql)),
+        (None, Some(col)) => sql.push_str(&format!(" ORDER BY {}", quote(col))),
+        (None, None) => {}
+    }
+    sql
+}
+
+/// Rows handed to a [`RowSink`] per call while streaming.
+pub(crate) const STREAM_BATCH: usize = 500;
+
+/// Rows fetched ahead of the Runner grid's cursor; a streamed result waits
+/// for the cursor to come closer before reading further.
+pub(crate) const STREAM_AHEAD: u64 = 2_000;
+
+/// Where [`Driver::stream_rows`] reads from.
+#[derive(Debug, Clone)]
+pub enum RowSource {
+    /// Every row of a table, filtered and sorted as on the Data screen.
+    Table {
+        table: String,
+        order_by: Option<String>,
+        filter: Option<CompiledFilter>,
+    },
+    /// A single read-only statement, run again.
+    Query { sql: String },
+}
+
+impl RowSource {
+    /// The SELECT to run and its bound parameters; `from` qualifies a table name.
+    pub(crate) fn select(
+        &self,
+        quote: impl Fn(&str) -> String,
+        from: impl Fn(&str) -> String,
+    ) -> (String, &[CellValue]) {
+        match self {
+            RowSource::Table {
+                table,
+                order_by,
+                filter,
+            } => (
+                page_select(quote, &from(table), order_by.as_deref(), filter.as_ref()),
+                filter
+                    .as_ref()
+                    .map(|f| f.params.as_slice())
+                    .unwrap_or_default(),
+            ),
+            RowSource::Query { sql } => (sql.clone(), &[]),
+        }
+    }
+}
+
+/// Receives streamed rows batch by batch, e.g. to write them to a file.
+#[async_trait]
+pub trait RowSink: Send {
+    /// Called once, before any rows.
+    async fn columns(&mut self, columns: &[ResultColumn]) -> Result<(), sqlx::Error>;
+    async fn rows(&mut self, rows: Vec<Vec<CellValue>>) -> Result<(), sqlx::Error>;
+}
+
+/// Pull decoded rows off `stream` into `sink`, [`STREAM_BATCH`] at a time.
+pub(crate) async fn drain_rows<R>(
+    mut stream: impl Stream<Item = Result<R, sqlx::Error>> + Unpin,
+    decode: impl Fn(&R) -> Vec<CellValue>,
+    sink: &mut dyn RowSink,
+) -> Result<u64, sqlx::Error> {
+    let mut total = 0;
+    let mut batch = Vec::with_capacity(STREAM_BATCH);
+    while let Some(row) = stream.try_next().await? {
+        batch.push(decode(&row));
+        if batch.len() == STREAM_BATCH {
+            total += batch.len() as u64;
+            sink.rows(std::mem::take(&mut batch)).await?;
+        }
+    }
+    if !batch.is_empty() {
+        total += batch.len() as u64;
+        sink.rows(batch).await?;
+    }
+    Ok(total)
+}
+
+/// Run the statements behind `stream` (a multi-statement `fetch_many`) to the
+/// end. Rows go to `sink` as for [`drain_rows`], after the columns of the
+/// first one, and each statement's affected count is added up; the returned
+/// result holds no rows.
+pub(crate) async fn drain_results<Q, R: Row>(
+    sql: &str,
+    mut stream: impl Stream<Item = Result<Either<Q, R>, sqlx::Error>> + Unpin,
+    affected: impl Fn(&Q) -> u64,
+    decode: impl Fn(&R) -> Vec<CellValue>,
+    sink: &mut dyn RowSink,
+) -> Result<QueryResult, sqlx::Error> {
+    let started = Instant::now();
+    let mut columns = Vec::new();
+    let mut rows_affected = 0;
+    let mut batch = Vec::with_capacity(STREAM_BATCH);
+    while let Some(item) = stream.try_next().await? {
+        match item {
+            Either::Left(done) => rows_affected += affected(&done),
+            Either::Right(row) => {
+                if columns.is_empty() {
+                    columns = result_columns(row.columns());
+                    sink.columns(&columns).await?;
+                }
+                batch.push(decode(&row));
+                if batch.len() == STREAM_BATCH {
+                    sink.rows(std::mem::take(&mut batch)).await?;
+                }
+            }
+        }
+    }
+    if !batch.is_empty() {
+        sink.rows(batch).await?;
+    }
+    Ok(QueryResult {
+        sql: sql.to_string(),
+        columns,
+        rows: Default::default(),
+        rows_affected,
+        elapsed: started.elapsed(),
+    })
+}
+
+/// The statements inside `execute`, by execution id, with what `cancel`
+/// needs to reach each one. Runner statements overlap, so a cancel or a
+/// finishing statement only ever touches its own entry.
+pub(crate) struct Running<T>(Mutex<HashMap<u64, T>>);
+
+impl<T: Clone> Running<T> {
+    pub(crate) fn new() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+
+    pub(crate) fn get(&self, id: u64) -> Option<T> {
+        self.0.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Record `handle` for `id` until the returned guard drops, which also
+    /// covers a statement whose task is aborted mid-way.
+    pub(crate) fn track(&self, id: u64, handle: T) -> RunningGuard<'_, T> {
+        self.0.lock().unwrap().insert(id, handle);
+        RunningGuard { running: self, id }
+    }
+}
+
+pub(crate) struct RunningGuard<'a, T> {
+    running: &'a Running<T>,
+    id: u64,
+}
+
+impl<T> Drop for RunningGuard<'_, T> {
+    fn drop(&mut self) {
+        self.running.0.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// One LIMIT/OFFSET page of table rows.
+pub struct Page {
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+/// Engine-specific operations the DB worker needs. Each implementation owns its
+/// own pool; the worker only ever sees a `Box<dyn Driver>`.
+#[async_trait]
+pub trait Driver: Send + Sync {
+    fn kind(&self) -> DbKind;
+
+    /// Quote an identifier (table/column name) for this engine.
+    fn quote_ident(&self, name: &str) -> String {
+        self.kind().quote_ident(name)
+    }
+
+    /// Schema (MySQL: database) that catalog queries and `execute` are scoped to.
+    fn current_schema(&self) -> Option<String>;
+
+    /// Switch the active schema without reconnecting.
+    async fn use_schema(&self, name: &str) -> Result<(), sqlx::Error>;
+
+    async fn list_schemas(&self) -> Result<Vec<String>, sqlx::Error>;
+
+    /// Tables, views, routines, triggers and events in the current schema.
+    async fn list_objects(&self) -> Result<Vec<SchemaObject>, sqlx::Error>;
+
+    /// Source text of a routine, trigger or event.
+    async fn object_definition(&self, object: &SchemaObject) -> Result<String, sqlx::Error>;
+
+    /// Full column, index, foreign key and DDL details for the Schema screen.
+    async fn describe_table(&self, table: &str) -> Result<TableDetails, sqlx::Error>;
+
+    /// Fetch one page of `table`, ordered by `order_by` when given so paging is
+    /// stable. A `filter` restricts the rows, and its sort (if any) replaces
+    /// `order_by`.
+    async fn fetch_page(
+        &self,
+        table: &str,
+        order_by: Option<&str>,
+        filter: Option<&CompiledFilter>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Page, sqlx::Error>;
+
+    /// Feed every row of `source` to `sink` in batches of [`STREAM_BATCH`]
+    /// without collecting them; returns the number of rows.
+    async fn stream_rows(
+        &self,
+        source: &RowSource,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, sqlx::Error>;
+
+    /// Run `sql` once per row, binding the row's values, in one transaction
+    /// that is committed, or rolled back when `dry_run`. Postgres binds every
+    /// value as text, so its placeholders need casts to the column types.
+    async fn write_rows(
+        &self,
+        sql: &str,
+        rows: &[Vec<CellValue>],
+        dry_run: bool,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Run the statements of a grid save in one transaction, committed only
+    /// if each statement touched exactly one row; otherwise it is rolled back
+    /// with `RowNotFound`. Values are bound as for [`Driver::write_rows`].
+    async fn apply_changes(&self, statements: &[EditStatement]) -> Result<(), sqlx::Error>;
+
+    /// Cheap row-count estimate from catalog statistics (not exact).
+    async fn estimate_rows(&self, table: &str) -> Result<Option<u64>, sqlx::Error>;
+
+    /// Run arbitrary SQL, handing its rows to `sink` in batches of
+    /// [`STREAM_BATCH`] as they arrive. The result has the columns and the
+    /// total affected count, but no rows. `id` names this execution for
+    /// [`Driver::cancel`].
+    async fn execute(
+        &self,
+        id: u64,
+        sql: &str,
+        sink: &mut dyn RowSink,
+    ) -> Result<QueryResult, sqlx::Error>;
+
+    /// Ask the server to stop execution `id`, if it is still running. Other
+    /// statements running at the same time are left alone.
+    async fn cancel(&self, id: u64) -> Result<(), sqlx::Error>;
+
+    async fn close(&self);
+}
@@ -0,0 +1,233 @@
+//! Editing rows in place on the Data grid. Changed cells, rows marked for
+//! deletion and new rows are staged on the loaded page, then applied together
+//! as UPDATE, DELETE and INSERT statements that find each existing row by its
+//! primary or unique key.
+
+use super::{
+    export::sql_literal,
+    import::{CoerceError, coerce},
+    introstect::ColumnDetail,
+    types::CellValue,
+};
+use crate::{app::state::ColumnInfo, domain::connection::DbKind};
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EditError {
+    #[error("Editing needs a primary key or unique column on {0}")]
+    NoKey(String),
+
+    #[error("Row {0} has a NULL key, so it can't be matched")]
+    NullKey(usize),
+}
+
+/// One statement of a save, with its values bound to placeholders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditStatement {
+    pub sql: String,
+    pub params: Vec<CellValue>,
+    pub preview: String, // the same statement with literal values, for review
+}
+
+/// Changes staged on the loaded page until they are saved or discarded.
+/// Rows are indices into the page; new rows are appended after it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PendingChanges {
+    pub cells: BTreeMap<(usize, usize), CellValue>, // (row, column) → new value
+    pub deleted: BTreeSet<usize>,
+    pub new_from: Option<usize>, // first appended row, once there is one
+}
+
+impl PendingChanges {
+    pub fn is_new(&self, row: usize) -> bool {
+        self.new_from.is_some_and(|n| row >= n)
+    }
+
+    /// The staged value of a cell, if it was changed.
+    pub fn cell(&self, row: usize, col: usize) -> Option<&CellValue> {
+        self.cells.get(&(row, col))
+    }
+
+    /// Stage `value` for a cell whose loaded value is `current`; setting a
+    /// cell back to what was loaded unstages it.
+    pub fn stage(&mut self, row: usize, col: usize, value: CellValue, current: &CellValue) {
+        if &value == current && !self.is_new(row) {
+            self.cells.remove(&(row, col));
+        } else {
+            self.cells.insert((row, col), value);
+        }
+    }
+
+    /// Mark a row for deletion, or unmark it.
+    pub fn toggle_delete(&mut self, row: usize) {
+        if !self.deleted.remove(&row) {
+            self.deleted.insert(row);
+        }
+    }
+
+    /// Rows that saving would touch. New rows count once they have a value;
+    /// new rows marked for deletion are simply dropped.
+    pub fn count(&self) -> usize {
+        let rows: BTreeSet<usize> = self
+            .cells
+            .keys()
+            .map(|(r, _)| *r)
+            .chain(self.deleted.iter().copied())
+            .collect();
+        rows.iter()
+            .filter(|r| !(self.is_new(**r) && self.deleted.contains(r)))
+            .count()
+    }
+
+    /// Statements applying the changes to `table`: deletes first, so an insert
+    /// may reuse a deleted row's key, then updates, then inserts. `columns`
+    /// describe the page's columns in order, `key` indexes those identifying
+    /// a row, and `rows` is the page with any new rows.
+    pub fn statements(
+        &self,
+        kind: DbKind,
+        table: &str,
+        columns: &[ColumnInfo],
+        key: &[usize],
+        rows: &[Vec<CellValue>],
+    ) -> Result<Vec<EditStatement>, EditError> {
+        if key.is_empty() {
+            return Err(EditError::NoKey(table.to_string()));
+        }
+        let table = kind.quote_ident(table);
+        let mut out = Vec::new();
+
+        for &row in self.deleted.iter().filter(|r| !self.is_new(**r)) {
+            let mut s = Builder::new(kind, columns, format!("DELETE FROM {}", table));
+            s.key(key, row, &rows[row])?;
+            out.push(s.finish());
+        }
+
+        let changed: BTreeSet<usize> = self.cells.keys().map(|(r, _)| *r).collect();
+        let updated = changed
+            .iter()
+            .filter(|r| !self.is_new(**r) && !self.deleted.contains(r));
+        for &row in updated {
+            let mut s = Builder::new(kind, columns, format!("UPDATE {} SET ", table));
+            for (i, (col, value)) in self.row_cells(row).enumerate() {
+                if i > 0 {
+                    s.push(", ");
+                }
+                s.push(&format!("{} = ", kind.quote_ident(&columns[col].name)));
+                s.value(col, value);
+            }
+            s.key(key, row, &rows[row])?;
+            out.push(s.finish());
+        }
+
+        let inserted = changed
+            .iter()
+            .filter(|r| self.is_new(**r) && !self.deleted.contains(r));
+        for &row in inserted {
+            let names: Vec<String> = self
+                .row_cells(row)
+                .map(|(col, _)| kind.quote_ident(&columns[col].name))
+                .collect();
+            let head = format!("INSERT INTO {} ({}) VALUES (", table, names.join(", "));
+            let mut s = Builder::new(kind, columns, head);
+            for (i, (col, value)) in self.row_cells(row).enumerate() {
+                if i > 0 {
+                    s.push(", ");
+                }
+                s.value(col, value);
+            }
+            s.push(")");
+            out.push(s.finish());
+        }
+        Ok(out)
+    }
+
+    fn row_cells(&self, row: usize) -> impl Iterator<Item = (usize, &CellValue)> {
+        self.cells
+            .range((row, 0)..(row + 1, 0))
+            .map(|(&(_, col), v)| (col, v))
+    }
+}
+
+/// Builds a statement's SQL and its review text side by side.
+struct Builder<'a> {
+    kind: DbKind,
+    columns: &'a [ColumnInfo],
+    stmt: EditStatement,
+}
+
+impl<'a> Builder<'a> {
+    fn new(kind: DbKind, columns: &'a [ColumnInfo], head: String) -> Self {
+        Self {
+            kind,
+            columns,
+            stmt: EditStatement {
+                sql: head.clone(),
+                params: vec![],
+                preview: head,
+            },
+        }
+    }
+
+    fn push(&mut self, s: &str) {
+        self.stmt.sql.push_str(s);
+        self.stmt.preview.push_str(s);
+    }
+
+    /// A placeholder for `value`. Postgres binds every value as text, so the
+    /// placeholder is cast to the column's type.
+    fn value(&mut self, col: usize, value: &CellValue) {
+        let mut p = self.kind.placeholder(self.stmt.params.len() + 1);
+        if self.kind == DbKind::Postgres {
+            p = format!("CAST({} AS {})", p, self.columns[col].data_type);
+        }
+        self.stmt.sql.push_str(&p);
+        self.stmt.preview.push_str(&sql_literal(self.kind, value));
+        self.stmt.params.push(value.clone());
+    }
+
+    /// `WHERE` matching the row's loaded key values.
+    fn key(&mut self, key: &[usize], row: usize, values: &[CellValue]) -> Result<(), EditError> {
+        self.push(" WHERE ");
+        for (i, &col) in key.iter().enumerate() {
+            if values[col].is_null() {
+                return Err(EditError::NullKey(row + 1));
+            }
+            if i > 0 {
+                self.push(" AND ");
+            }
+            self.push(&format!(
+                "{} = ",
+                self.kind.quote_ident(&self.columns[col].name)
+            ));
+            self.value(col, &values[col]);
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> EditStatement {
+        self.stmt
+    }
+}
+
+/// Positions among `columns` of the `key` columns, as found by
+/// [`TableDetails::key_columns`](super::introstect::TableDetails::key_columns).
+/// Empty when there is no key or a key column is missing from the result,
+/// since the other columns alone can't tell rows apart.
+pub fn row_key(columns: &[ColumnInfo], key: &[String]) -> Vec<usize> {
+    key.iter()
+        .map(|k| columns.iter().position(|c| &c.name == k))
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+/// Text typed into the cell editor as a value for `column`; `None` is NULL.
+pub fn parse_cell(column: &ColumnInfo, raw: Option<&str>) -> Result<CellValue, CoerceError> {
+    let detail = ColumnDetail {
+        name: column.name.clone(),
+        data_type: column.data_type.clone(),
+        ..Default::default()
+    };
+    coerce(&detail, raw)
+}
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use crate::app::state::ColumnInfo;
+    use crate::db::{
+        edit::{EditError, PendingChanges, parse_cell, row_key},
+        types::CellValue,
+    };
+    use crate::domain::connection::DbKind;
+
+    fn col(name: &str, data_type: &str, key: Option<&str>) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            column_key: key.map(str::to_string),
+        }
+    }
+
+    fn columns() -> Vec<ColumnInfo> {
+        vec![
+            col("id", "integer", Some("PRI")),
+            col("title", "text", None),
+            col("born", "date", None),
+        ]
+    }
+
+    fn rows() -> Vec<Vec<CellValue>> {
+        vec![
+            vec![
+                CellValue::Int(1),
+                CellValue::Text("a".into()),
+                CellValue::Null,
+            ],
+            vec![
+                CellValue::Int(2),
+                CellValue::Text("b".into()),
+                CellValue::Null,
+            ],
+            vec![CellValue::Null, CellValue::Null, CellValue::Null], // new
+        ]
+    }
+
+    #[test]
+    fn rows_are_keyed_on_every_column_of_the_table_key() {
+        let key = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(row_key(&columns(), &key(&["id"])), [0]);
+        // A composite key needs all of its columns, in the key's order.
+        assert_eq!(row_key(&columns(), &key(&["born", "title"])), [2, 1]);
+        // Without one of them the rest can't tell rows apart.
+        assert!(row_key(&columns(), &key(&["title", "isbn"])).is_empty());
+        assert!(row_key(&columns(), &[]).is_empty());
+    }
+
+    #[test]
+    fn staging_a_loaded_value_again_unstages_it() {
+        let mut c = PendingChanges::default();
+        let loaded = CellValue::Text("a".into());
+        c.stage(0, 1, CellValue::Text("x".into()), &loaded);
+        assert_eq!(c.cell(0, 1), Some(&CellValue::Text("x".into())));
+        c.stage(0, 1, loaded.clone(), &loaded);
+        assert_eq!(c.cell(0, 1), None);
+        assert_eq!(c.count(), 0);
+
+        // New rows keep every value typed into them, and deleting one drops it.
+        c.new_from = Some(2);
+        c.stage(2, 1, CellValue::Null, &CellValue::Null);
+        assert_eq!(c.count(), 1);
+        c.toggle_delete(2);
+        c.toggle_delete(0);
+        assert_eq!(c.count(), 1);
+        c.toggle_delete(0);
+        assert_eq!(c.count(), 0);
+    }
+
+    #[test]
+    fn statements_delete_then_update_then_insert_by_key() {
+        let mut c = PendingChanges {
+            new_from: Some(2),
+            ..Default::default()
+        };
+        c.stage(1, 1, CellValue::Text("it's".into()), &rows()[1][1]);
+        c.stage(
+            1,
+            2,
+            parse_cell(&columns()[2], Some("2024-02-29")).unwrap(),
+            &CellValue::Null,
+        );
+        c.stage(2, 0, CellValue::Int(1), &CellValue::Null);
+        c.stage(2, 1, CellValue::Text("new".into()), &CellValue::Null);
+        c.toggle_delete(0);
+
+        let pg = c
+            .statements(DbKind::Postgres, "books", &columns(), &[0], &rows())
+            .unwrap();
+        let sql: Vec<&str> = pg.iter().map(|s| s.sql.as_str()).collect();
+        assert_eq!(
+            sql,
+            [
+                "DELETE FROM \"books\" WHERE \"id\" = CAST($1 AS integer)",
+                "UPDATE \"books\" SET \"title\" = CAST($1 AS text), \"born\" = CAST($2 AS date) \
+                 WHERE \"id\" = CAST($3 AS integer)",
+                "INSERT INTO \"books\" (\"id\", \"title\") \
+                 VALUES (CAST($1 AS integer), CAST($2 AS text))",
+            ]
+        );
+        assert_eq!(pg[1].params[2], CellValue::Int(2));
+
+        let my = c
+            .statements(DbKind::Mysql, "books", &columns(), &[0], &rows())
+            .unwrap();
+        assert_eq!(
+            my[1].sql,
+            "UPDATE `books` SET `title` = ?, `born` = ? WHERE `id` = ?"
+        );
+        assert_eq!(
+            my[1].preview,
+            "UPDATE `books` SET `title` = 'it''s', `born` = '2024-02-29' WHERE `id` = 2"
+        );
+    }
+
+    #[test]
+    fn statements_refuse_rows_they_cannot_match() {
+        let mut c = PendingChanges::default();
+        c.toggle_delete(2);
+        assert_eq!(
+            c.statements(DbKind::Sqlite, "books", &columns(), &[0], &rows()),
+            Err(EditError::NullKey(3))
+        );
+        assert_eq!(
+            c.statements(DbKind::Sqlite, "books", &columns(), &[], &rows()),
+            Err(EditError::NoKey("books".into()))
+        );
+        assert_eq!(
+            EditError::NoKey("books".into()).to_string(),
+            "Editing needs a primary key or unique column on books"
+        );
+    }
+}
@@ -0,0 +1,489 @@
+//! Writing result rows to a file, as a tabular text format or as SQL
+//! statements. Rows arrive in batches (from the screen or streamed from the
+//! driver) and are written as they come, so a full-table export never sits in
+//! memory.
+
+use super::{
+    driver::RowSource,
+    introstect::TableDetails,
+    types::{CellValue, ResultColumn},
+};
+use crate::domain::connection::DbKind;
+use std::io::{self, Write};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Tsv,
+    Json,
+    Ndjson,
+    Markdown,
+    Sql,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 6] = [
+        ExportFormat::Csv,
+        ExportFormat::Tsv,
+        ExportFormat::Json,
+        ExportFormat::Ndjson,
+        ExportFormat::Markdown,
+        ExportFormat::Sql,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Tsv => "TSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Ndjson => "NDJSON",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Sql => "SQL",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Sql => "sql",
+        }
+    }
+
+    /// Next format in `ALL`, wrapping.
+    pub fn cycle(self, forward: bool) -> ExportFormat {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        let n = Self::ALL.len();
+        let next = if forward { i + 1 } else { i + n - 1 };
+        Self::ALL[next % n]
+    }
+}
+
+/// When CSV/TSV fields are wrapped in double quotes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    #[default]
+    Minimal, // only fields containing the delimiter, a quote or a line break
+    Always, // every non-NULL field, so NULL stays distinguishable
+    Never,
+}
+
+impl QuoteStyle {
+    pub fn label(self) -> &'static str {
+        match self {
+            QuoteStyle::Minimal => "minimal",
+            QuoteStyle::Always => "always",
+            QuoteStyle::Never => "never",
+        }
+    }
+
+    pub fn cycle(self, forward: bool) -> QuoteStyle {
+        match (self, forward) {
+            (QuoteStyle::Minimal, true) | (QuoteStyle::Never, false) => QuoteStyle::Always,
+            (QuoteStyle::Always, true) | (QuoteStyle::Minimal, false) => QuoteStyle::Never,
+            (QuoteStyle::Never, true) | (QuoteStyle::Always, false) => QuoteStyle::Minimal,
+        }
+    }
+}
+
+/// Statement written per batch by the SQL format.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SqlStatement {
+    #[default]
+    Insert,
+    Replace, // Postgres has no REPLACE; it gets an upsert instead
+    Upsert,  // ON DUPLICATE KEY UPDATE / ON CONFLICT … DO UPDATE
+}
+
+impl SqlStatement {
+    pub fn label(self) -> &'static str {
+        match self {
+            SqlStatement::Insert => "INSERT",
+            SqlStatement::Replace => "REPLACE",
+            SqlStatement::Upsert => "UPSERT",
+        }
+    }
+
+    pub fn cycle(self, forward: bool) -> SqlStatement {
+        match (self, forward) {
+            (SqlStatement::Insert, true) | (SqlStatement::Upsert, false) => SqlStatement::Replace,
+            (SqlStatement::Replace, true) | (SqlStatement::Insert, false) => SqlStatement::Upsert,
+            (SqlStatement::Upsert, true) | (SqlStatement::Replace, false) => SqlStatement::Insert,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub header: bool,
+    pub delimiter: char, // CSV only; TSV always uses a tab
+    pub null: String,    // written for NULL in CSV, TSV and Markdown
+    pub quote: QuoteStyle,
+
+    // SQL only
+    pub table: String, // table the statements target
+    pub statement: SqlStatement,
+    pub batch_size: usize, // rows per statement
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            header: true,
+            delimiter: ',',
+            null: String::new(),
+            quote: QuoteStyle::Minimal,
+            table: String::new(),
+            statement: SqlStatement::Insert,
+            batch_size: 100,
+        }
+    }
+}
+
+/// What the SQL format needs to know about the target table, from the
+/// Schema introspection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlTable {
+    pub kind: DbKind,
+    pub name: String,
+    pub generated: Vec<String>, // skipped: the engine computes them
+    pub key: Vec<String>,       // conflict target for upserts
+}
+
+impl SqlTable {
+    pub fn new(kind: DbKind, details: &TableDetails) -> Self {
+        Self {
+            kind,
+            name: details.table.clone(),
+            generated: details
+                .columns
+                .iter()
+                .filter(|c| c.is_generated())
+                .map(|c| c.name.clone())
+                .collect(),
+            key: details.key_columns(),
+        }
+    }
+}
+
+/// What to export: rows already on screen, or rows streamed from the database.
+#[derive(Debug, Clone)]
+pub enum ExportSource {
+    Rows {
+        columns: Vec<ResultColumn>,
+        rows: Vec<Vec<CellValue>>,
+    },
+    Stream(RowSource),
+}
+
+/// Writes rows to `out` in the chosen format. Call [`ExportWriter::finish`] to
+/// close the document (JSON needs its closing bracket) and flush.
+pub struct ExportWriter<W: Write> {
+    out: W,
+    options: ExportOptions,
+    columns: Vec<String>,
+    rows: u64,
+
+    // SQL: target table, indices of the columns written, rows awaiting a statement
+    table: Option<SqlTable>,
+    keep: Vec<usize>,
+    pending: Vec<Vec<CellValue>>,
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// Start the document, writing the header row where the format has one.
+    pub fn new(mut out: W, options: ExportOptions, columns: &[ResultColumn]) -> io::Result<Self> {
+        let columns: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+        match options.format {
+            ExportFormat::Csv | ExportFormat::Tsv if options.header => {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .map(|c| delimited_field(c, &options))
+                    .collect();
+                writeln!(out, "{}", fields.join(&delimiter(&options).to_string()))?;
+            }
+            ExportFormat::Json => write!(out, "[")?,
+            ExportFormat::Markdown => {
+                let names: Vec<String> = columns.iter().map(|c| markdown_cell(c)).collect();
+                writeln!(out, "| {} |", names.join(" | "))?;
+                writeln!(out, "|{}", " --- |".repeat(columns.len()))?;
+            }
+            _ => {}
+        }
+        Ok(Self {
+            keep: (0..columns.len()).collect(),
+            out,
+            options,
+            columns,
+            rows: 0,
+            table: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Target `table` with SQL statements, leaving out its generated columns.
+    /// Fails when an upsert is asked for but the table has no key to conflict on.
+    pub fn for_table(mut self, table: SqlTable) -> io::Result<Self> {
+        let upsert = match self.options.statement {
+            SqlStatement::Insert => false,
+            SqlStatement::Replace => table.kind == DbKind::Postgres,
+            SqlStatement::Upsert => table.kind != DbKind::Mysql,
+        };
+        if upsert && table.key.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} needs a primary key or unique index on {}",
+                    self.options.statement.label(),
+                    table.name
+                ),
+            ));
+        }
+        self.keep = (0..self.columns.len())
+            .filter(|&i| !table.generated.contains(&self.columns[i]))
+            .collect();
+        self.table = Some(table);
+        Ok(self)
+    }
+
+    pub fn write_row(&mut self, row: &[CellValue]) -> io::Result<()> {
+        let o = &self.options;
+        match o.format {
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                let fields: Vec<String> = row
+                    .iter()
+                    .map(|v| match v {
+                        CellValue::Null => o.null.clone(),
+                        v => delimited_field(&plain_text(v), o),
+                    })
+                    .collect();
+                writeln!(self.out, "{}", fields.join(&delimiter(o).to_string()))?;
+            }
+            ExportFormat::Json | ExportFormat::Ndjson => {
+                let object = json_object(&self.columns, row);
+                if o.format == ExportFormat::Ndjson {
+                    writeln!(self.out, "{}", object)?;
+                } else {
+                    let sep = if self.rows == 0 { "" } else { "," };
+                    write!(self.out, "{}\n  {}", sep, object)?;
+                }
+            }
+            ExportFormat::Markdown => {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|v| match v {
+                        CellValue::Null => markdown_cell(&o.null),
+                        v => markdown_cell(&plain_text(v)),
+                    })
+                    .collect();
+                writeln!(self.out, "| {} |", cells.join(" | "))?;
+            }
+            ExportFormat::Sql => {
+                self.pending
+                    .push(self.keep.iter().map(|&i| row[i].clone()).collect());
+                if self.pending.len() >= self.options.batch_size.max(1) {
+                    self.write_statement()?;
+                }
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// One multi-row statement for the pending rows.
+    fn write_statement(&mut self) -> io::Result<()> {
+        let Some(t) = self.table.as_ref() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "SQL export needs a target table",
+            ));
+        };
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let names: Vec<&str> = self
+            .keep
+            .iter()
+            .map(|&i| self.columns[i].as_str())
+            .collect();
+        let quoted: Vec<String> = names.iter().map(|n| t.kind.quote_ident(n)).collect();
+        let replace = self.options.statement == SqlStatement::Replace && t.kind != DbKind::Postgres;
+        writeln!(
+            self.out,
+            "{} INTO {} ({}) VALUES",
+            if replace { "REPLACE" } else { "INSERT" },
+            t.kind.quote_ident(&t.name),
+            quoted.join(", ")
+        )?;
+        let tuples: Vec<String> = self
+            .pending
+            .drain(..)
+            .map(|row| {
+                let values: Vec<String> = row.iter().map(|v| sql_literal(t.kind, v)).collect();
+                format!("  ({})", values.join(", "))
+            })
+            .collect();
+        write!(self.out, "{}", tuples.join(",\n"))?;
+
+        if self.options.statement != SqlStatement::Insert && !replace {
+            write!(self.out, "\n{}", upsert_clause(t.kind, &names, &t.key))?;
+        }
+        writeln!(self.out, ";")
+    }
+
+    /// Close the document and flush; returns the number of rows written.
+    pub fn finish(mut self) -> io::Result<u64> {
+        if self.options.format == ExportFormat::Sql {
+            self.write_statement()?;
+        }
+        if self.options.format == ExportFormat::Json {
+            let close = if self.rows == 0 { "]\n" } else { "\n]\n" };
+            write!(self.out, "{}", close)?;
+        }
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+}
+
+fn delimiter(o: &ExportOptions) -> char {
+    if o.format == ExportFormat::Tsv {
+        '\t'
+    } else {
+        o.delimiter
+    }
+}
+
+fn delimited_field(s: &str, o: &ExportOptions) -> String {
+    let needs_quotes = match o.quote {
+        QuoteStyle::Always => true,
+        QuoteStyle::Never => false,
+        QuoteStyle::Minimal => s.contains([delimiter(o), '"', '\n', '\r']),
+    };
+    if needs_quotes {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// `ON DUPLICATE KEY UPDATE …` (MySQL) or `ON CONFLICT (key) DO …` updating
+/// every column in `names` that isn't part of `key`.
+pub(crate) fn upsert_clause(kind: DbKind, names: &[&str], key: &[String]) -> String {
+    let q = |c: &str| kind.quote_ident(c);
+    let updates: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|n| !key.iter().any(|k| k == n))
+        .collect();
+    if kind == DbKind::Mysql {
+        // Assigning a key column to itself keeps the row as it is.
+        let set: Vec<String> = if updates.is_empty() {
+            names
+                .iter()
+                .take(1)
+                .map(|n| format!("{0} = {0}", q(n)))
+                .collect()
+        } else {
+            updates
+                .iter()
+                .map(|n| format!("{0} = VALUES({0})", q(n)))
+                .collect()
+        };
+        return format!("ON DUPLICATE KEY UPDATE {}", set.join(", "));
+    }
+
+    let target: Vec<String> = key.iter().map(|k| q(k)).collect();
+    if updates.is_empty() {
+        return format!("ON CONFLICT ({}) DO NOTHING", target.join(", "));
+    }
+    let set: Vec<String> = updates
+        .iter()
+        .map(|n| format!("{0} = EXCLUDED.{0}", q(n)))
+        .collect();
+    format!(
+        "ON CONFLICT ({}) DO UPDATE SET {}",
+        target.join(", "),
+        set.join(", ")
+    )
+}
+
+/// `v` as a SQL literal for `kind`. Text-like values (dates, JSON …) become
+/// quoted strings, which every engine converts on assignment to the column.
+pub(crate) fn sql_literal(kind: DbKind, v: &CellValue) -> String {
+    match v {
+        CellValue::Null => "NULL".to_string(),
+        CellValue::Bool(b) if kind == DbKind::Postgres => {
+            if *b { "TRUE" } else { "FALSE" }.to_string()
+        }
+        CellValue::Bool(b) => u8::from(*b).to_string(),
+        CellValue::Int(n) => n.to_string(),
+        CellValue::UInt(n) => n.to_string(),
+        CellValue::Float(f) if f.is_finite() => f.to_string(),
+        CellValue::Decimal(d) => d.clone(),
+        CellValue::Bytes(b) => {
+            let hex: String = b.iter().map(|b| format!("{:02X}", b)).collect();
+            match kind {
+                DbKind::Postgres => format!("'\\x{}'::bytea", hex),
+                _ => format!("X'{}'", hex),
+            }
+        }
+        v => {
+            let mut s = v.to_string().replace('\'', "''");
+            // MySQL treats backslashes in strings as escapes by default.
+            if kind == DbKind::Mysql {
+                s = s.replace('\\', "\\\\").replace('\0', "\\0");
+            }
+            format!("'{}'", s)
+        }
+    }
+}
+
+/// Full text of a value. Unlike `Display`, binary values are never truncated.
+pub(crate) fn plain_text(v: &CellValue) -> String {
+    match v {
+        CellValue::Bytes(b) => {
+            let hex: String = b.iter().map(|b| format!("{:02X}", b)).collect();
+            format!("0x{}", hex)
+        }
+        v => v.to_string(),
+    }
+}
+
+/// One row as a JSON object with keys in column order. Numbers and booleans
+/// stay native, JSON columns are embedded as-is and everything else is text;
+/// decimals are kept as strings so no precision is lost.
+fn json_object(columns: &[String], row: &[CellValue]) -> String {
+    let fields: Vec<String> = columns
+        .iter()
+        .zip(row)
+        .map(|(name, v)| {
+            let value = match v {
+                CellValue::Null => serde_json::Value::Null,
+                CellValue::Bool(b) => (*b).into(),
+                CellValue::Int(n) => (*n).into(),
+                CellValue::UInt(n) => (*n).into(),
+                CellValue::Float(f) => serde_json::Number::from_f64(*f)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or_else(|| f.to_string().into()),
+                CellValue::Json(s) => serde_json::from_str(s).unwrap_or_else(|_| s.clone().into()),
+                v => plain_text(v).into(),
+            };
+            format!("{}:{}", serde_json::Value::from(name.as_str()), value)
+        })
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::{
+        export::{
+            ExportFormat, ExportOptions, ExportWriter, QuoteStyle, SqlStatement, SqlTable,
+            sql_literal,
+        },
+        types::{CellValue, ResultColumn},
+    };
+    use crate::domain::connection::DbKind;
+    use sqlx::types::chrono::NaiveDate;
+
+    fn columns() -> Vec<ResultColumn> {
+        ["id", "note", "meta", "blob"]
+            .iter()
+            .map(|n| ResultColumn {
+                name: n.to_string(),
+                type_name: String::new(),
+            })
+            .collect()
+    }
+
+    fn rows() -> Vec<Vec<CellValue>> {
+        vec![
+            vec![
+                CellValue::Int(1),
+                CellValue::Text("a, \"b\"\nc|d".into()),
+                CellValue::Json("{\"k\": [1, 2]}".into()),
+                CellValue::Bytes(vec![0xCA, 0xFE]),
+            ],
+            vec![
+                CellValue::UInt(2),
+                CellValue::Null,
+                CellValue::Null,
+                CellValue::Decimal("10.50".into()),
+            ],
+        ]
+    }
+
+    fn export(options: ExportOptions) -> String {
+        let mut out = Vec::new();
+        let mut w = ExportWriter::new(&mut out, options, &columns()).unwrap();
+        for row in rows() {
+            w.write_row(&row).unwrap();
+        }
+        assert_eq!(w.finish().unwrap(), 2);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn delimited_formats_quote_and_spell_null_as_asked() {
+        let csv = export(ExportOptions {
+            null: "\\N".into(),
+            ..Default::default()
+        });
+        assert_eq!(
+            csv,
+            "id,note,meta,blob\n\
+             1,\"a, \"\"b\"\"\nc|d\",\"{\"\"k\"\": [1, 2]}\",0xCAFE\n\
+             2,\\N,\\N,10.50\n"
+        );
+
+        let csv = export(ExportOptions {
+            header: false,
+            delimiter: ';',
+            quote: QuoteStyle::Always,
+            ..Default::default()
+        });
+        assert!(csv.starts_with("\"1\";"));
+        assert!(csv.ends_with("\"2\";;;\"10.50\"\n"));
+
+        let tsv = export(ExportOptions {
+            format: ExportFormat::Tsv,
+            quote: QuoteStyle::Never,
+            ..Default::default()
+        });
+        assert!(tsv.starts_with("id\tnote\tmeta\tblob\n1\ta, \"b\"\nc|d\t"));
+    }
+
+    #[test]
+    fn json_keeps_column_order_and_native_types() {
+        let json = export(ExportOptions {
+            format: ExportFormat::Json,
+            ..Default::default()
+        });
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["meta"]["k"][1], 2);
+        assert_eq!(parsed[0]["blob"], "0xCAFE");
+        assert_eq!(parsed[1]["note"], serde_json::Value::Null);
+        assert_eq!(parsed[1]["blob"], "10.50");
+        assert!(json.starts_with("[\n  {\"id\":1,\"note\":"));
+
+        let ndjson = export(ExportOptions {
+            format: ExportFormat::Ndjson,
+            ..Default::default()
+        });
+        let lines: Vec<_> = ndjson.lines().collect();
+        assert_eq!(
+            lines[1],
+            r#"{"id":2,"note":null,"meta":null,"blob":"10.50"}"#
+        );
+
+        let mut empty = Vec::new();
+        let w = ExportWriter::new(
+            &mut empty,
+            ExportOptions {
+                format: ExportFormat::Json,
+                ..Default::default()
+            },
+            &columns(),
+        )
+        .unwrap();
+        w.finish().unwrap();
+        assert_eq!(String::from_utf8(empty).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_and_line_breaks() {
+        let md = export(ExportOptions {
+            format: ExportFormat::Markdown,
+            null: "NULL".into(),
+            ..Default::default()
+        });
+        let lines: Vec<_> = md.lines().collect();
+        assert_eq!(lines[0], "| id | note | meta | blob |");
+        assert_eq!(lines[1], "| --- | --- | --- | --- |");
+        assert!(lines[2].starts_with(r#"| 1 | a, "b"<br>c\|d | "#));
+        assert_eq!(lines[3], "| 2 | NULL | NULL | 10.50 |");
+    }
+
+    fn sql_export(kind: DbKind, statement: SqlStatement, key: &[&str]) -> std::io::Result<String> {
+        let options = ExportOptions {
+            format: ExportFormat::Sql,
+            statement,
+            batch_size: 1,
+            ..Default::default()
+        };
+        let table = SqlTable {
+            kind,
+            name: "notes".into(),
+            generated: vec!["meta".into()],
+            key: key.iter().map(|k| k.to_string()).collect(),
+        };
+        let mut out = Vec::new();
+        let mut w = ExportWriter::new(&mut out, options, &columns())?.for_table(table)?;
+        for row in rows() {
+            w.write_row(&row)?;
+        }
+        w.finish()?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn sql_literals_escape_per_engine() {
+        let text = CellValue::Text("it's a \\ path".into());
+        assert_eq!(sql_literal(DbKind::Mysql, &text), r"'it''s a \\ path'");
+        assert_eq!(sql_literal(DbKind::Postgres, &text), r"'it''s a \ path'");
+        let bytes = CellValue::Bytes(vec![0x00, 0xFF]);
+        assert_eq!(sql_literal(DbKind::Sqlite, &bytes), "X'00FF'");
+        assert_eq!(sql_literal(DbKind::Postgres, &bytes), r"'\x00FF'::bytea");
+        assert_eq!(sql_literal(DbKind::Mysql, &CellValue::Bool(true)), "1");
+        assert_eq!(
+            sql_literal(DbKind::Postgres, &CellValue::Bool(true)),
+            "TRUE"
+        );
+        assert_eq!(sql_literal(DbKind::Postgres, &CellValue::Null), "NULL");
+        let date = CellValue::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(sql_literal(DbKind::Mysql, &date), "'2024-02-29'");
+        let json = CellValue::Json(r#"{"a": "b'c"}"#.into());
+        assert_eq!(sql_literal(DbKind::Sqlite, &json), r#"'{"a": "b''c"}'"#);
+    }
+
+    #[test]
+    fn sql_statements_batch_skip_generated_columns_and_upsert_on_key() {
+        let mut out = Vec::new();
+        let options = ExportOptions {
+            format: ExportFormat::Sql,
+            ..Default::default()
+        };
+        let table = SqlTable {
+            kind: DbKind::Sqlite,
+            name: "notes".into(),
+            ..Default::default()
+        };
+        let mut w = ExportWriter::new(&mut out, options, &columns())
+            .unwrap()
+            .for_table(table)
+            .unwrap();
+        for row in rows() {
+            w.write_row(&row).unwrap();
+        }
+        w.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "INSERT INTO \"notes\" (\"id\", \"note\", \"meta\", \"blob\") VALUES\n  \
+             (1, 'a, \"b\"\nc|d', '{\"k\": [1, 2]}', X'CAFE'),\n  \
+             (2, NULL, NULL, 10.50);\n"
+        );
+
+        let pg = sql_export(DbKind::Postgres, SqlStatement::Upsert, &["id"]).unwrap();
+        assert!(
+            pg.starts_with("INSERT INTO \"notes\" (\"id\", \"note\", \"blob\") VALUES\n  (1, ")
+        );
+        assert!(pg.ends_with(
+            "  (2, NULL, 10.50)\nON CONFLICT (\"id\") DO UPDATE SET \
+             \"note\" = EXCLUDED.\"note\", \"blob\" = EXCLUDED.\"blob\";\n"
+        ));
+        assert_eq!(pg.matches("INSERT INTO").count(), 2);
+
+        // Postgres has no REPLACE, so it upserts instead.
+        let pg = sql_export(DbKind::Postgres, SqlStatement::Replace, &["id"]).unwrap();
+        assert!(pg.contains("ON CONFLICT"));
+        let lite = sql_export(DbKind::Sqlite, SqlStatement::Replace, &[]).unwrap();
+        assert!(lite.starts_with("REPLACE INTO \"notes\""));
+
+        let my = sql_export(DbKind::Mysql, SqlStatement::Upsert, &[]).unwrap();
+        assert!(my.contains(
+            "ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `note` = VALUES(`note`), \
+             `blob` = VALUES(`blob`);"
+        ));
+        let err = sql_export(DbKind::Postgres, SqlStatement::Upsert, &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "UPSERT needs a primary key or unique index on notes"
+        );
+    }
+}
@@ -0,0 +1,563 @@
+//! Loading CSV, TSV or NDJSON files into an existing table. The file is read
+//! record by record, each mapped file column is coerced to its table column's
+//! type, and rows are written in batches, one transaction per batch.
+
+use super::{
+    export::upsert_clause,
+    introstect::{ColumnDetail, TableDetails},
+    types::CellValue,
+};
+use crate::domain::{
+    connection::DbKind,
+    filter::{ValueKind, value_kind},
+};
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::io::{self, BufRead};
+use thiserror::Error;
+
+/// Records read for the preview shown before an import runs.
+pub const PREVIEW_ROWS: usize = 10;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    #[default]
+    Csv,
+    Tsv,
+    Ndjson,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [ImportFormat::Csv, ImportFormat::Tsv, ImportFormat::Ndjson];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "CSV",
+            ImportFormat::Tsv => "TSV",
+            ImportFormat::Ndjson => "NDJSON",
+        }
+    }
+
+    /// Next format in `ALL`, wrapping.
+    pub fn cycle(self, forward: bool) -> ImportFormat {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        let n = Self::ALL.len();
+        let next = if forward { i + 1 } else { i + n - 1 };
+        Self::ALL[next % n]
+    }
+}
+
+/// What happens to a row whose key already exists.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    #[default]
+    Insert, // the batch fails on a duplicate key
+    Upsert, // the existing row is updated
+}
+
+impl ImportMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportMode::Insert => "insert",
+            ImportMode::Upsert => "upsert",
+        }
+    }
+
+    pub fn toggle(self) -> ImportMode {
+        match self {
+            ImportMode::Insert => ImportMode::Upsert,
+            ImportMode::Upsert => ImportMode::Insert,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    pub header: bool,    // CSV/TSV: the first record names the columns
+    pub delimiter: char, // CSV only; TSV always uses a tab
+    pub null: String,    // CSV/TSV: an unquoted field equal to this is NULL
+    pub mode: ImportMode,
+    pub batch_size: usize, // rows per transaction
+    pub dry_run: bool,     // run every batch, then roll it back
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            format: ImportFormat::Csv,
+            header: true,
+            delimiter: ',',
+            null: String::new(),
+            mode: ImportMode::Insert,
+            batch_size: 500,
+            dry_run: false,
+        }
+    }
+}
+
+impl ImportOptions {
+    fn delimiter(&self) -> char {
+        if self.format == ImportFormat::Tsv {
+            '\t'
+        } else {
+            self.delimiter
+        }
+    }
+}
+
+/// One file record: a field per file column, `None` for NULL.
+pub type Record = Vec<Option<String>>;
+
+/// A file value that doesn't fit its column's type.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{column} expects {expected}, got '{value}'")]
+pub struct CoerceError {
+    pub column: String,
+    pub expected: &'static str,
+    pub value: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Db(#[from] sqlx::Error),
+
+    #[error("line {line}: {message}")]
+    Parse { line: u64, message: String },
+
+    #[error("line {line}: {source}")]
+    Value { line: u64, source: CoerceError },
+
+    #[error("No file columns are mapped to table columns")]
+    NothingMapped,
+
+    #[error("Upsert needs a primary key or unique index on {0}")]
+    NoKey(String),
+
+    #[error("Upsert needs the key column(s) {} mapped", .0.join(", "))]
+    KeyNotMapped(Vec<String>),
+}
+
+/// Reads records from a CSV, TSV or NDJSON file. The column names come from
+/// the CSV header, `column1`, `column2` … without one, or the keys of the
+/// first NDJSON object (in sorted order).
+pub struct ImportReader<R> {
+    input: R,
+    options: ImportOptions,
+    columns: Vec<String>,
+    first: Option<Record>, // read to learn the columns, not yet handed out
+    line: u64,             // lines consumed so far
+    record_line: u64,      // line the last record started on
+    bytes: u64,            // bytes consumed so far, for progress
+}
+
+impl<R: BufRead> ImportReader<R> {
+    pub fn new(input: R, options: ImportOptions) -> Result<Self, ImportError> {
+        let mut reader = Self {
+            input,
+            options,
+            columns: Vec::new(),
+            first: None,
+            line: 0,
+            record_line: 0,
+            bytes: 0,
+        };
+        if reader.options.format == ImportFormat::Ndjson {
+            if let Some(object) = reader.read_object()? {
+                reader.columns = object.keys().cloned().collect();
+                reader.first = Some(reader.object_record(object));
+            }
+        } else if let Some(first) = reader.read_delimited()? {
+            if reader.options.header {
+                reader.columns = first.into_iter().map(Option::unwrap_or_default).collect();
+            } else {
+                reader.columns = (1..=first.len()).map(|i| format!("column{}", i)).collect();
+                reader.first = Some(first);
+            }
+        }
+        Ok(reader)
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Line the last record returned started on, for error messages.
+    pub fn record_line(&self) -> u64 {
+        self.record_line
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn next_record(&mut self) -> Result<Option<Record>, ImportError> {
+        if let Some(first) = self.first.take() {
+            return Ok(Some(first));
+        }
+        if self.options.format == ImportFormat::Ndjson {
+            Ok(self.read_object()?.map(|o| self.object_record(o)))
+        } else {
+            self.read_delimited()
+        }
+    }
+
+    /// Next non-blank line, or None at the end of the file.
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            let n = self.input.read_line(&mut buf)?;
+            if n == 0 {
+                return Ok(None);
+            }
+            self.bytes += n as u64;
+            self.line += 1;
+            if self.line == 1 && buf.starts_with('\u{feff}') {
+                buf.remove(0);
+            }
+            if !buf.trim_end_matches(['\r', '\n']).is_empty() {
+                self.record_line = self.line;
+                return Ok(Some(buf));
+            }
+        }
+    }
+
+    /// One delimited record. Quoted fields may hold delimiters, doubled
+    /// quotes and line breaks.
+    fn read_delimited(&mut self) -> Result<Option<Record>, ImportError> {
+        let Some(mut buf) = self.read_line()? else {
+            return Ok(None);
+        };
+        let delimiter = self.options.delimiter();
+        let null = self.options.null.clone();
+        let finish = |field: &mut String, quoted: bool| {
+            let field = std::mem::take(field);
+            (quoted || field != null).then_some(field)
+        };
+
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false; // the current field was quoted
+        let mut in_quotes = false;
+        loop {
+            let mut chars = buf.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c != '"' {
+                        field.push(c);
+                    } else if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        in_quotes = false;
+                    }
+                } else if c == '"' && field.is_empty() && !quoted {
+                    in_quotes = true;
+                    quoted = true;
+                } else if c == delimiter {
+                    record.push(finish(&mut field, quoted));
+                    quoted = false;
+                } else if c != '\r' && c != '\n' {
+                    field.push(c);
+                }
+            }
+            if !in_quotes {
+                break;
+            }
+            // The quoted field carries on past the line break.
+            buf.clear();
+            let n = self.input.read_line(&mut buf)?;
+            if n == 0 {
+                return Err(ImportError::Parse {
+                    line: self.record_line,
+                    message: "unterminated quoted field".to_string(),
+                });
+            }
+            self.bytes += n as u64;
+            self.line += 1;
+        }
+        record.push(finish(&mut field, quoted));
+        Ok(Some(record))
+    }
+
+    fn read_object(
+        &mut self,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, ImportError> {
+        let Some(buf) = self.read_line()? else {
+            return Ok(None);
+        };
+        let parse_error = |message: String| ImportError::Parse {
+            line: self.record_line,
+            message,
+        };
+        match serde_json::from_str(buf.trim()) {
+            Ok(serde_json::Value::Object(object)) => Ok(Some(object)),
+            Ok(_) => Err(parse_error("expected a JSON object".to_string())),
+            Err(e) => Err(parse_error(e.to_string())),
+        }
+    }
+
+    /// An object's values in column order. Strings are taken as they are;
+    /// nested objects and arrays keep their JSON text.
+    fn object_record(&self, object: serde_json::Map<String, serde_json::Value>) -> Record {
+        self.columns
+            .iter()
+            .map(|c| match object.get(c) {
+                None | Some(serde_json::Value::Null) => None,
+                Some(serde_json::Value::String(s)) => Some(s.clone()),
+                Some(v) => Some(v.to_string()),
+            })
+            .collect()
+    }
+}
+
+/// What the import dialog shows once a file is picked: the file's columns
+/// and first records, and the target table.
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    pub kind: DbKind,
+    pub table: TableDetails,
+    pub columns: Vec<String>,
+    pub rows: Vec<Record>,
+}
+
+impl ImportPreview {
+    /// File column feeding each table column. With a header, columns match by
+    /// name, ignoring case, `_`, `-` and spaces; without one, by position.
+    /// Generated columns are never fed.
+    pub fn auto_map(&self, header: bool) -> Vec<Option<usize>> {
+        let norm = |s: &str| -> String {
+            s.chars()
+                .filter(|c| !matches!(c, '_' | '-' | ' '))
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        self.table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                if col.is_generated() {
+                    None
+                } else if header {
+                    self.columns.iter().position(|f| norm(f) == norm(&col.name))
+                } else {
+                    (i < self.columns.len()).then_some(i)
+                }
+            })
+            .collect()
+    }
+}
+
+/// `raw` as a value for `column`, checked against its declared type.
+/// Integers, numbers, booleans, dates and times, JSON and binary values
+/// (`0x`-prefixed hex, or the text's bytes) are parsed; anything else is
+/// passed through as text for the engine to judge.
+pub fn coerce(column: &ColumnDetail, raw: Option<&str>) -> Result<CellValue, CoerceError> {
+    let Some(raw) = raw else {
+        return Ok(CellValue::Null);
+    };
+    let invalid = |expected| CoerceError {
+        column: column.name.clone(),
+        expected,
+        value: raw.to_string(),
+    };
+    let t = column.data_type.to_lowercase();
+    let v = raw.trim();
+
+    if ["blob", "bytea", "binary"].iter().any(|w| t.contains(w)) {
+        let Some(hex) = v.strip_prefix("0x").or_else(|| v.strip_prefix("\\x")) else {
+            return Ok(CellValue::Bytes(raw.as_bytes().to_vec()));
+        };
+        return decode_hex(hex)
+            .map(CellValue::Bytes)
+            .ok_or_else(|| invalid("hex bytes"));
+    }
+    if t.contains("json") {
+        return serde_json::from_str::<serde_json::Value>(v)
+            .map(|_| CellValue::Json(raw.to_string()))
+            .map_err(|_| invalid("JSON"));
+    }
+
+    Ok(match value_kind(&t) {
+        ValueKind::Int => match v.parse::<i64>() {
+            Ok(n) => CellValue::Int(n),
+            Err(_) => CellValue::UInt(v.parse().map_err(|_| invalid("an integer"))?),
+        },
+        ValueKind::Float => {
+            let n: f64 = v.parse().map_err(|_| invalid("a number"))?;
+            // Keep exact decimals as written rather than round-tripping a float.
+            if t.contains("decimal") || t.contains("numeric") {
+                CellValue::Decimal(v.to_string())
+            } else {
+                CellValue::Float(n)
+            }
+        }
+        ValueKind::Bool => CellValue::Bool(match v.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "1" => true,
+            "false" | "f" | "no" | "0" => false,
+            _ => return Err(invalid("true or false")),
+        }),
+        ValueKind::Temporal => temporal(&t, v).ok_or_else(|| invalid(temporal_hint(&t)))?,
+        ValueKind::Text | ValueKind::Other => CellValue::Text(raw.to_string()),
+    })
+}
+
+const DATETIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+];
+
+fn temporal(t: &str, v: &str) -> Option<CellValue> {
+    if t.contains("zone") || t.contains("tz") {
+        // Offsets come in too many spellings; the engine parses these.
+        return Some(CellValue::Text(v.to_string()));
+    }
+    if t.contains("datetime") || t.contains("timestamp") {
+        DATETIME_FORMATS
+            .iter()
+            .find_map(|f| NaiveDateTime::parse_from_str(v, f).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                    .ok()?
+                    .and_hms_opt(0, 0, 0)
+            })
+            .map(CellValue::DateTime)
+    } else if t.contains("date") {
+        NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .ok()
+            .map(CellValue::Date)
+    } else if t.contains("time") {
+        ["%H:%M:%S%.f", "%H:%M"]
+            .iter()
+            .find_map(|f| NaiveTime::parse_from_str(v, f).ok())
+            .map(CellValue::Time)
+    } else {
+        v.parse().ok().map(CellValue::Int) // year
+    }
+}
+
+fn temporal_hint(t: &str) -> &'static str {
+    if t.contains("datetime") || t.contains("timestamp") {
+        "a date and time (YYYY-MM-DD HH:MM:SS)"
+    } else if t.contains("date") {
+        "a date (YYYY-MM-DD)"
+    } else if t.contains("time") {
+        "a time (HH:MM:SS)"
+    } else {
+        "a year"
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A ready-to-run import: which file column feeds which table column, and
+/// the statement each row is written with.
+#[derive(Debug, Clone)]
+pub struct ImportPlan {
+    pub kind: DbKind,
+    pub table: String,
+    pub targets: Vec<ColumnDetail>, // columns written, in statement order
+    pub sources: Vec<usize>,        // file column feeding each target
+    pub key: Vec<String>,           // conflict target for upserts
+    pub mode: ImportMode,
+}
+
+impl ImportPlan {
+    /// `mapping` holds the file column for each of the table's columns, as
+    /// from [`ImportPreview::auto_map`]. Unmapped columns get their defaults.
+    pub fn new(
+        preview: &ImportPreview,
+        mapping: &[Option<usize>],
+        mode: ImportMode,
+    ) -> Result<Self, ImportError> {
+        let (targets, sources): (Vec<ColumnDetail>, Vec<usize>) = preview
+            .table
+            .columns
+            .iter()
+            .zip(mapping)
+            .filter_map(|(col, src)| Some((col.clone(), (*src)?)))
+            .filter(|(col, _)| !col.is_generated())
+            .unzip();
+        if targets.is_empty() {
+            return Err(ImportError::NothingMapped);
+        }
+
+        let key = preview.table.key_columns();
+        if mode == ImportMode::Upsert && preview.kind != DbKind::Mysql {
+            if key.is_empty() {
+                return Err(ImportError::NoKey(preview.table.table.clone()));
+            }
+            let missing: Vec<String> = key
+                .iter()
+                .filter(|k| !targets.iter().any(|t| &t.name == *k))
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                return Err(ImportError::KeyNotMapped(missing));
+            }
+        }
+        Ok(Self {
+            kind: preview.kind,
+            table: preview.table.table.clone(),
+            targets,
+            sources,
+            key,
+            mode,
+        })
+    }
+
+    /// A single-row INSERT (or upsert) with a placeholder per target. On
+    /// Postgres values are bound as text and cast to the column's type.
+    pub fn statement(&self) -> String {
+        let q = |c: &str| self.kind.quote_ident(c);
+        let names: Vec<&str> = self.targets.iter().map(|c| c.name.as_str()).collect();
+        let quoted: Vec<String> = names.iter().map(|n| q(n)).collect();
+        let placeholders: Vec<String> = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                let p = self.kind.placeholder(i + 1);
+                if self.kind == DbKind::Postgres {
+                    format!("CAST({} AS {})", p, col.data_type)
+                } else {
+                    p
+                }
+            })
+            .collect();
+        let mut sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            q(&self.table),
+            quoted.join(", "),
+            placeholders.join(", ")
+        );
+        if self.mode == ImportMode::Upsert {
+            sql.push(' ');
+            sql.push_str(&upsert_clause(self.kind, &names, &self.key));
+        }
+        sql
+    }
+
+    /// The values of `record` to bind to [`ImportPlan::statement`].
+    pub fn row(&self, record: &[Option<String>]) -> Result<Vec<CellValue>, CoerceError> {
+        self.targets
+            .iter()
+            .zip(&self.sources)
+            .map(|(col, &src)| coerce(col, record.get(src).and_then(|f| f.as_deref())))
+            .collect()
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::{
+        import::{
+            CoerceError, ImportError, ImportFormat, ImportMode, ImportOptions, ImportPlan,
+            ImportPreview, ImportReader, coerce,
+        },
+        introstect::{ColumnDetail, IndexInfo, TableDetails},
+        types::CellValue,
+    };
+    use crate::domain::connection::DbKind;
+    use sqlx::types::chrono::NaiveDate;
+
+    fn col(name: &str, data_type: &str) -> ColumnDetail {
+        ColumnDetail {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn read_all(text: &str, options: ImportOptions) -> (Vec<String>, Vec<Vec<Option<String>>>) {
+        let mut reader = ImportReader::new(text.as_bytes(), options).unwrap();
+        let mut records = Vec::new();
+        while let Some(r) = reader.next_record().unwrap() {
+            records.push(r);
+        }
+        (reader.columns().to_vec(), records)
+    }
+
+    fn some(fields: &[&str]) -> Vec<Option<String>> {
+        fields.iter().map(|f| Some(f.to_string())).collect()
+    }
+
+    #[test]
+    fn csv_records_handle_quotes_line_breaks_and_null_markers() {
+        let text = "\u{feff}id,Note\r\n1,\"a, \"\"b\"\"\nc\"\r\n\n2,\n3,\"\"\n";
+        let (columns, records) = read_all(text, ImportOptions::default());
+        assert_eq!(columns, ["id", "Note"]);
+        assert_eq!(
+            records,
+            [
+                some(&["1", "a, \"b\"\nc"]),
+                vec![Some("2".into()), None],
+                some(&["3", ""]),
+            ]
+        );
+
+        let options = ImportOptions {
+            format: ImportFormat::Tsv,
+            header: false,
+            null: "\\N".into(),
+            ..Default::default()
+        };
+        let (columns, records) = read_all("1\t\\N\n2\tx,y\n", options);
+        assert_eq!(columns, ["column1", "column2"]);
+        assert_eq!(records, [vec![Some("1".into()), None], some(&["2", "x,y"])]);
+
+        let mut reader =
+            ImportReader::new("a\n\"open\n".as_bytes(), ImportOptions::default()).unwrap();
+        let err = reader.next_record().unwrap_err();
+        assert_eq!(err.to_string(), "line 2: unterminated quoted field");
+    }
+
+    #[test]
+    fn ndjson_records_follow_the_first_objects_keys() {
+        let options = ImportOptions {
+            format: ImportFormat::Ndjson,
+            ..Default::default()
+        };
+        let text =
+            "{\"name\":\"ada\",\"id\":1,\"tags\":[1]}\n\n{\"id\":2,\"name\":null,\"extra\":true}\n";
+        let (columns, records) = read_all(text, options.clone());
+        assert_eq!(columns, ["id", "name", "tags"]);
+        assert_eq!(
+            records,
+            [
+                some(&["1", "ada", "[1]"]),
+                vec![Some("2".into()), None, None]
+            ]
+        );
+
+        let mut reader = ImportReader::new("{\"id\":1}\n[2]\n".as_bytes(), options).unwrap();
+        reader.next_record().unwrap();
+        let err = reader.next_record().unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected a JSON object");
+    }
+
+    #[test]
+    fn values_are_coerced_to_the_column_type() {
+        let ok = |data_type: &str, raw: &str| coerce(&col("c", data_type), Some(raw)).unwrap();
+        assert_eq!(ok("bigint", " 42 "), CellValue::Int(42));
+        assert_eq!(
+            ok("bigint unsigned", "18446744073709551615"),
+            CellValue::UInt(u64::MAX)
+        );
+        assert_eq!(
+            ok("numeric(6,2)", "10.50"),
+            CellValue::Decimal("10.50".into())
+        );
+        assert_eq!(ok("REAL", "9.5"), CellValue::Float(9.5));
+        assert_eq!(ok("boolean", "yes"), CellValue::Bool(true));
+        assert_eq!(
+            ok("date", "2024-02-29"),
+            CellValue::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())
+        );
+        assert_eq!(
+            ok("datetime", "2024-02-29T10:30:00").to_string(),
+            "2024-02-29 10:30:00"
+        );
+        assert_eq!(
+            ok("timestamp with time zone", "2024-02-29 10:30+02"),
+            CellValue::Text("2024-02-29 10:30+02".into())
+        );
+        assert_eq!(
+            ok("jsonb", "{\"a\": 1}"),
+            CellValue::Json("{\"a\": 1}".into())
+        );
+        assert_eq!(ok("BLOB", "0xCAFE"), CellValue::Bytes(vec![0xCA, 0xFE]));
+        assert_eq!(ok("bytea", "hi"), CellValue::Bytes(b"hi".to_vec()));
+        assert_eq!(
+            ok("varchar(20)", " kept "),
+            CellValue::Text(" kept ".into())
+        );
+        assert_eq!(coerce(&col("c", "int"), None), Ok(CellValue::Null));
+
+        let err = |data_type: &str, raw: &str| coerce(&col("c", data_type), Some(raw)).unwrap_err();
+        assert_eq!(
+            err("integer", "1.5"),
+            CoerceError {
+                column: "c".into(),
+                expected: "an integer",
+                value: "1.5".into(),
+            }
+        );
+        assert_eq!(err("date", "29/02/2024").expected, "a date (YYYY-MM-DD)");
+        assert_eq!(err("json", "{oops").expected, "JSON");
+        assert_eq!(err("blob", "0xABC").expected, "hex bytes");
+        assert_eq!(err("bool", "maybe").expected, "true or false");
+    }
+
+    fn preview(kind: DbKind) -> ImportPreview {
+        let mut generated = col("shout", "text");
+        generated.extra = "generated".into();
+        ImportPreview {
+            kind,
+            table: TableDetails {
+                table: "notes".into(),
+                columns: vec![
+                    col("id", "integer"),
+                    col("note_text", "text"),
+                    generated,
+                    col("born", "date"),
+                ],
+                indexes: vec![IndexInfo {
+                    name: "pk".into(),
+                    columns: vec!["id".into()],
+                    unique: true,
+                    primary: true,
+                    index_type: String::new(),
+                }],
+                ..Default::default()
+            },
+            columns: vec!["Note Text".into(), "ID".into(), "shout".into()],
+            rows: vec![],
+        }
+    }
+
+    #[test]
+    fn columns_map_by_name_or_position_but_never_to_generated_ones() {
+        let p = preview(DbKind::Postgres);
+        assert_eq!(p.auto_map(true), [Some(1), Some(0), None, None]);
+        assert_eq!(p.auto_map(false), [Some(0), Some(1), None, None]);
+    }
+
+    #[test]
+    fn plans_insert_or_upsert_on_the_key_and_coerce_records() {
+        let p = preview(DbKind::Postgres);
+        let plan = ImportPlan::new(&p, &p.auto_map(true), ImportMode::Upsert).unwrap();
+        assert_eq!(
+            plan.statement(),
+            "INSERT INTO \"notes\" (\"id\", \"note_text\") \
+             VALUES (CAST($1 AS integer), CAST($2 AS text)) \
+             ON CONFLICT (\"id\") DO UPDATE SET \"note_text\" = EXCLUDED.\"note_text\""
+        );
+        assert_eq!(
+            plan.row(&[Some("hi".into()), Some("7".into())]).unwrap(),
+            [CellValue::Int(7), CellValue::Text("hi".into())]
+        );
+        let err = plan.row(&[None, Some("x".into())]).unwrap_err();
+        assert_eq!(err.to_string(), "id expects an integer, got 'x'");
+
+        let p = preview(DbKind::Mysql);
+        let plan = ImportPlan::new(&p, &[None, Some(0), None, None], ImportMode::Upsert).unwrap();
+        assert_eq!(
+            plan.statement(),
+            "INSERT INTO `notes` (`note_text`) VALUES (?) \
+             ON DUPLICATE KEY UPDATE `note_text` = VALUES(`note_text`)"
+        );
+        let plan = ImportPlan::new(&p, &[Some(1), None, None, None], ImportMode::Insert).unwrap();
+        assert_eq!(plan.statement(), "INSERT INTO `notes` (`id`) VALUES (?)");
+
+        let p = preview(DbKind::Sqlite);
+        let err = ImportPlan::new(&p, &[None, Some(0), None, None], ImportMode::Upsert);
+        assert!(matches!(err, Err(ImportError::KeyNotMapped(k)) if k == ["id"]));
+        let err = ImportPlan::new(&p, &[None; 4], ImportMode::Insert);
+        assert!(matches!(err, Err(ImportError::NothingMapped)));
+        let mut keyless = p.clone();
+        keyless.table.indexes.clear();
+        let err = ImportPlan::new(&keyless, &keyless.auto_map(true), ImportMode::Upsert);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Upsert needs a primary key or unique index on notes"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::app::state::ColumnInfo;
+
+/// Kinds of object the Schema screen's tree groups by, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectKind {
+    Table,
+    View,
+    Procedure,
+    Function,
+    Trigger,
+    Event,
+}
+
+impl ObjectKind {
+    pub const ALL: [ObjectKind; 6] = [
+        ObjectKind::Table,
+        ObjectKind::View,
+        ObjectKind::Procedure,
+        ObjectKind::Function,
+        ObjectKind::Trigger,
+        ObjectKind::Event,
+    ];
+
+    pub fn group_title(self) -> &'static str {
+        match self {
+            ObjectKind::Table => "Tables",
+            ObjectKind::View => "Views",
+            ObjectKind::Procedure => "Procedures",
+            ObjectKind::Function => "Functions",
+            ObjectKind::Trigger => "Triggers",
+            ObjectKind::Event => "Events",
+        }
+    }
+
+    /// Tables and views have columns and rows; the rest only a definition.
+    pub fn is_relation(self) -> bool {
+        matches!(self, ObjectKind::Table | ObjectKind::View)
+    }
+}
+
+/// A named object in the current schema.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SchemaObject {
+    pub kind: ObjectKind,
+    pub name: String,
+}
+
+impl SchemaObject {
+    pub fn new(kind: ObjectKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+        }
+    }
+}
+
+/// Everything the Schema screen's detail tabs show for one table.
+#[derive(Debug, Clone, Default)]
+pub struct TableDetails {
+    pub table: String,
+    pub columns: Vec<ColumnDetail>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    pub referenced_by: Vec<ReferencingKey>,
+    /// `CREATE TABLE` (plus index) statements as the engine reports them.
+    pub ddl: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ColumnDetail {
+    pub name: String,
+    pub data_type: String,   // e.g. "varchar"
+    pub column_type: String, // e.g. "varchar(255)"
+    pub is_nullable: bool,
+    pub column_key: Option<String>, // "PRI", "UNI", "MUL"
+    pub default: Option<String>,
+    pub extra: String, // auto_increment, generated, on update …
+    pub comment: String,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+}
+
+impl TableDetails {
+    /// Columns identifying a row: the primary key, else the first unique index
+    /// on plain columns. Empty when there is neither.
+    pub fn key_columns(&self) -> Vec<String> {
+        let plain = |ix: &&IndexInfo| !ix.columns.iter().any(|c| c.starts_with('('));
+        self.indexes
+            .iter()
+            .filter(plain)
+            .find(|ix| ix.primary)
+            .map(|ix| ix.columns.clone())
+            .or_else(|| {
+                // SQLite's `INTEGER PRIMARY KEY` aliases the rowid and has no index.
+                let pk: Vec<String> = self
+                    .columns
+                    .iter()
+                    .filter(|c| c.column_key.as_deref() == Some("PRI"))
+                    .map(|c| c.name.clone())
+                    .collect();
+                (!pk.is_empty()).then_some(pk)
+            })
+            .or_else(|| {
+                let unique = self.indexes.iter().filter(plain).find(|ix| ix.unique)?;
+                Some(unique.columns.clone())
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl ColumnDetail {
+    /// Computed from other columns, so it can't be written. MySQL reports
+    /// expression defaults as `DEFAULT_GENERATED`; those are writable.
+    pub fn is_generated(&self) -> bool {
+        let extra = self.extra.to_lowercase();
+        extra.contains("generated") && !extra.contains("default_generated")
+    }
+
+    pub fn info(&self) -> ColumnInfo {
+        ColumnInfo {
+            name: self.name.clone(),
+            data_type: self.data_type.clone(),
+            is_nullable: self.is_nullable,
+            column_key: self.column_key.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexInfo {
+    pub name: String,
+    pub columns: Vec<String>, // in index order; expressions show as "(expr)"
+    pub unique: bool,
+    pub primary: bool,
+    pub index_type: String, // BTREE, HASH, gin …
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForeignKeyInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+/// A foreign key on another table of the same schema that points at this one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferencingKey {
+    pub table: String,
+    pub key: ForeignKeyInfo, // `columns` belong to `table`, `ref_columns` to this one
+}
+
+/// Append one `(index, column)` row; rows must arrive grouped by index name
+/// and ordered by position within the index.
+pub(crate) fn push_index_column(
+    indexes: &mut Vec<IndexInfo>,
+    name: String,
+    column: String,
+    unique: bool,
+    primary: bool,
+    index_type: String,
+) {
+    match indexes.last_mut() {
+        Some(last) if last.name == name => last.columns.push(column),
+        _ => indexes.push(IndexInfo {
+            name,
+            columns: vec![column],
+            unique,
+            primary,
+            index_type,
+        }),
+    }
+}
+
+/// Append one `(constraint, column pair)` row; same grouping rules as
+/// [`push_index_column`].
+pub(crate) fn push_fk_column(
+    fks: &mut Vec<ForeignKeyInfo>,
+    name: String,
+    column: String,
+    ref_table: String,
+    ref_column: String,
+    on_update: String,
+    on_delete: String,
+) {
+    match fks.last_mut() {
+        Some(last) if last.name == name => {
+            last.columns.push(column);
+            last.ref_columns.push(ref_column);
+        }
+        _ => fks.push(ForeignKeyInfo {
+            name,
+            columns: vec![column],
+            ref_table,
+            ref_columns: vec![ref_column],
+            on_update,
+            on_delete,
+        }),
+    }
+}
+
+/// Append one column pair of a referencing key, given as a single-column
+/// `key`; rows must arrive grouped by table, then constraint name, ordered by
+/// position.
+pub(crate) fn push_referencing_column(
+    refs: &mut Vec<ReferencingKey>,
+    table: String,
+    key: ForeignKeyInfo,
+) {
+    match refs.last_mut() {
+        Some(last) if last.table == table && last.key.name == key.name => {
+            last.key.columns.extend(key.columns);
+            last.key.ref_columns.extend(key.ref_columns);
+        }
+        _ => refs.push(ReferencingKey { table, key }),
+    }
+}
+
+/// Fill in MySQL-style `column_key`s for engines that don't report one:
+/// PRI for primary key columns, UNI for single-column unique indexes, MUL for
+/// the leading column of any other index. Keys already set are kept.
+pub(crate) fn fill_column_keys(columns: &mut [ColumnDetail], indexes: &[IndexInfo]) {
+    for col in columns.iter_mut().filter(|c| c.column_key.is_none()) {
+        let mut key = None;
+        for ix in indexes {
+            let k = if ix.primary && ix.columns.contains(&col.name) {
+                "PRI"
+            } else if ix.unique && ix.columns == [col.name.as_str()] {
+                "UNI"
+            } else if ix.columns.first() == Some(&col.name) {
+                "MUL"
+            } else {
+                continue;
+            };
+            // Strongest key wins: PRI > UNI > MUL.
+            if key.is_none_or(|cur| rank(k) < rank(cur)) {
+                key = Some(k);
+            }
+        }
+        col.column_key = key.map(str::to_string);
+    }
+
+    fn rank(k: &str) -> u8 {
+        match k {
+            "PRI" => 0,
+            "UNI" => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// Human-readable referential action for Postgres' one-letter codes.
+pub(crate) fn pg_fk_action(code: &str) -> String {
+    match code {
+        "r" => "RESTRICT",
+        "c" => "CASCADE",
+        "n" => "SET NULL",
+        "d" => "SET DEFAULT",
+        _ => "NO ACTION",
+    }
+    .to_string()
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::introstect::{
+        ColumnDetail, ForeignKeyInfo, IndexInfo, TableDetails, fill_column_keys, push_fk_column,
+        push_index_column, push_referencing_column,
+    };
+
+    fn col(name: &str) -> ColumnDetail {
+        ColumnDetail {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn index_and_fk_rows_group_by_name() {
+        let mut ix = Vec::new();
+        push_index_column(&mut ix, "pk".into(), "a".into(), true, true, "btree".into());
+        push_index_column(&mut ix, "pk".into(), "b".into(), true, true, "btree".into());
+        push_index_column(
+            &mut ix,
+            "i_c".into(),
+            "c".into(),
+            false,
+            false,
+            "btree".into(),
+        );
+        assert_eq!(ix.len(), 2);
+        assert_eq!(ix[0].columns, ["a", "b"]);
+        assert_eq!(ix[1].columns, ["c"]);
+
+        let mut fks = Vec::new();
+        for (c, r) in [("a", "x"), ("b", "y")] {
+            push_fk_column(
+                &mut fks,
+                "fk".into(),
+                c.into(),
+                "parent".into(),
+                r.into(),
+                "CASCADE".into(),
+                "NO ACTION".into(),
+            );
+        }
+        assert_eq!(fks.len(), 1);
+        assert_eq!(fks[0].columns, ["a", "b"]);
+        assert_eq!(fks[0].ref_columns, ["x", "y"]);
+
+        // Referencing keys of different tables may share a constraint name.
+        let mut refs = Vec::new();
+        for (table, c, r) in [("a", "p1", "x"), ("a", "p2", "y"), ("b", "p1", "x")] {
+            let key = ForeignKeyInfo {
+                name: "fk".into(),
+                columns: vec![c.into()],
+                ref_table: "parent".into(),
+                ref_columns: vec![r.into()],
+                ..Default::default()
+            };
+            push_referencing_column(&mut refs, table.into(), key);
+        }
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].key.columns, ["p1", "p2"]);
+        assert_eq!(
+            (refs[1].table.as_str(), refs[1].key.ref_columns.len()),
+            ("b", 1)
+        );
+    }
+
+    #[test]
+    fn column_keys_follow_mysql_precedence() {
+        let mut cols = vec![col("id"), col("email"), col("team"), col("note"), col("x")];
+        cols[4].column_key = Some("PRI".into()); // already known: kept
+        let indexes = [
+            IndexInfo {
+                name: "pk".into(),
+                columns: vec!["id".into()],
+                unique: true,
+                primary: true,
+                ..Default::default()
+            },
+            IndexInfo {
+                name: "by_team".into(),
+                columns: vec!["team".into(), "email".into()],
+                ..Default::default()
+            },
+            IndexInfo {
+                name: "email_uq".into(),
+                columns: vec!["email".into()],
+                unique: true,
+                ..Default::default()
+            },
+        ];
+
+        fill_column_keys(&mut cols, &indexes);
+        let keys: Vec<_> = cols.iter().map(|c| c.column_key.as_deref()).collect();
+        assert_eq!(
+            keys,
+            [Some("PRI"), Some("UNI"), Some("MUL"), None, Some("PRI")]
+        );
+    }
+
+    #[test]
+    fn key_prefers_primary_then_plain_unique_and_spots_generated_columns() {
+        let ix = |name: &str, columns: &[&str], unique: bool, primary: bool| IndexInfo {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            unique,
+            primary,
+            index_type: String::new(),
+        };
+        let mut details = TableDetails {
+            indexes: vec![
+                ix("by_lower", &["(expr)"], true, false),
+                ix("by_email", &["email"], true, false),
+                ix("pk", &["id", "rev"], true, true),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(details.key_columns(), ["id", "rev"]);
+        details.indexes.pop();
+        assert_eq!(details.key_columns(), ["email"]);
+        // A rowid-alias primary key shows up on the column only.
+        details.columns = vec![ColumnDetail {
+            column_key: Some("PRI".into()),
+            ..col("rowid_pk")
+        }];
+        assert_eq!(details.key_columns(), ["rowid_pk"]);
+        details.columns.clear();
+        details.indexes.pop();
+        assert!(details.key_columns().is_empty());
+
+        let with_extra = |extra: &str| ColumnDetail {
+            extra: extra.to_string(),
+            ..col("c")
+        };
+        assert!(with_extra("STORED GENERATED").is_generated());
+        assert!(with_extra("generated").is_generated());
+        assert!(!with_extra("DEFAULT_GENERATED on update CURRENT_TIMESTAMP").is_generated());
+        assert!(!with_extra("auto_increment").is_generated());
+    }
+}
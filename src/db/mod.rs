@@ -1 +1,30 @@
+pub mod connect;
+pub mod driver;
+pub mod edit;
+pub mod export;
+pub mod import;
+pub mod introstect;
+pub mod mysql;
+pub mod postgres;
+pub mod query;
+pub mod sqlite;
+pub mod tunnel;
+pub mod types;
 pub mod worker;
+
+#[cfg(test)]
+mod connect_tests;
+#[cfg(test)]
+mod edit_tests;
+#[cfg(test)]
+mod export_tests;
+#[cfg(test)]
+mod import_tests;
+#[cfg(test)]
+mod introstect_tests;
+#[cfg(test)]
+mod query_tests;
+#[cfg(test)]
+mod tunnel_tests;
+#[cfg(test)]
+mod types_tests;
@@ -0,0 +1,554 @@
+use super::{
+    driver::{Driver, Page, RowSink, RowSource, Running, drain_results, drain_rows, page_select},
+    edit::EditStatement,
+    introstect::{
+        ColumnDetail, ForeignKeyInfo, ObjectKind, SchemaObject, TableDetails, push_fk_column,
+        push_index_column, push_referencing_column,
+    },
+    types::{CellValue, QueryResult, bind_cell, result_columns},
+};
+use crate::domain::{connection::DbKind, filter::CompiledFilter};
+use async_trait::async_trait;
+use sqlx::{
+    Column, Connection, Executor, MySqlPool, Row, Statement, TypeInfo,
+    mysql::{MySqlQueryResult, MySqlRow},
+    types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
+};
+use std::sync::Mutex;
+
+pub struct MySqlDriver {
+    pool: MySqlPool,
+    running: Running<u64>, // CONNECTION_ID() of each session inside `execute`
+    // Active database. Catalog queries bind it explicitly and `execute` issues
+    // `USE` first, since pooled sessions don't share a default database.
+    schema: Mutex<Option<String>>,
+}
+
+impl MySqlDriver {
+    pub fn new(pool: MySqlPool, schema: Option<String>) -> Self {
+        Self {
+            pool,
+            running: Running::new(),
+            schema: Mutex::new(schema),
+        }
+    }
+
+    fn schema(&self) -> Option<String> {
+        self.schema.lock().unwrap().clone()
+    }
+
+    /// `db`.`name` when a database is active, so it resolves on any session.
+    fn qualified(&self, name: &str) -> String {
+        match self.schema() {
+            Some(db) => format!("{}.{}", self.quote_ident(&db), self.quote_ident(name)),
+            None => self.quote_ident(name),
+        }
+    }
+}
+
+#[async_trait]
+impl Driver for MySqlDriver {
+    fn kind(&self) -> DbKind {
+        DbKind::Mysql
+    }
+
+    async fn list_schemas(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT schema_name
+            FROM information_schema.schemata
+            ORDER BY schema_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    fn current_schema(&self) -> Option<String> {
+        self.schema()
+    }
+
+    async fn use_schema(&self, name: &str) -> Result<(), sqlx::Error> {
+        // Let the server validate the name (and privileges) before switching.
+        self.pool
+            .execute(format!("USE {}", self.quote_ident(name)).as_str())
+            .await?;
+        *self.schema.lock().unwrap() = Some(name.to_string());
+        Ok(())
+    }
+
+    async fn list_objects(&self) -> Result<Vec<SchemaObject>, sqlx::Error> {
+        let mut objects = Vec::new();
+        let relations = sqlx::query(
+            r#"
+            SELECT table_name, table_type
+            FROM information_schema.tables
+            WHERE table_schema = ?
+            "#,
+        )
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &relations {
+            let kind = match text(r, 1).as_deref() {
+                Some("BASE TABLE") => ObjectKind::Table,
+                _ => ObjectKind::View,
+            };
+            objects.push(SchemaObject::new(kind, text(r, 0).unwrap_or_default()));
+        }
+
+        let routines = sqlx::query(
+            r#"
+            SELECT routine_name, routine_type
+            FROM information_schema.routines
+            WHERE routine_schema = ?
+            "#,
+        )
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &routines {
+            let kind = match text(r, 1).as_deref() {
+                Some("PROCEDURE") => ObjectKind::Procedure,
+                _ => ObjectKind::Function,
+            };
+            objects.push(SchemaObject::new(kind, text(r, 0).unwrap_or_default()));
+        }
+
+        let triggers = sqlx::query(
+            r#"
+            SELECT trigger_name
+            FROM information_schema.triggers
+            WHERE trigger_schema = ?
+            "#,
+        )
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &triggers {
+            objects.push(SchemaObject::new(
+                ObjectKind::Trigger,
+                text(r, 0).unwrap_or_default(),
+            ));
+        }
+
+        let events = sqlx::query(
+            r#"
+            SELECT event_name
+            FROM information_schema.events
+            WHERE event_schema = ?
+            "#,
+        )
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &events {
+            objects.push(SchemaObject::new(
+                ObjectKind::Event,
+                text(r, 0).unwrap_or_default(),
+            ));
+        }
+
+        Ok(objects)
+    }
+
+    async fn object_definition(&self, object: &SchemaObject) -> Result<String, sqlx::Error> {
+        let (what, column) = match object.kind {
+            ObjectKind::Table => ("TABLE", "Create Table"),
+            ObjectKind::View => ("VIEW", "Create View"),
+            ObjectKind::Procedure => ("PROCEDURE", "Create Procedure"),
+            ObjectKind::Function => ("FUNCTION", "Create Function"),
+            ObjectKind::Trigger => ("TRIGGER", "SQL Original Statement"),
+            ObjectKind::Event => ("EVENT", "Create Event"),
+        };
+        let sql = format!("SHOW CREATE {} {}", what, self.qualified(&object.name));
+        let row = self.pool.fetch_optional(sql.as_str()).await?;
+
+        // The body column is NULL when the user lacks privileges on the routine.
+        Ok(row
+            .and_then(|r| {
+                let idx = r.columns().iter().position(|c| c.name() == column)?;
+                text(&r, idx)
+            })
+            .unwrap_or_else(|| "(definition not visible with current privileges)".to_string()))
+    }
+
+    async fn describe_table(&self, table: &str) -> Result<TableDetails, sqlx::Error> {
+        let columns = sqlx::query(
+            r#"
+            SELECT column_name, data_type, column_type, is_nullable, column_key,
+                   column_default, extra, column_comment,
+                   character_set_name, collation_name
+            FROM information_schema.columns
+            WHERE table_schema = ?
+              AND table_name = ?
+            ORDER BY ordinal_position
+            "#,
+        )
+        .bind(self.schema())
+        .bind(table)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|r| ColumnDetail {
+            name: text(r, 0).unwrap_or_default(),
+            data_type: text(r, 1).unwrap_or_default(),
+            column_type: text(r, 2).unwrap_or_default(),
+            is_nullable: text(r, 3).as_deref() == Some("YES"),
+            column_key: text(r, 4).filter(|k| !k.is_empty()),
+            default: text(r, 5),
+            extra: text(r, 6).unwrap_or_default(),
+            comment: text(r, 7).unwrap_or_default(),
+            charset: text(r, 8),
+            collation: text(r, 9),
+        })
+        .collect();
+
+        let mut indexes = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT index_name, column_name, non_unique, index_type
+            FROM information_schema.statistics
+            WHERE table_schema = ?
+              AND table_name = ?
+            ORDER BY index_name <> 'PRIMARY', index_name, seq_in_index
+            "#,
+        )
+        .bind(self.schema())
+        .bind(table)
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            let name = text(r, 0).unwrap_or_default();
+            let non_unique: i64 = r.try_get(2).unwrap_or(1);
+            push_index_column(
+                &mut indexes,
+                name.clone(),
+                text(r, 1).unwrap_or_else(|| "(expr)".to_string()),
+                non_unique == 0,
+                name == "PRIMARY",
+                text(r, 3).unwrap_or_default(),
+            );
+        }
+
+        let mut foreign_keys = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT k.constraint_name, k.column_name,
+                   k.referenced_table_name, k.referenced_column_name,
+                   r.update_rule, r.delete_rule
+            FROM information_schema.key_column_usage k
+            JOIN information_schema.referential_constraints r
+              ON r.constraint_schema = k.constraint_schema
+             AND r.constraint_name = k.constraint_name
+             AND r.table_name = k.table_name
+            WHERE k.table_schema = ?
+              AND k.table_name = ?
+            ORDER BY k.constraint_name, k.ordinal_position
+            "#,
+        )
+        .bind(self.schema())
+        .bind(table)
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            push_fk_column(
+                &mut foreign_keys,
+                text(r, 0).unwrap_or_default(),
+                text(r, 1).unwrap_or_default(),
+                text(r, 2).unwrap_or_default(),
+                text(r, 3).unwrap_or_default(),
+                text(r, 4).unwrap_or_default(),
+                text(r, 5).unwrap_or_default(),
+            );
+        }
+
+        let mut referenced_by = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT k.table_name, k.constraint_name, k.column_name,
+                   k.referenced_column_name, r.update_rule, r.delete_rule
+            FROM information_schema.key_column_usage k
+            JOIN information_schema.referential_constraints r
+              ON r.constraint_schema = k.constraint_schema
+             AND r.constraint_name = k.constraint_name
+             AND r.table_name = k.table_name
+            WHERE k.table_schema = ?
+              AND k.referenced_table_schema = k.table_schema
+              AND k.referenced_table_name = ?
+            ORDER BY k.table_name, k.constraint_name, k.ordinal_position
+            "#,
+        )
+        .bind(self.schema())
+        .bind(table)
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            let key = ForeignKeyInfo {
+                name: text(r, 1).unwrap_or_default(),
+                columns: vec![text(r, 2).unwrap_or_default()],
+                ref_table: table.to_string(),
+                ref_columns: vec![text(r, 3).unwrap_or_default()],
+                on_update: text(r, 4).unwrap_or_default(),
+                on_delete: text(r, 5).unwrap_or_default(),
+            };
+            push_referencing_column(&mut referenced_by, text(r, 0).unwrap_or_default(), key);
+        }
+
+        let sql = format!("SHOW CREATE TABLE {}", self.qualified(table));
+        let ddl = self
+            .pool
+            .fetch_optional(sql.as_str())
+            .await?
+            .and_then(|r| text(&r, 1))
+            .unwrap_or_default();
+
+        Ok(TableDetails {
+            table: table.to_string(),
+            columns,
+            indexes,
+            foreign_keys,
+            referenced_by,
+            ddl,
+        })
+    }
+
+    async fn fetch_page(
+        &self,
+        table: &str,
+        order_by: Option<&str>,
+        filter: Option<&CompiledFilter>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Page, sqlx::Error> {
+        let from = self.qualified(table);
+        let mut sql = page_select(|c| self.quote_ident(c), &from, order_by, filter);
+        sql.push_str(" LIMIT ? OFFSET ?");
+
+        // Prepare first so column metadata is available even for an empty page.
+        let stmt = self.pool.prepare(&sql).await?;
+        let columns = result_columns(stmt.columns());
+
+        let mut query = stmt.query();
+        for p in filter.map(|f| f.params.as_slice()).unwrap_or_default() {
+            query = bind_cell(query, p);
+        }
+        let rows = query
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(decode_row)
+            .collect();
+
+        Ok(Page { columns, rows })
+    }
+
+    async fn stream_rows(
+        &self,
+        source: &RowSource,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        if let Some(db) = self.schema() {
+            conn.execute(format!("USE {}", self.quote_ident(&db)).as_str())
+                .await?;
+        }
+
+        let (sql, params) = source.select(|c| self.quote_ident(c), |t| self.qualified(t));
+        let stmt = conn.prepare(&sql).await?;
+        sink.columns(&result_columns(stmt.columns())).await?;
+
+        let mut query = stmt.query();
+        for p in params {
+            query = bind_cell(query, p);
+        }
+        drain_rows(query.fetch(&mut *conn), decode_row, sink).await
+    }
+
+    async fn write_rows(
+        &self,
+        sql: &str,
+        rows: &[Vec<CellValue>],
+        dry_run: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        if let Some(db) = self.schema() {
+            conn.execute(format!("USE {}", self.quote_ident(&db)).as_str())
+                .await?;
+        }
+
+        let mut tx = conn.begin().await?;
+        for row in rows {
+            let mut query = sqlx::query(sql);
+            for v in row {
+                query = bind_cell(query, v);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        if dry_run {
+            tx.rollback().await
+        } else {
+            tx.commit().await
+        }
+    }
+
+    async fn apply_changes(&self, statements: &[EditStatement]) -> Result<(), sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        if let Some(db) = self.schema() {
+            conn.execute(format!("USE {}", self.quote_ident(&db)).as_str())
+                .await?;
+        }
+
+        // Dropping the transaction on an early return rolls it back.
+        let mut tx = conn.begin().await?;
+        for stmt in statements {
+            let mut query = sqlx::query(&stmt.sql);
+            for v in &stmt.params {
+                query = bind_cell(query, v);
+            }
+            // None means the row changed since it was read; more, that the
+            // key isn't unique after all (NULLs in a unique index).
+            if query.execute(&mut *tx).await?.rows_affected() != 1 {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn estimate_rows(&self, table: &str) -> Result<Option<u64>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT table_rows
+            FROM information_schema.tables
+            WHERE table_schema = ?
+              AND table_name = ?
+            "#,
+        )
+        .bind(self.schema())
+        .bind(table)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|r| r.try_get::<Option<u64>, _>(0).ok().flatten()))
+    }
+
+    async fn execute(
+        &self,
+        id: u64,
+        sql: &str,
+        sink: &mut dyn RowSink,
+    ) -> Result<QueryResult, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+
+        // Pooled sessions don't remember a schema switch, so select it each time.
+        if let Some(db) = self.schema() {
+            conn.execute(format!("USE {}", self.quote_ident(&db)).as_str())
+                .await?;
+        }
+
+        // Remember which session runs the statement so `cancel` can KILL it.
+        let conn_id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+            .fetch_one(&mut *conn)
+            .await?;
+        let _running = self.running.track(id, conn_id);
+
+        // Text protocol (a plain `&str` executor) so any statement works, not
+        // just preparable ones.
+        drain_results(
+            sql,
+            conn.fetch_many(sql),
+            MySqlQueryResult::rows_affected,
+            decode_row,
+            sink,
+        )
+        .await
+    }
+
+    async fn cancel(&self, id: u64) -> Result<(), sqlx::Error> {
+        let Some(conn_id) = self.running.get(id) else {
+            return Ok(());
+        };
+        self.pool
+            .execute(format!("KILL QUERY {}", conn_id).as_str())
+            .await?;
+        Ok(())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+/// Catalog text by position. Some servers hand `information_schema` columns
+/// back as binary strings, so fall back to the raw bytes.
+fn text(row: &MySqlRow, idx: usize) -> Option<String> {
+    row.try_get::<Option<String>, _>(idx).unwrap_or_else(|_| {
+        row.try_get_unchecked::<Option<Vec<u8>>, _>(idx)
+            .ok()
+            .flatten()
+            .map(|b| String::from_utf8_lossy(&b).into_owned())
+    })
+}
+
+fn decode_row(row: &MySqlRow) -> Vec<CellValue> {
+    (0..row.len()).map(|i| decode_cell(row, i)).collect()
+}
+
+fn decode_cell(row: &MySqlRow, idx: usize) -> CellValue {
+    let type_name = row.column(idx).type_info().name().to_string();
+
+    let decoded = match type_name.as_str() {
+        "BOOLEAN" => row
+            .try_get_unchecked::<Option<bool>, _>(idx)
+            .map(|v| v.map(CellValue::Bool)),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" | "YEAR" => row
+            .try_get_unchecked::<Option<i64>, _>(idx)
+            .map(|v| v.map(CellValue::Int)),
+        t if t.ends_with("UNSIGNED") || t == "BIT" => row
+            .try_get_unchecked::<Option<u64>, _>(idx)
+            .map(|v| v.map(CellValue::UInt)),
+        "FLOAT" => row
+            .try_get_unchecked::<Option<f32>, _>(idx)
+            .map(|v| v.map(|f| CellValue::Float(f as f64))),
+        "DOUBLE" => row
+            .try_get_unchecked::<Option<f64>, _>(idx)
+            .map(|v| v.map(CellValue::Float)),
+        "DECIMAL" => row
+            .try_get_unchecked::<Option<String>, _>(idx)
+            .map(|v| v.map(CellValue::Decimal)),
+        "DATE" => row
+            .try_get_unchecked::<Option<NaiveDate>, _>(idx)
+            .map(|v| v.map(CellValue::Date)),
+        "DATETIME" | "TIMESTAMP" => row
+            .try_get_unchecked::<Option<NaiveDateTime>, _>(idx)
+            .map(|v| v.map(CellValue::DateTime)),
+        "TIME" => row
+            .try_get_unchecked::<Option<NaiveTime>, _>(idx)
+            .map(|v| v.map(CellValue::Time)),
+        "JSON" => row
+            .try_get_unchecked::<Option<String>, _>(idx)
+            .map(|v| v.map(CellValue::Json)),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => {
+            row.try_get_unchecked::<Option<Vec<u8>>, _>(idx)
+                .map(|v| v.map(CellValue::Bytes))
+        }
+        _ => row
+            .try_get_unchecked::<Option<String>, _>(idx)
+            .map(|v| v.map(CellValue::Text)),
+    };
+
+    match decoded {
+        Ok(Some(v)) => v,
+        Ok(None) => CellValue::Null,
+        // Fall back to raw bytes so an odd type never hides the whole row.
+        Err(_) => match row.try_get_unchecked::<Option<Vec<u8>>, _>(idx) {
+            Ok(Some(bytes)) => match String::from_utf8(bytes) {
+                Ok(s) => CellValue::Text(s),
+                Err(e) => CellValue::Bytes(e.into_bytes()),
+            },
+            _ => CellValue::Null,
+        },
+    }
+}
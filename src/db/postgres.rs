@@ -0,0 +1,776 @@
+use super::{
+    driver::{Driver, Page, RowSink, RowSource, Running, drain_results, drain_rows, page_select},
+    edit::EditStatement,
+    introstect::{
+        ColumnDetail, ForeignKeyInfo, ObjectKind, SchemaObject, TableDetails, fill_column_keys,
+        pg_fk_action, push_fk_column, push_index_column, push_referencing_column,
+    },
+    types::{CellValue, QueryResult, bind_cell, result_columns},
+};
+use crate::domain::{connection::DbKind, filter::CompiledFilter};
+use async_trait::async_trait;
+use sqlx::{
+    Column, Connection, Executor, PgPool, Row, Statement, TypeInfo, ValueRef,
+    postgres::{PgQueryResult, PgRow, PgValueFormat},
+    types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc},
+};
+use std::sync::Mutex;
+
+pub struct PgDriver {
+    pool: PgPool,
+    running: Running<i32>, // pg_backend_pid() of each session inside `execute`
+    // Active schema. Catalog queries bind it explicitly and `execute` sets
+    // `search_path` first, since pooled sessions don't share one.
+    schema: Mutex<String>,
+}
+
+impl PgDriver {
+    pub fn new(pool: PgPool, schema: String) -> Self {
+        Self {
+            pool,
+            running: Running::new(),
+            schema: Mutex::new(schema),
+        }
+    }
+
+    fn schema(&self) -> String {
+        self.schema.lock().unwrap().clone()
+    }
+
+    fn qualified(&self, name: &str) -> String {
+        format!(
+            "{}.{}",
+            self.quote_ident(&self.schema()),
+            self.quote_ident(name)
+        )
+    }
+
+    /// Postgres has no `SHOW CREATE TABLE`; rebuild one from the columns plus
+    /// the server's own rendering of each constraint and standalone index.
+    async fn create_table_sql(
+        &self,
+        table: &str,
+        columns: &[ColumnDetail],
+    ) -> Result<String, sqlx::Error> {
+        let view: Option<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT relkind::text, pg_get_viewdef(oid, true)
+            FROM pg_class
+            WHERE oid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+              AND relkind IN ('v', 'm')
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some((relkind, def)) = view {
+            let what = if relkind == "m" {
+                "MATERIALIZED VIEW"
+            } else {
+                "VIEW"
+            };
+            return Ok(format!(
+                "CREATE {} {} AS\n{}",
+                what,
+                self.quote_ident(table),
+                def
+            ));
+        }
+
+        let mut lines: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let mut line = format!("    {} {}", self.quote_ident(&c.name), c.column_type);
+                if let Some(d) = &c.default {
+                    line.push_str(&format!(" DEFAULT {}", d));
+                }
+                if !c.is_nullable {
+                    line.push_str(" NOT NULL");
+                }
+                line
+            })
+            .collect();
+
+        let constraints: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT conname::text, pg_get_constraintdef(oid)
+            FROM pg_constraint
+            WHERE conrelid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+              AND contype IN ('p', 'u', 'f', 'c', 'x')
+            ORDER BY contype = 'p' DESC, conname
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for (name, def) in constraints {
+            lines.push(format!(
+                "    CONSTRAINT {} {}",
+                self.quote_ident(&name),
+                def
+            ));
+        }
+
+        let mut ddl = format!(
+            "CREATE TABLE {} (\n{}\n);",
+            self.quote_ident(table),
+            lines.join(",\n")
+        );
+
+        let indexes: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT pg_get_indexdef(ix.indexrelid)
+            FROM pg_index ix
+            WHERE ix.indrelid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+              AND NOT EXISTS (SELECT 1 FROM pg_constraint c WHERE c.conindid = ix.indexrelid)
+            ORDER BY ix.indexrelid
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for def in indexes {
+            ddl.push_str(&format!("\n{};", def));
+        }
+
+        Ok(ddl)
+    }
+}
+
+#[async_trait]
+impl Driver for PgDriver {
+    fn kind(&self) -> DbKind {
+        DbKind::Postgres
+    }
+
+    fn current_schema(&self) -> Option<String> {
+        Some(self.schema())
+    }
+
+    async fn use_schema(&self, name: &str) -> Result<(), sqlx::Error> {
+        let exists: Option<i32> =
+            sqlx::query_scalar("SELECT 1 FROM pg_namespace WHERE nspname = $1")
+                .bind(name)
+                .fetch_optional(&self.pool)
+                .await?;
+        if exists.is_none() {
+            return Err(sqlx::Error::Protocol(format!(
+                "schema \"{}\" does not exist",
+                name
+            )));
+        }
+        *self.schema.lock().unwrap() = name.to_string();
+        Ok(())
+    }
+
+    async fn list_schemas(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT schema_name::text
+            FROM information_schema.schemata
+            WHERE schema_name NOT IN ('pg_catalog', 'information_schema')
+              AND schema_name NOT LIKE 'pg_toast%'
+              AND schema_name NOT LIKE 'pg_temp%'
+            ORDER BY schema_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn list_objects(&self) -> Result<Vec<SchemaObject>, sqlx::Error> {
+        // Functions are listed with their argument types so overloads stay distinct.
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT c.relname::text,
+                   CASE WHEN c.relkind IN ('v', 'm') THEN 'view' ELSE 'table' END
+            FROM pg_class c
+            WHERE c.relnamespace = to_regnamespace(quote_ident($1))
+              AND c.relkind IN ('r', 'p', 'f', 'v', 'm')
+            UNION ALL
+            SELECT p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+                   CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END
+            FROM pg_proc p
+            WHERE p.pronamespace = to_regnamespace(quote_ident($1))
+              AND p.prokind IN ('f', 'p')
+            UNION ALL
+            SELECT t.tgname::text, 'trigger'
+            FROM pg_trigger t
+            JOIN pg_class c ON c.oid = t.tgrelid
+            WHERE c.relnamespace = to_regnamespace(quote_ident($1))
+              AND NOT t.tgisinternal
+            "#,
+        )
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, kind)| {
+                let kind = match kind.as_str() {
+                    "table" => ObjectKind::Table,
+                    "view" => ObjectKind::View,
+                    "procedure" => ObjectKind::Procedure,
+                    "function" => ObjectKind::Function,
+                    _ => ObjectKind::Trigger,
+                };
+                SchemaObject::new(kind, name)
+            })
+            .collect())
+    }
+
+    async fn object_definition(&self, object: &SchemaObject) -> Result<String, sqlx::Error> {
+        let sql = match object.kind {
+            ObjectKind::Procedure | ObjectKind::Function => {
+                r#"
+                SELECT pg_get_functiondef(p.oid)
+                FROM pg_proc p
+                WHERE p.pronamespace = to_regnamespace(quote_ident($2))
+                  AND p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')' = $1
+                "#
+            }
+            ObjectKind::Trigger => {
+                r#"
+                SELECT pg_get_triggerdef(t.oid, true)
+                FROM pg_trigger t
+                JOIN pg_class c ON c.oid = t.tgrelid
+                WHERE c.relnamespace = to_regnamespace(quote_ident($2))
+                  AND t.tgname = $1
+                "#
+            }
+            ObjectKind::Table | ObjectKind::View => {
+                return Ok(self.describe_table(&object.name).await?.ddl);
+            }
+            // Postgres has no scheduled events.
+            ObjectKind::Event => return Ok(String::new()),
+        };
+
+        let def: Option<String> = sqlx::query_scalar(sql)
+            .bind(&object.name)
+            .bind(self.schema())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(def.unwrap_or_default())
+    }
+
+    async fn describe_table(&self, table: &str) -> Result<TableDetails, sqlx::Error> {
+        let mut columns: Vec<ColumnDetail> = sqlx::query(
+            r#"
+            SELECT a.attname::text,
+                   format_type(a.atttypid, NULL),
+                   format_type(a.atttypid, a.atttypmod),
+                   NOT a.attnotnull,
+                   pg_get_expr(d.adbin, d.adrelid),
+                   CASE WHEN a.attidentity <> '' THEN 'identity'
+                        WHEN a.attgenerated <> '' THEN 'generated'
+                        ELSE '' END,
+                   coalesce(col_description(a.attrelid, a.attnum), ''),
+                   co.collname::text
+            FROM pg_attribute a
+            LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            LEFT JOIN pg_collation co
+              ON co.oid = a.attcollation AND co.collname <> 'default'
+            WHERE a.attrelid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+              AND a.attnum > 0
+              AND NOT a.attisdropped
+            ORDER BY a.attnum
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|r| ColumnDetail {
+            name: r.try_get(0).unwrap_or_default(),
+            data_type: r.try_get(1).unwrap_or_default(),
+            column_type: r.try_get(2).unwrap_or_default(),
+            is_nullable: r.try_get(3).unwrap_or(true),
+            column_key: None,
+            default: r.try_get(4).unwrap_or(None),
+            extra: r.try_get(5).unwrap_or_default(),
+            comment: r.try_get(6).unwrap_or_default(),
+            charset: None, // per-database in Postgres
+            collation: r.try_get(7).unwrap_or(None),
+        })
+        .collect();
+
+        let mut indexes = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT i.relname::text, a.attname::text, ix.indisunique, ix.indisprimary,
+                   am.amname::text
+            FROM pg_index ix
+            JOIN pg_class i ON i.oid = ix.indexrelid
+            JOIN pg_am am ON am.oid = i.relam
+            JOIN LATERAL unnest(ix.indkey) WITH ORDINALITY AS k(attnum, ord) ON true
+            LEFT JOIN pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = k.attnum
+            WHERE ix.indrelid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+            ORDER BY ix.indisprimary DESC, i.relname, k.ord
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            push_index_column(
+                &mut indexes,
+                r.try_get(0).unwrap_or_default(),
+                r.try_get::<Option<String>, _>(1)
+                    .unwrap_or(None)
+                    .unwrap_or_else(|| "(expr)".to_string()),
+                r.try_get(2).unwrap_or(false),
+                r.try_get(3).unwrap_or(false),
+                r.try_get(4).unwrap_or_default(),
+            );
+        }
+        fill_column_keys(&mut columns, &indexes);
+
+        let mut foreign_keys = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT c.conname::text, la.attname::text, c.confrelid::regclass::text,
+                   ra.attname::text, c.confupdtype::text, c.confdeltype::text
+            FROM pg_constraint c
+            JOIN LATERAL unnest(c.conkey, c.confkey) WITH ORDINALITY AS k(l, r, ord) ON true
+            JOIN pg_attribute la ON la.attrelid = c.conrelid AND la.attnum = k.l
+            JOIN pg_attribute ra ON ra.attrelid = c.confrelid AND ra.attnum = k.r
+            WHERE c.contype = 'f'
+              AND c.conrelid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+            ORDER BY c.conname, k.ord
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            push_fk_column(
+                &mut foreign_keys,
+                r.try_get(0).unwrap_or_default(),
+                r.try_get(1).unwrap_or_default(),
+                r.try_get(2).unwrap_or_default(),
+                r.try_get(3).unwrap_or_default(),
+                pg_fk_action(r.try_get(4).unwrap_or_default()),
+                pg_fk_action(r.try_get(5).unwrap_or_default()),
+            );
+        }
+
+        let mut referenced_by = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT c.conrelid::regclass::text, c.conname::text, la.attname::text,
+                   ra.attname::text, c.confupdtype::text, c.confdeltype::text
+            FROM pg_constraint c
+            JOIN LATERAL unnest(c.conkey, c.confkey) WITH ORDINALITY AS k(l, r, ord) ON true
+            JOIN pg_attribute la ON la.attrelid = c.conrelid AND la.attnum = k.l
+            JOIN pg_attribute ra ON ra.attrelid = c.confrelid AND ra.attnum = k.r
+            WHERE c.contype = 'f'
+              AND c.confrelid = to_regclass(quote_ident($2) || '.' || quote_ident($1))
+            ORDER BY 1, c.conname, k.ord
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            let key = ForeignKeyInfo {
+                name: r.try_get(1).unwrap_or_default(),
+                columns: vec![r.try_get(2).unwrap_or_default()],
+                ref_table: table.to_string(),
+                ref_columns: vec![r.try_get(3).unwrap_or_default()],
+                on_update: pg_fk_action(r.try_get(4).unwrap_or_default()),
+                on_delete: pg_fk_action(r.try_get(5).unwrap_or_default()),
+            };
+            push_referencing_column(&mut referenced_by, r.try_get(0).unwrap_or_default(), key);
+        }
+
+        let ddl = self.create_table_sql(table, &columns).await?;
+
+        Ok(TableDetails {
+            table: table.to_string(),
+            columns,
+            indexes,
+            foreign_keys,
+            referenced_by,
+            ddl,
+        })
+    }
+
+    async fn fetch_page(
+        &self,
+        table: &str,
+        order_by: Option<&str>,
+        filter: Option<&CompiledFilter>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Page, sqlx::Error> {
+        let from = self.qualified(table);
+        let mut sql = page_select(|c| self.quote_ident(c), &from, order_by, filter);
+        // Integers only, so inlining is safe; it keeps the query on the simple
+        // (text) protocol, which renders every type, not just the ones we decode.
+        sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+
+        // Describe first so column metadata is available even for an empty page.
+        let stmt = self.pool.prepare(&sql).await?;
+        let columns = result_columns(stmt.columns());
+
+        // Bound filter values need the extended protocol (binary results).
+        let params = filter.map(|f| f.params.as_slice()).unwrap_or_default();
+        let rows = if params.is_empty() {
+            self.pool.fetch_all(sql.as_str()).await?
+        } else {
+            let mut query = stmt.query();
+            for p in params {
+                query = bind_cell(query, p);
+            }
+            query.fetch_all(&self.pool).await?
+        };
+        let rows = rows.iter().map(decode_row).collect();
+
+        Ok(Page { columns, rows })
+    }
+
+    async fn stream_rows(
+        &self,
+        source: &RowSource,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let set_path = format!(
+            "SET search_path TO {}, public",
+            self.quote_ident(&self.schema())
+        );
+        conn.execute(set_path.as_str()).await?;
+
+        let (sql, params) = source.select(|c| self.quote_ident(c), |t| self.qualified(t));
+        let stmt = conn.prepare(&sql).await?;
+        sink.columns(&result_columns(stmt.columns())).await?;
+
+        // Text protocol unless there are values to bind, as in `fetch_page`.
+        if params.is_empty() {
+            drain_rows(conn.fetch(sql.as_str()), decode_row, sink).await
+        } else {
+            let mut query = stmt.query();
+            for p in params {
+                query = bind_cell(query, p);
+            }
+            drain_rows(query.fetch(&mut *conn), decode_row, sink).await
+        }
+    }
+
+    async fn write_rows(
+        &self,
+        sql: &str,
+        rows: &[Vec<CellValue>],
+        dry_run: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let set_path = format!(
+            "SET search_path TO {}, public",
+            self.quote_ident(&self.schema())
+        );
+        conn.execute(set_path.as_str()).await?;
+
+        let mut tx = conn.begin().await?;
+        for row in rows {
+            let mut query = sqlx::query(sql);
+            for v in row {
+                query = query.bind(text_param(v));
+            }
+            query.execute(&mut *tx).await?;
+        }
+        if dry_run {
+            tx.rollback().await
+        } else {
+            tx.commit().await
+        }
+    }
+
+    async fn apply_changes(&self, statements: &[EditStatement]) -> Result<(), sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let set_path = format!(
+            "SET search_path TO {}, public",
+            self.quote_ident(&self.schema())
+        );
+        conn.execute(set_path.as_str()).await?;
+
+        // Dropping the transaction on an early return rolls it back.
+        let mut tx = conn.begin().await?;
+        for stmt in statements {
+            let mut query = sqlx::query(&stmt.sql);
+            for v in &stmt.params {
+                query = query.bind(text_param(v));
+            }
+            // None means the row changed since it was read; more, that the
+            // key isn't unique after all (NULLs in a unique index).
+            if query.execute(&mut *tx).await?.rows_affected() != 1 {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn estimate_rows(&self, table: &str) -> Result<Option<u64>, sqlx::Error> {
+        let n: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT reltuples::bigint
+            FROM pg_class
+            WHERE oid = to_regclass($1)
+            "#,
+        )
+        .bind(self.qualified(table))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        // -1 means the table has never been analyzed.
+        Ok(n.and_then(|n| u64::try_from(n).ok()))
+    }
+
+    async fn execute(
+        &self,
+        id: u64,
+        sql: &str,
+        sink: &mut dyn RowSink,
+    ) -> Result<QueryResult, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+
+        // Pooled sessions don't remember a schema switch, so set it each time.
+        let set_path = format!(
+            "SET search_path TO {}, public",
+            self.quote_ident(&self.schema())
+        );
+        conn.execute(set_path.as_str()).await?;
+
+        // Remember which backend runs the statement so `cancel` can signal it.
+        let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&mut *conn)
+            .await?;
+        let _running = self.running.track(id, pid);
+
+        // Simple query protocol (a plain `&str` executor): allows multiple
+        // statements and returns every value in text format.
+        drain_results(
+            sql,
+            conn.fetch_many(sql),
+            PgQueryResult::rows_affected,
+            decode_row,
+            sink,
+        )
+        .await
+    }
+
+    async fn cancel(&self, id: u64) -> Result<(), sqlx::Error> {
+        let Some(pid) = self.running.get(id) else {
+            return Ok(());
+        };
+        sqlx::query("SELECT pg_cancel_backend($1)")
+            .bind(pid)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+/// `v` in Postgres' text input format, for a placeholder cast to its column type.
+fn text_param(v: &CellValue) -> Option<String> {
+    match v {
+        CellValue::Null => None,
+        CellValue::Bytes(b) => {
+            let hex: String = b.iter().map(|b| format!("{:02x}", b)).collect();
+            Some(format!("\\x{}", hex))
+        }
+        v => Some(v.to_string()),
+    }
+}
+
+pub(crate) fn decode_row(row: &PgRow) -> Vec<CellValue> {
+    (0..row.len()).map(|i| decode_cell(row, i)).collect()
+}
+
+fn decode_cell(row: &PgRow, idx: usize) -> CellValue {
+    let Ok(raw) = row.try_get_raw(idx) else {
+        return CellValue::Null;
+    };
+    if raw.is_null() {
+        return CellValue::Null;
+    }
+    let type_name = row.column(idx).type_info().name().to_string();
+
+    match raw.format() {
+        PgValueFormat::Text => match raw.as_str() {
+            Ok(s) => from_text(&type_name, s),
+            Err(_) => CellValue::Null,
+        },
+        PgValueFormat::Binary => from_binary(row, idx, &type_name),
+    }
+}
+
+fn from_text(type_name: &str, s: &str) -> CellValue {
+    let parsed = match type_name {
+        "BOOL" => Some(CellValue::Bool(s == "t")),
+        "INT2" | "INT4" | "INT8" => s.parse().ok().map(CellValue::Int),
+        "OID" => s.parse().ok().map(CellValue::UInt),
+        "FLOAT4" | "FLOAT8" => s.parse().ok().map(CellValue::Float),
+        "NUMERIC" | "MONEY" => Some(CellValue::Decimal(s.to_string())),
+        "DATE" => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .map(CellValue::Date),
+        "TIMESTAMP" => NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .map(CellValue::DateTime),
+        "TIME" => NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+            .ok()
+            .map(CellValue::Time),
+        "JSON" | "JSONB" => Some(CellValue::Json(s.to_string())),
+        "BYTEA" => s
+            .strip_prefix("\\x")
+            .and_then(decode_hex)
+            .map(CellValue::Bytes),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| CellValue::Text(s.to_string()))
+}
+
+fn from_binary(row: &PgRow, idx: usize, type_name: &str) -> CellValue {
+    let decoded = match type_name {
+        "BOOL" => row.try_get_unchecked::<bool, _>(idx).map(CellValue::Bool),
+        "INT2" => row
+            .try_get_unchecked::<i16, _>(idx)
+            .map(|v| CellValue::Int(v.into())),
+        "INT4" => row
+            .try_get_unchecked::<i32, _>(idx)
+            .map(|v| CellValue::Int(v.into())),
+        "INT8" => row.try_get_unchecked::<i64, _>(idx).map(CellValue::Int),
+        "FLOAT4" => row
+            .try_get_unchecked::<f32, _>(idx)
+            .map(|v| CellValue::Float(v.into())),
+        "FLOAT8" => row.try_get_unchecked::<f64, _>(idx).map(CellValue::Float),
+        "DATE" => row
+            .try_get_unchecked::<NaiveDate, _>(idx)
+            .map(CellValue::Date),
+        "TIMESTAMP" => row
+            .try_get_unchecked::<NaiveDateTime, _>(idx)
+            .map(CellValue::DateTime),
+        "TIMESTAMPTZ" => row
+            .try_get_unchecked::<DateTime<Utc>, _>(idx)
+            .map(|v| CellValue::Text(v.to_rfc3339())),
+        "TIME" => row
+            .try_get_unchecked::<NaiveTime, _>(idx)
+            .map(CellValue::Time),
+        "BYTEA" => row
+            .try_get_unchecked::<Vec<u8>, _>(idx)
+            .map(CellValue::Bytes),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CHAR" | "CITEXT" | "UNKNOWN" => {
+            row.try_get_unchecked::<String, _>(idx).map(CellValue::Text)
+        }
+        _ => {
+            // Types without a sqlx decoder in our feature set: decode the wire format ourselves.
+            let bytes = row.try_get_raw(idx).ok().and_then(|r| r.as_bytes().ok());
+            return match (type_name, bytes) {
+                ("JSON", Some(b)) => CellValue::Json(String::from_utf8_lossy(b).into_owned()),
+                // JSONB binary = 1 version byte + JSON text
+                ("JSONB", Some(b)) if !b.is_empty() => {
+                    CellValue::Json(String::from_utf8_lossy(&b[1..]).into_owned())
+                }
+                ("NUMERIC", Some(b)) => numeric_to_string(b)
+                    .map(CellValue::Decimal)
+                    .unwrap_or(CellValue::Bytes(b.to_vec())),
+                ("UUID", Some(b)) if b.len() == 16 => CellValue::Text(format_uuid(b)),
+                // Enums (and other text-like types) send their label as-is.
+                (_, Some(b)) => match std::str::from_utf8(b) {
+                    Ok(s) if !s.chars().any(char::is_control) => CellValue::Text(s.to_string()),
+                    _ => CellValue::Bytes(b.to_vec()),
+                },
+                (_, None) => CellValue::Null,
+            };
+        }
+    };
+    decoded.unwrap_or(CellValue::Null)
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn format_uuid(b: &[u8]) -> String {
+    let hex: String = b.iter().map(|x| format!("{:02x}", x)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Render Postgres' binary NUMERIC (base-10000 digit groups) as a decimal string.
+fn numeric_to_string(b: &[u8]) -> Option<String> {
+    let word = |i: usize| -> Option<i16> { Some(i16::from_be_bytes([*b.get(i)?, *b.get(i + 1)?])) };
+
+    let ndigits = word(0)? as usize;
+    let weight = word(2)? as i32;
+    let sign = word(4)? as u16;
+    let dscale = word(6)? as u16 as usize;
+
+    match sign {
+        0xC000 => return Some("NaN".to_string()),
+        0xD000 => return Some("Infinity".to_string()),
+        0xF000 => return Some("-Infinity".to_string()),
+        _ => {}
+    }
+
+    let digits: Vec<i16> = (0..ndigits)
+        .map(|i| word(8 + 2 * i))
+        .collect::<Option<_>>()?;
+    let digit = |i: i32| {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| digits.get(i).copied())
+            .unwrap_or(0)
+    };
+
+    let mut out = String::new();
+    if sign == 0x4000 {
+        out.push('-');
+    }
+    if weight < 0 {
+        out.push('0');
+    } else {
+        out.push_str(&digit(0).to_string());
+        for i in 1..=weight {
+            out.push_str(&format!("{:04}", digit(i)));
+        }
+    }
+
+    if dscale > 0 {
+        let mut frac = String::new();
+        let mut i = weight + 1;
+        while frac.len() < dscale {
+            frac.push_str(&format!("{:04}", digit(i)));
+            i += 1;
+        }
+        frac.truncate(dscale);
+        out.push('.');
+        out.push_str(&frac);
+    }
+
+    Some(out)
+}
@@ -0,0 +1,228 @@
+use crate::domain::connection::DbKind;
+
+/// A statement within a script, as a char range (end exclusive, excluding the `;`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementSpan {
+    pub start: usize,
+    pub end: usize,
+    pub sql: String,
+}
+
+/// Split a script on `;`, ignoring semicolons inside quotes, comments and
+/// (on Postgres) dollar-quoted bodies.
+pub fn split_statements(script: &str, kind: DbKind) -> Vec<StatementSpan> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    let mut push = |start: usize, end: usize| {
+        let sql: String = chars[start..end].iter().collect();
+        if !sql.trim().is_empty() {
+            spans.push(StatementSpan {
+                start,
+                end,
+                sql: sql.trim().to_string(),
+            });
+        }
+    };
+
+    while i < chars.len() {
+        if let Some(end) = skip_literal(&chars, i, kind) {
+            i = end + 1;
+            continue;
+        }
+        if chars[i] == ';' {
+            push(start, i);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    push(start, chars.len().max(start));
+
+    spans
+}
+
+/// Index of the last char of the quoted string, comment or dollar-quoted
+/// body opening at `chars[i]`, as `kind` reads them; None when code starts
+/// there. MySQL runs the body of `/*! … */` (and MariaDB that of `/*M! … */`),
+/// so only its opener is skipped.
+fn skip_literal(chars: &[char], i: usize, kind: DbKind) -> Option<usize> {
+    let at = |j: usize| chars.get(j).copied();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let line_end = || {
+        (i..chars.len())
+            .find(|&j| chars[j] == '\n')
+            .unwrap_or(chars.len())
+    };
+    match chars[i] {
+        q @ ('\'' | '"' | '`') => {
+            // Backslashes escape in MySQL strings and Postgres E'…' strings.
+            let e_string = kind == DbKind::Postgres
+                && q == '\''
+                && i > 0
+                && matches!(chars[i - 1], 'E' | 'e')
+                && !(i > 1 && is_word(chars[i - 2]));
+            let escapes = q != '`' && (kind == DbKind::Mysql || e_string);
+            let mut j = i + 1;
+            while j < chars.len() && chars[j] != q {
+                j += if escapes && chars[j] == '\\' { 2 } else { 1 };
+            }
+            Some(j)
+        }
+        '-' if at(i + 1) == Some('-') => Some(line_end()),
+        '#' if kind == DbKind::Mysql => Some(line_end()),
+        '/' if at(i + 1) == Some('*') => {
+            let bang = match (at(i + 2), at(i + 3)) {
+                (Some('!'), _) => Some(i + 2),
+                (Some('M'), Some('!')) => Some(i + 3),
+                _ => None,
+            };
+            if kind == DbKind::Mysql
+                && let Some(mut j) = bang
+            {
+                // The optional version number belongs to the opener.
+                while at(j + 1).is_some_and(|c| c.is_ascii_digit()) {
+                    j += 1;
+                }
+                return Some(j);
+            }
+            let mut j = i + 2;
+            while j < chars.len() && !(chars[j] == '*' && at(j + 1) == Some('/')) {
+                j += 1;
+            }
+            Some(j + 1)
+        }
+        // `$tag$ … $tag$`, where the tag may be empty; `$1` is a parameter.
+        '$' if kind == DbKind::Postgres && !(i > 0 && is_word(chars[i - 1])) => {
+            if at(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let close = (i + 1..chars.len()).find(|&j| !is_word(chars[j]))?;
+            if chars[close] != '$' {
+                return None;
+            }
+            let tag = &chars[i..=close];
+            let end = (close + 1..chars.len())
+                .find(|&j| chars[j..].starts_with(tag))
+                .map_or(chars.len(), |j| j + tag.len() - 1);
+            Some(end)
+        }
+        _ => None,
+    }
+}
+
+/// The statement the cursor (a char offset) sits in or directly after.
+pub fn statement_at(script: &str, cursor: usize, kind: DbKind) -> Option<String> {
+    let spans = split_statements(script, kind);
+    spans
+        .iter()
+        .find(|s| cursor >= s.start && cursor <= s.end)
+        .or_else(|| spans.iter().rev().find(|s| s.end <= cursor))
+        .or_else(|| spans.first())
+        .map(|s| s.sql.clone())
+}
+
+/// What a statement may do to the database, least to most harmful; the
+/// read-only and production modes act on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatementClass {
+    Read,
+    Write,
+    /// Drops, truncates or alters objects, or updates or deletes every row.
+    Destructive,
+}
+
+/// Words (and `=` signs) of a statement, upper-cased, with the depth of
+/// parentheses each sits at. Quoted strings and identifiers, comments and
+/// dollar-quoted bodies are skipped.
+fn words(sql: &str, kind: DbKind) -> Vec<(String, usize)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c.to_ascii_uppercase());
+            i += 1;
+            continue;
+        }
+        if !word.is_empty() {
+            words.push((std::mem::take(&mut word), depth));
+        }
+        if let Some(end) = skip_literal(&chars, i, kind) {
+            i = end + 1;
+            continue;
+        }
+        match c {
+            '=' => words.push(("=".to_string(), depth)),
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+    if !word.is_empty() {
+        words.push((word, depth));
+    }
+    words
+}
+
+/// Class of the statement starting at `words[0]`, judged on its own words
+/// rather than those of its subqueries.
+fn class_of(words: &[(String, usize)]) -> StatementClass {
+    let Some((first, depth)) = words.first() else {
+        return StatementClass::Read;
+    };
+    let own = || {
+        words
+            .iter()
+            .take_while(|(_, d)| d >= depth)
+            .filter(|(_, d)| d == depth)
+            .map(|(w, _)| w.as_str())
+    };
+    let writes = |w: &str| matches!(w, "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "REPLACE");
+    match first.as_str() {
+        // Only EXPLAIN ANALYZE runs what it explains.
+        "EXPLAIN" if words.iter().any(|(w, _)| w == "ANALYZE") => {
+            match words.iter().position(|(w, _)| writes(w)) {
+                Some(i) => class_of(&words[i..]),
+                None => StatementClass::Read,
+            }
+        }
+        "EXPLAIN" | "SHOW" | "DESCRIBE" | "DESC" => StatementClass::Read,
+        // Setting a pragma writes; reading one doesn't.
+        "PRAGMA" if own().any(|w| w == "=") => StatementClass::Write,
+        "PRAGMA" => StatementClass::Read,
+        // `SELECT … INTO` creates a table (Postgres) or writes a file (MySQL).
+        "SELECT" | "VALUES" | "TABLE" if own().any(|w| w == "INTO") => StatementClass::Write,
+        "SELECT" | "VALUES" | "TABLE" => StatementClass::Read,
+        // Postgres allows data-modifying statements inside a CTE.
+        "WITH" => match words.iter().position(|(w, _)| writes(w)) {
+            Some(i) => class_of(&words[i..]),
+            None if own().any(|w| w == "INTO") => StatementClass::Write,
+            None => StatementClass::Read,
+        },
+        "UPDATE" | "DELETE" if !own().any(|w| w == "WHERE") => StatementClass::Destructive,
+        "DROP" | "TRUNCATE" | "ALTER" => StatementClass::Destructive,
+        _ => StatementClass::Write,
+    }
+}
+
+/// The most harmful class among the statements of `sql`, read in `kind`'s
+/// dialect. Errs towards the more harmful class when unsure.
+pub fn classify(sql: &str, kind: DbKind) -> StatementClass {
+    split_statements(sql, kind)
+        .iter()
+        .map(|s| class_of(&words(&s.sql, kind)))
+        .max()
+        .unwrap_or(StatementClass::Read)
+}
+
+/// Whether `sql` is a single statement that only reads, so it is safe to run
+/// again (e.g. to stream its full result). Errs on the side of `false`.
+pub fn is_read_only(sql: &str, kind: DbKind) -> bool {
+    split_statements(sql, kind).len() == 1 && classify(sql, kind) == StatementClass::Read
+}
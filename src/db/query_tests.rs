@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::query::{
+        StatementClass, classify, is_read_only, split_statements, statement_at,
+    };
+    use crate::domain::connection::DbKind;
+
+    #[test]
+    fn split_ignores_semicolons_in_strings_and_comments() {
+        let script = "SELECT ';' AS a; -- x; y\nSELECT `b;c` FROM t /* ; */;\n";
+        let sqls: Vec<String> = split_statements(script, DbKind::Mysql)
+            .into_iter()
+            .map(|s| s.sql)
+            .collect();
+        assert_eq!(
+            sqls,
+            vec![
+                "SELECT ';' AS a".to_string(),
+                "-- x; y\nSELECT `b;c` FROM t /* ; */".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn statement_at_picks_the_one_under_the_cursor() {
+        let script = "SELECT 1;\nSELECT 2;\n\n";
+        assert_eq!(
+            statement_at(script, 3, DbKind::Mysql).as_deref(),
+            Some("SELECT 1")
+        );
+        assert_eq!(
+            statement_at(script, 12, DbKind::Mysql).as_deref(),
+            Some("SELECT 2")
+        );
+        // trailing blank lines fall back to the previous statement
+        assert_eq!(
+            statement_at(script, script.len(), DbKind::Mysql).as_deref(),
+            Some("SELECT 2")
+        );
+    }
+
+    #[test]
+    fn read_only_detection_skips_comments_and_refuses_writes() {
+        assert!(is_read_only(
+            "  -- latest\n/* x */ select * from t",
+            DbKind::Mysql
+        ));
+        assert!(is_read_only(
+            "WITH r AS (SELECT 1) SELECT * FROM r",
+            DbKind::Mysql
+        ));
+        assert!(is_read_only("SHOW TABLES", DbKind::Mysql));
+        assert!(!is_read_only("UPDATE t SET a = 1", DbKind::Mysql));
+        assert!(!is_read_only("SELECT 1; DELETE FROM t", DbKind::Mysql));
+        assert!(!is_read_only(
+            "WITH gone AS (DELETE FROM t RETURNING *) SELECT * FROM gone",
+            DbKind::Postgres
+        ));
+    }
+
+    #[test]
+    fn classifier_flags_unbounded_writes_and_schema_changes() {
+        use StatementClass::*;
+        let cases = [
+            ("SELECT * FROM t WHERE note = 'drop table x'", Read),
+            ("EXPLAIN DELETE FROM t", Read),
+            ("PRAGMA table_info(t)", Read),
+            ("PRAGMA journal_mode = WAL", Write),
+            ("SELECT * INTO archive FROM t", Write),
+            ("INSERT INTO t VALUES (1)", Write),
+            ("UPDATE t SET a = 1 WHERE id = 2", Write),
+            ("DELETE FROM t WHERE id IN (SELECT id FROM u)", Write),
+            ("UPDATE t SET a = 'where'", Destructive),
+            (
+                "UPDATE t SET a = (SELECT max(b) FROM u WHERE u.id = 1)",
+                Destructive,
+            ),
+            ("delete from t", Destructive),
+            ("EXPLAIN ANALYZE DELETE FROM t", Destructive),
+            (
+                "WITH gone AS (DELETE FROM t RETURNING *) SELECT * FROM gone",
+                Destructive,
+            ),
+            ("/* tidy */ TRUNCATE TABLE t", Destructive),
+            ("ALTER TABLE t ADD COLUMN c int", Destructive),
+            ("SELECT 1; DROP TABLE t", Destructive),
+        ];
+        for (sql, class) in cases {
+            assert_eq!(classify(sql, DbKind::Mysql), class, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn mysql_runs_executable_comments() {
+        for sql in ["/*!50000 DROP TABLE t */", "/*M!100100 DROP TABLE t */"] {
+            assert_eq!(classify(sql, DbKind::Mysql), StatementClass::Destructive);
+            assert_eq!(classify(sql, DbKind::Postgres), StatementClass::Read);
+        }
+    }
+
+    #[test]
+    fn hash_starts_a_comment_only_on_mysql() {
+        let sql = "SELECT 5 # 3; DROP TABLE t";
+        assert_eq!(classify(sql, DbKind::Mysql), StatementClass::Read);
+        assert_eq!(classify(sql, DbKind::Postgres), StatementClass::Destructive);
+        assert_eq!(split_statements(sql, DbKind::Sqlite).len(), 2);
+    }
+
+    #[test]
+    fn postgres_dollar_quoted_bodies_stay_whole() {
+        let script = "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql;\n\
+                      DO $body$ BEGIN DELETE FROM t; END $body$;\n\
+                      SELECT $1";
+        let sqls: Vec<String> = split_statements(script, DbKind::Postgres)
+            .into_iter()
+            .map(|s| s.sql)
+            .collect();
+        assert_eq!(
+            sqls,
+            vec![
+                "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql".to_string(),
+                "DO $body$ BEGIN DELETE FROM t; END $body$".to_string(),
+                "SELECT $1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn backslashes_escape_only_where_the_dialect_says_so() {
+        let sql = r"SELECT '\'; DROP TABLE t; -- '";
+        assert_eq!(classify(sql, DbKind::Mysql), StatementClass::Read);
+        assert_eq!(classify(sql, DbKind::Postgres), StatementClass::Destructive);
+        let sql = r"SELECT E'\'; DROP TABLE t; -- '";
+        assert_eq!(classify(sql, DbKind::Postgres), StatementClass::Read);
+    }
+}
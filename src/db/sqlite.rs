@@ -0,0 +1,453 @@
+use super::{
+    driver::{Driver, Page, RowSink, RowSource, Running, drain_results, drain_rows, page_select},
+    edit::EditStatement,
+    introstect::{
+        ColumnDetail, ForeignKeyInfo, ObjectKind, SchemaObject, TableDetails, fill_column_keys,
+        push_fk_column, push_index_column, push_referencing_column,
+    },
+    types::{CellValue, QueryResult, bind_cell, result_columns},
+};
+use crate::domain::{connection::DbKind, filter::CompiledFilter};
+use async_trait::async_trait;
+use sqlx::{
+    Executor, Row, SqlitePool, Statement, TypeInfo, ValueRef,
+    sqlite::{SqliteQueryResult, SqliteRow},
+};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Virtual machine instructions between checks for a cancelled statement.
+const PROGRESS_OPS: i32 = 1_000;
+
+pub struct SqliteDriver {
+    pool: SqlitePool,
+    running: Running<Arc<AtomicBool>>, // stop flag of each statement inside `execute`
+    schema: Mutex<String>,             // "main", "temp" or an attached database's name
+}
+
+impl SqliteDriver {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            running: Running::new(),
+            schema: Mutex::new("main".to_string()),
+        }
+    }
+
+    fn schema(&self) -> String {
+        self.schema.lock().unwrap().clone()
+    }
+
+    fn qualified(&self, name: &str) -> String {
+        format!(
+            "{}.{}",
+            self.quote_ident(&self.schema()),
+            self.quote_ident(name)
+        )
+    }
+
+    /// Primary key columns of `table` in key order.
+    async fn primary_key(&self, table: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT name FROM pragma_table_info(?1, ?2) WHERE pk > 0 ORDER BY pk")
+            .bind(table)
+            .bind(self.schema())
+            .fetch_all(&self.pool)
+            .await
+    }
+}
+
+#[async_trait]
+impl Driver for SqliteDriver {
+    fn kind(&self) -> DbKind {
+        DbKind::Sqlite
+    }
+
+    fn current_schema(&self) -> Option<String> {
+        Some(self.schema())
+    }
+
+    async fn use_schema(&self, name: &str) -> Result<(), sqlx::Error> {
+        if !self.list_schemas().await?.iter().any(|s| s == name) {
+            return Err(sqlx::Error::Protocol(format!(
+                "no database named \"{}\" is attached",
+                name
+            )));
+        }
+        *self.schema.lock().unwrap() = name.to_string();
+        Ok(())
+    }
+
+    async fn list_schemas(&self) -> Result<Vec<String>, sqlx::Error> {
+        // Attached databases play the role of schemas.
+        sqlx::query_scalar("SELECT name FROM pragma_database_list ORDER BY seq")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn list_objects(&self) -> Result<Vec<SchemaObject>, sqlx::Error> {
+        // No routines or events in SQLite.
+        let sql = format!(
+            r#"
+            SELECT name, type
+            FROM {}
+            WHERE type IN ('table', 'view', 'trigger')
+              AND name NOT LIKE 'sqlite_%'
+            "#,
+            self.qualified("sqlite_master")
+        );
+        let rows: Vec<(String, String)> = sqlx::query_as(&sql).fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, kind)| {
+                let kind = match kind.as_str() {
+                    "table" => ObjectKind::Table,
+                    "view" => ObjectKind::View,
+                    _ => ObjectKind::Trigger,
+                };
+                SchemaObject::new(kind, name)
+            })
+            .collect())
+    }
+
+    async fn object_definition(&self, object: &SchemaObject) -> Result<String, sqlx::Error> {
+        let sql = format!(
+            "SELECT sql FROM {} WHERE name = ?1",
+            self.qualified("sqlite_master")
+        );
+        let def: Option<String> = sqlx::query_scalar(&sql)
+            .bind(&object.name)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(def.map(|d| format!("{};", d)).unwrap_or_default())
+    }
+
+    async fn describe_table(&self, table: &str) -> Result<TableDetails, sqlx::Error> {
+        // table_xinfo (unlike table_info) also lists generated columns.
+        let mut columns: Vec<ColumnDetail> = sqlx::query(
+            r#"
+            SELECT name, type, "notnull", dflt_value, pk, hidden
+            FROM pragma_table_xinfo(?1, ?2)
+            ORDER BY cid
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|r| {
+            let column_type: String = r.try_get(1).unwrap_or_default();
+            ColumnDetail {
+                name: r.try_get(0).unwrap_or_default(),
+                // Declared type without its length/precision, e.g. "varchar".
+                data_type: column_type
+                    .split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase(),
+                column_type,
+                is_nullable: r.try_get::<i64, _>(2).map(|v| v == 0).unwrap_or(true),
+                column_key: (r.try_get::<i64, _>(4).unwrap_or(0) > 0).then(|| "PRI".to_string()),
+                default: r.try_get(3).unwrap_or(None),
+                extra: match r.try_get::<i64, _>(5).unwrap_or(0) {
+                    2 | 3 => "generated".to_string(),
+                    _ => String::new(),
+                },
+                comment: String::new(),
+                charset: None,
+                collation: None,
+            }
+        })
+        .collect();
+
+        let mut indexes = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT il.name, ii.name, il."unique", il.origin = 'pk'
+            FROM pragma_index_list(?1, ?2) AS il
+            JOIN pragma_index_info(il.name, ?2) AS ii
+            ORDER BY il.origin <> 'pk', il.name, ii.seqno
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            push_index_column(
+                &mut indexes,
+                r.try_get(0).unwrap_or_default(),
+                r.try_get::<Option<String>, _>(1)
+                    .unwrap_or(None)
+                    .unwrap_or_else(|| "(expr)".to_string()),
+                r.try_get(2).unwrap_or(false),
+                r.try_get(3).unwrap_or(false),
+                "BTREE".to_string(),
+            );
+        }
+        fill_column_keys(&mut columns, &indexes);
+
+        // SQLite foreign keys are unnamed; `to` is NULL when it targets the
+        // parent's primary key implicitly, which is looked up below.
+        let mut foreign_keys = Vec::new();
+        let rows = sqlx::query(
+            r#"
+            SELECT id, "from", "table", "to", on_update, on_delete
+            FROM pragma_foreign_key_list(?1, ?2)
+            ORDER BY id, seq
+            "#,
+        )
+        .bind(table)
+        .bind(self.schema())
+        .fetch_all(&self.pool)
+        .await?;
+        for r in &rows {
+            push_fk_column(
+                &mut foreign_keys,
+                format!("fk_{}", r.try_get::<i64, _>(0).unwrap_or_default()),
+                r.try_get(1).unwrap_or_default(),
+                r.try_get(2).unwrap_or_default(),
+                r.try_get::<Option<String>, _>(3)
+                    .unwrap_or(None)
+                    .unwrap_or_else(|| "(pk)".to_string()),
+                r.try_get(4).unwrap_or_default(),
+                r.try_get(5).unwrap_or_default(),
+            );
+        }
+        for fk in foreign_keys.iter_mut() {
+            if fk.ref_columns.iter().all(|c| c == "(pk)") {
+                let pk = self.primary_key(&fk.ref_table).await?;
+                if pk.len() == fk.columns.len() {
+                    fk.ref_columns = pk;
+                }
+            }
+        }
+
+        // No catalog lists keys pointing at a table, so ask every table.
+        let sql = format!(
+            r#"
+            SELECT m.name, f.id, f."from", f."to", f.on_update, f.on_delete, f.seq
+            FROM {} AS m
+            JOIN pragma_foreign_key_list(m.name, ?2) AS f
+            WHERE m.type = 'table'
+              AND f."table" = ?1 COLLATE NOCASE
+            ORDER BY m.name, f.id, f.seq
+            "#,
+            self.qualified("sqlite_master")
+        );
+        let rows = sqlx::query(&sql)
+            .bind(table)
+            .bind(self.schema())
+            .fetch_all(&self.pool)
+            .await?;
+        let mut referenced_by = Vec::new();
+        let pk = self.primary_key(table).await?;
+        for r in &rows {
+            let to: Option<String> = r.try_get(3).unwrap_or(None);
+            let seq = r.try_get::<i64, _>(6).unwrap_or_default() as usize;
+            let key = ForeignKeyInfo {
+                name: format!("fk_{}", r.try_get::<i64, _>(1).unwrap_or_default()),
+                columns: vec![r.try_get(2).unwrap_or_default()],
+                ref_table: table.to_string(),
+                ref_columns: vec![to.or_else(|| pk.get(seq).cloned()).unwrap_or_default()],
+                on_update: r.try_get(4).unwrap_or_default(),
+                on_delete: r.try_get(5).unwrap_or_default(),
+            };
+            push_referencing_column(&mut referenced_by, r.try_get(0).unwrap_or_default(), key);
+        }
+
+        let sql = format!(
+            r#"
+            SELECT sql
+            FROM {}
+            WHERE tbl_name = ?1
+              AND sql IS NOT NULL
+            ORDER BY type <> 'table', name
+            "#,
+            self.qualified("sqlite_master")
+        );
+        let ddl: Vec<String> = sqlx::query_scalar(&sql)
+            .bind(table)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(TableDetails {
+            table: table.to_string(),
+            columns,
+            indexes,
+            foreign_keys,
+            referenced_by,
+            ddl: ddl
+                .iter()
+                .map(|s| format!("{};", s))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        })
+    }
+
+    async fn fetch_page(
+        &self,
+        table: &str,
+        order_by: Option<&str>,
+        filter: Option<&CompiledFilter>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Page, sqlx::Error> {
+        let from = self.qualified(table);
+        let mut sql = page_select(|c| self.quote_ident(c), &from, order_by, filter);
+        sql.push_str(" LIMIT ? OFFSET ?");
+
+        // Prepare first so column metadata is available even for an empty page.
+        let stmt = self.pool.prepare(&sql).await?;
+        let columns = result_columns(stmt.columns());
+
+        let mut query = stmt.query();
+        for p in filter.map(|f| f.params.as_slice()).unwrap_or_default() {
+            query = bind_cell(query, p);
+        }
+        let rows = query
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(decode_row)
+            .collect();
+
+        Ok(Page { columns, rows })
+    }
+
+    async fn stream_rows(
+        &self,
+        source: &RowSource,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        let (sql, params) = source.select(|c| self.quote_ident(c), |t| self.qualified(t));
+        let stmt = conn.prepare(&sql).await?;
+        sink.columns(&result_columns(stmt.columns())).await?;
+
+        let mut query = stmt.query();
+        for p in params {
+            query = bind_cell(query, p);
+        }
+        drain_rows(query.fetch(&mut *conn), decode_row, sink).await
+    }
+
+    async fn write_rows(
+        &self,
+        sql: &str,
+        rows: &[Vec<CellValue>],
+        dry_run: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let mut query = sqlx::query(sql);
+            for v in row {
+                query = bind_cell(query, v);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        if dry_run {
+            tx.rollback().await
+        } else {
+            tx.commit().await
+        }
+    }
+
+    async fn apply_changes(&self, statements: &[EditStatement]) -> Result<(), sqlx::Error> {
+        // Dropping the transaction on an early return rolls it back.
+        let mut tx = self.pool.begin().await?;
+        for stmt in statements {
+            let mut query = sqlx::query(&stmt.sql);
+            for v in &stmt.params {
+                query = bind_cell(query, v);
+            }
+            // None means the row changed since it was read; more, that the
+            // key isn't unique after all (NULLs in a unique index).
+            if query.execute(&mut *tx).await?.rows_affected() != 1 {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn estimate_rows(&self, table: &str) -> Result<Option<u64>, sqlx::Error> {
+        // No catalog statistics to lean on; counting a local file is cheap enough.
+        let sql = format!("SELECT count(*) FROM {}", self.qualified(table));
+        let n: i64 = sqlx::query_scalar(&sql).fetch_one(&self.pool).await?;
+        Ok(Some(n as u64))
+    }
+
+    async fn execute(
+        &self,
+        id: u64,
+        sql: &str,
+        sink: &mut dyn RowSink,
+    ) -> Result<QueryResult, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+
+        // No server to signal: the engine polls a progress handler while the
+        // statement runs, and `cancel` makes it answer "stop", which fails
+        // the statement with SQLITE_INTERRUPT.
+        let stop = Arc::new(AtomicBool::new(false));
+        let _running = self.running.track(id, stop.clone());
+        conn.lock_handle()
+            .await?
+            .set_progress_handler(PROGRESS_OPS, move || !stop.load(Ordering::Relaxed));
+
+        let res = drain_results(
+            sql,
+            conn.fetch_many(sql),
+            SqliteQueryResult::rows_affected,
+            decode_row,
+            sink,
+        )
+        .await;
+
+        // The session goes back to the pool. A task aborted before this point
+        // leaves its handler behind, but only ever as the pool closes.
+        conn.lock_handle().await?.remove_progress_handler();
+        res
+    }
+
+    async fn cancel(&self, id: u64) -> Result<(), sqlx::Error> {
+        if let Some(stop) = self.running.get(id) {
+            stop.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+fn decode_row(row: &SqliteRow) -> Vec<CellValue> {
+    (0..row.len()).map(|i| decode_cell(row, i)).collect()
+}
+
+/// SQLite is dynamically typed, so decode by each value's storage class rather
+/// than the column's declared type.
+fn decode_cell(row: &SqliteRow, idx: usize) -> CellValue {
+    let storage = match row.try_get_raw(idx) {
+        Ok(raw) if raw.is_null() => return CellValue::Null,
+        Ok(raw) => raw.type_info().name().to_string(),
+        Err(_) => return CellValue::Null,
+    };
+
+    let decoded = match storage.as_str() {
+        "INTEGER" => row.try_get_unchecked::<i64, _>(idx).map(CellValue::Int),
+        "REAL" => row.try_get_unchecked::<f64, _>(idx).map(CellValue::Float),
+        "BLOB" => row
+            .try_get_unchecked::<Vec<u8>, _>(idx)
+            .map(CellValue::Bytes),
+        _ => row.try_get_unchecked::<String, _>(idx).map(CellValue::Text),
+    };
+
+    decoded.unwrap_or(CellValue::Null)
+}
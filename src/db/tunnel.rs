@@ -0,0 +1,190 @@
+//! Local port-forwards through an SSH bastion. The system `ssh` client does
+//! the work, so keys, agents, known_hosts and `~/.ssh/config` behave exactly
+//! as they do in a shell.
+
+use super::connect::expand_home;
+use crate::domain::connection::SshTunnel;
+use std::{
+    io,
+    net::{Ipv4Addr, TcpListener},
+    process::Stdio,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+use tokio::{
+    io::AsyncReadExt,
+    net::TcpStream,
+    process::{Child, Command},
+};
+
+/// Environment variable carrying a key passphrase to the askpass helper; see
+/// [`askpass_reply`].
+pub const PASSPHRASE_ENV: &str = "LENSQL_SSH_PASSPHRASE";
+
+/// How long ssh gets to authenticate and start listening.
+const OPEN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Local ports tried before giving up when each is taken before ssh binds it.
+const PORT_ATTEMPTS: u32 = 3;
+
+/// What ssh logs when the local end of a forward can't be bound.
+const PORT_TAKEN: &str = "Address already in use";
+
+#[derive(Debug, Error)]
+pub enum TunnelError {
+    #[error("Could not start ssh: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("SSH tunnel via {host} failed: {message}")]
+    Failed { host: String, message: String },
+
+    #[error("SSH tunnel via {0} timed out")]
+    Timeout(String),
+}
+
+/// A running `ssh -N -L` process. Dropping it kills ssh.
+#[derive(Debug)]
+pub struct Tunnel {
+    child: Child,
+    port: u16,
+}
+
+impl Tunnel {
+    /// Forward a free local port to `host:port` as seen from the bastion, and
+    /// wait until the forward accepts connections. The port is picked before
+    /// ssh binds it, so if another process takes it meanwhile ssh gives up
+    /// and a fresh port is tried.
+    pub async fn open(
+        ssh: &SshTunnel,
+        passphrase: &str,
+        host: &str,
+        port: u16,
+    ) -> Result<Self, TunnelError> {
+        let deadline = Instant::now() + OPEN_TIMEOUT;
+        let mut attempt = 1;
+        'attempts: loop {
+            let local = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+                .local_addr()?
+                .port();
+            let mut cmd = Command::new("ssh");
+            cmd.args(ssh_args(ssh, !passphrase.is_empty(), local, host, port))
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+            if !passphrase.is_empty() {
+                // ssh asks us back for the passphrase; see `askpass_reply`.
+                cmd.env("SSH_ASKPASS", std::env::current_exe()?)
+                    .env("SSH_ASKPASS_REQUIRE", "force")
+                    .env(PASSPHRASE_ENV, passphrase);
+            }
+            let mut child = cmd.spawn()?;
+
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    let mut stderr = String::new();
+                    if let Some(mut err) = child.stderr.take() {
+                        let _ = err.read_to_string(&mut stderr).await;
+                    }
+                    if stderr.contains(PORT_TAKEN) && attempt < PORT_ATTEMPTS {
+                        attempt += 1;
+                        continue 'attempts;
+                    }
+                    let message = stderr
+                        .lines()
+                        .map(str::trim)
+                        .rfind(|l| !l.is_empty())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("ssh exited with {}", status));
+                    return Err(TunnelError::Failed {
+                        host: ssh.host.clone(),
+                        message,
+                    });
+                }
+                if TcpStream::connect((Ipv4Addr::LOCALHOST, local))
+                    .await
+                    .is_ok()
+                {
+                    // Keep reading what ssh reports later (e.g. dropped
+                    // forwards), so a full pipe never blocks it.
+                    if let Some(mut err) = child.stderr.take() {
+                        tokio::spawn(async move {
+                            let _ = tokio::io::copy(&mut err, &mut tokio::io::sink()).await;
+                        });
+                    }
+                    return Ok(Self { child, port: local });
+                }
+                if Instant::now() >= deadline {
+                    let _ = child.kill().await;
+                    return Err(TunnelError::Timeout(ssh.host.clone()));
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+
+    /// Local port the database is reachable on.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.child.kill().await;
+    }
+}
+
+/// Arguments for a forward-only ssh that never prompts on the terminal: with
+/// no passphrase it runs in batch mode, otherwise it asks the askpass helper
+/// once. Only public keys are tried, so the passphrase can't be sent as a
+/// login password.
+pub(crate) fn ssh_args(
+    ssh: &SshTunnel,
+    askpass: bool,
+    local: u16,
+    host: &str,
+    port: u16,
+) -> Vec<String> {
+    let mut args: Vec<String> = ["-N", "-T"].map(String::from).into();
+    let mut opt = |o: &str| {
+        args.push("-o".into());
+        args.push(o.into());
+    };
+    opt("ExitOnForwardFailure=yes");
+    opt("ConnectTimeout=10");
+    opt("ServerAliveInterval=30");
+    opt("LogLevel=ERROR");
+    opt("PreferredAuthentications=publickey");
+    if ssh.skip_host_key_check {
+        opt("StrictHostKeyChecking=no");
+        opt("UserKnownHostsFile=/dev/null");
+    } else {
+        opt("StrictHostKeyChecking=yes");
+    }
+    if askpass {
+        opt("NumberOfPasswordPrompts=1");
+    } else {
+        opt("BatchMode=yes");
+    }
+    if let Some(key) = ssh.key_file.as_deref() {
+        opt("IdentitiesOnly=yes");
+        args.push("-i".into());
+        args.push(expand_home(key).to_string_lossy().into_owned());
+    }
+    args.push("-L".into());
+    args.push(format!("127.0.0.1:{}:{}:{}", local, host, port));
+    args.push("-p".into());
+    args.push(ssh.port.to_string());
+    if !ssh.user.is_empty() {
+        args.push("-l".into());
+        args.push(ssh.user.clone());
+    }
+    args.push("--".into());
+    args.push(ssh.host.clone());
+    args
+}
+
+/// When ssh runs this binary as its askpass helper, the passphrase to print.
+pub fn askpass_reply() -> Option<String> {
+    std::env::var_os("SSH_ASKPASS")?;
+    std::env::var(PASSPHRASE_ENV).ok()
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::tunnel::{Tunnel, TunnelError, ssh_args};
+    use crate::domain::connection::SshTunnel;
+
+    fn bastion() -> SshTunnel {
+        SshTunnel {
+            host: "bastion.example".into(),
+            port: 2222,
+            user: String::new(),
+            key_file: None,
+            skip_host_key_check: false,
+        }
+    }
+
+    #[test]
+    fn agent_tunnel_runs_in_batch_mode_with_host_key_checks() {
+        let args = ssh_args(&bastion(), false, 40001, "db.internal", 3306);
+        let has = |a: &str| args.iter().any(|x| x == a);
+        assert!(has("BatchMode=yes") && has("StrictHostKeyChecking=yes"));
+        assert!(!has("-i") && !has("-l") && !has("NumberOfPasswordPrompts=1"));
+        assert!(args.ends_with(&[
+            "-L".into(),
+            "127.0.0.1:40001:db.internal:3306".into(),
+            "-p".into(),
+            "2222".into(),
+            "--".into(),
+            "bastion.example".into(),
+        ]));
+    }
+
+    #[test]
+    fn key_file_tunnel_offers_only_that_key_and_asks_once() {
+        let ssh = SshTunnel {
+            user: "deploy".into(),
+            key_file: Some("/keys/id_ed25519".into()),
+            skip_host_key_check: true,
+            ..bastion()
+        };
+        let args = ssh_args(&ssh, true, 40001, "db.internal", 5432);
+        let at = |a: &str| args.iter().position(|x| x == a).unwrap();
+        assert_eq!(args[at("-i") + 1], "/keys/id_ed25519");
+        assert_eq!(args[at("-l") + 1], "deploy");
+        assert!(args.contains(&"IdentitiesOnly=yes".into()));
+        assert!(args.contains(&"NumberOfPasswordPrompts=1".into()));
+        assert!(args.contains(&"UserKnownHostsFile=/dev/null".into()));
+        assert!(!args.contains(&"BatchMode=yes".into()));
+    }
+
+    #[tokio::test]
+    async fn unreachable_bastion_reports_sshs_reason() {
+        if std::process::Command::new("ssh")
+            .arg("-V")
+            .output()
+            .is_err()
+        {
+            return; // no ssh client here
+        }
+        let ssh = SshTunnel {
+            host: "127.0.0.1".into(),
+            port: 1,
+            ..bastion()
+        };
+        match Tunnel::open(&ssh, "", "db.internal", 3306).await {
+            Err(TunnelError::Failed { host, message }) => {
+                assert_eq!(host, "127.0.0.1");
+                assert!(message.contains("refused"), "{}", message);
+            }
+            other => panic!("unexpected: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use sqlx::{
+    Column, Database, Encode, Type, TypeInfo,
+    query::Query,
+    types::chrono::{NaiveDate, NaiveDateTime, NaiveTime},
+};
+use std::{borrow::Cow, collections::VecDeque, fmt, ops::Range, time::Duration};
+
+/// A single decoded cell from a result row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Decimal(String),
+    Text(String),
+    Json(String),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    Time(NaiveTime),
+    Bytes(Vec<u8>),
+}
+
+/// Binary values longer than this are cut short in the record view.
+const DETAIL_BYTES: usize = 16 * 1024;
+
+/// Memory a Runner result may hold in rows, as estimated by [`CellValue::size`].
+///
+/// A result is read only `STREAM_AHEAD` rows ahead of the grid's cursor, so a
+/// long one, and anything past this cap, keeps its statement open while the
+/// grid waits. MySQL closes a result left unread for longer than
+/// `net_write_timeout` (60 s by default); the Runner then keeps the rows it
+/// has and asks for the statement to be re-run.
+pub const RESULT_BYTES: usize = 32 * 1024 * 1024;
+
+impl CellValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, CellValue::Null)
+    }
+
+    /// Rough bytes the value takes in memory, for capping buffered results.
+    pub fn size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + match self {
+                CellValue::Decimal(s) | CellValue::Text(s) | CellValue::Json(s) => s.len(),
+                CellValue::Bytes(b) => b.len(),
+                _ => 0,
+            }
+    }
+
+    /// The whole value for the record view, one entry per line: JSON (and
+    /// text holding a JSON object or array) pretty-printed, binary as a hex
+    /// dump with offsets and printable ASCII, anything else split at line
+    /// breaks. Never empty.
+    pub fn detail_lines(&self) -> Vec<String> {
+        let text = match self {
+            CellValue::Bytes(b) if !b.is_empty() => return hex_dump(b),
+            CellValue::Json(s) => pretty_json(s).unwrap_or_else(|| s.clone()),
+            CellValue::Text(s) if s.trim_start().starts_with(['{', '[']) => {
+                pretty_json(s).unwrap_or_else(|| s.clone())
+            }
+            v => v.to_string(),
+        };
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines
+    }
+}
+
+fn pretty_json(s: &str) -> Option<String> {
+    let v: serde_json::Value = serde_json::from_str(s).ok()?;
+    serde_json::to_string_pretty(&v).ok()
+}
+
+/// `00000010  ca fe 41 …  |..A…|`, 16 bytes a line.
+fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    let shown = &bytes[..bytes.len().min(DETAIL_BYTES)];
+    let mut lines: Vec<String> = shown
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", i * 16, hex.join(" "), ascii)
+        })
+        .collect();
+    if bytes.len() > shown.len() {
+        lines.push(format!("… {} more bytes", bytes.len() - shown.len()));
+    }
+    lines
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Null => write!(f, "NULL"),
+            CellValue::Bool(v) => write!(f, "{}", v),
+            CellValue::Int(v) => write!(f, "{}", v),
+            CellValue::UInt(v) => write!(f, "{}", v),
+            CellValue::Float(v) => write!(f, "{}", v),
+            CellValue::Decimal(v) | CellValue::Text(v) | CellValue::Json(v) => write!(f, "{}", v),
+            CellValue::Date(v) => write!(f, "{}", v),
+            CellValue::DateTime(v) => write!(f, "{}", v),
+            CellValue::Time(v) => write!(f, "{}", v),
+            CellValue::Bytes(v) => {
+                write!(f, "0x")?;
+                for b in v.iter().take(32) {
+                    write!(f, "{:02X}", b)?;
+                }
+                if v.len() > 32 {
+                    write!(f, "…")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Result column name plus the driver-reported type name (e.g. "BIGINT UNSIGNED").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultColumn {
+    pub name: String,
+    pub type_name: String,
+}
+
+/// Rows of a streamed result, oldest first, in a ring buffer of at most `cap`
+/// estimated bytes: appending past it drops rows from the front.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowBuffer {
+    rows: VecDeque<Vec<CellValue>>,
+    bytes: usize,
+    cap: usize,
+    dropped: u64, // rows gone from the front
+}
+
+impl RowBuffer {
+    pub fn with_cap(cap: usize) -> Self {
+        Self {
+            rows: VecDeque::new(),
+            bytes: 0,
+            cap,
+            dropped: 0,
+        }
+    }
+
+    /// Append `rows`, then drop the oldest (never the newest) until the buffer
+    /// fits its cap again. Returns how many were dropped.
+    pub fn push(&mut self, rows: Vec<Vec<CellValue>>) -> usize {
+        for row in rows {
+            self.bytes += row_size(&row);
+            self.rows.push_back(row);
+        }
+        let mut dropped = 0;
+        while self.bytes > self.cap && self.rows.len() > 1 {
+            if let Some(row) = self.rows.pop_front() {
+                self.bytes -= row_size(&row);
+                dropped += 1;
+            }
+        }
+        self.dropped += dropped as u64;
+        dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> Option<&Vec<CellValue>> {
+        self.rows.get(i)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<CellValue>> {
+        self.rows.iter()
+    }
+
+    /// Every held row as one slice, moving them into one run first if the
+    /// ring buffer has wrapped.
+    pub fn as_slice(&mut self) -> &[Vec<CellValue>] {
+        self.rows.make_contiguous()
+    }
+
+    /// Held rows `range`, borrowed when they sit in one run of the ring
+    /// buffer and copied only when they straddle its wrap.
+    pub fn window(&self, range: Range<usize>) -> Cow<'_, [Vec<CellValue>]> {
+        let (front, back) = self.rows.as_slices();
+        let split = front.len();
+        if range.end <= split {
+            Cow::Borrowed(&front[range])
+        } else if range.start >= split {
+            Cow::Borrowed(&back[range.start - split..range.end - split])
+        } else {
+            Cow::Owned(self.rows.range(range).cloned().collect())
+        }
+    }
+
+    /// Rows dropped from the front so far; the first held row is this one
+    /// (0-based) of the whole result.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Rows received in all, held or dropped.
+    pub fn fetched(&self) -> u64 {
+        self.dropped + self.rows.len() as u64
+    }
+
+    /// "rows 1–N dropped" once the cap has pushed rows out; they are not
+    /// fetched again short of re-running the statement.
+    pub fn dropped_label(&self) -> Option<String> {
+        (self.dropped > 0).then(|| format!("rows 1–{} dropped", self.dropped))
+    }
+}
+
+impl Default for RowBuffer {
+    fn default() -> Self {
+        Self::with_cap(RESULT_BYTES)
+    }
+}
+
+impl std::ops::Index<usize> for RowBuffer {
+    type Output = Vec<CellValue>;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.rows[i]
+    }
+}
+
+impl From<Vec<Vec<CellValue>>> for RowBuffer {
+    fn from(rows: Vec<Vec<CellValue>>) -> Self {
+        let mut buf = Self::default();
+        buf.push(rows);
+        buf
+    }
+}
+
+fn row_size(row: &[CellValue]) -> usize {
+    std::mem::size_of::<Vec<CellValue>>() + row.iter().map(CellValue::size).sum::<usize>()
+}
+
+/// Outcome of an ad-hoc statement from the Runner.
+#[derive(Debug, Clone)]
+pub struct QueryResult {
+    pub sql: String,
+    pub columns: Vec<ResultColumn>,
+    pub rows: RowBuffer,
+    pub rows_affected: u64,
+    pub elapsed: Duration,
+}
+
+/// Column names + driver type names for any sqlx backend.
+pub fn result_columns<C: Column>(cols: &[C]) -> Vec<ResultColumn> {
+    cols.iter()
+        .map(|c| ResultColumn {
+            name: c.name().to_string(),
+            type_name: c.type_info().name().to_string(),
+        })
+        .collect()
+}
+
+/// Bind a filter parameter or imported value. Numbers, booleans and bytes bind
+/// natively; everything else goes as text and is left to the engine (or an
+/// explicit cast) to coerce.
+pub(crate) fn bind_cell<'q, DB>(
+    q: Query<'q, DB, <DB as Database>::Arguments<'q>>,
+    value: &CellValue,
+) -> Query<'q, DB, <DB as Database>::Arguments<'q>>
+where
+    DB: Database,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+    Option<String>: Encode<'q, DB> + Type<DB>,
+    Vec<u8>: Encode<'q, DB> + Type<DB>,
+{
+    match value {
+        CellValue::Null => q.bind(None::<String>),
+        CellValue::Bool(v) => q.bind(*v),
+        CellValue::Int(v) => q.bind(*v),
+        CellValue::UInt(v) => match i64::try_from(*v) {
+            Ok(v) => q.bind(v),
+            Err(_) => q.bind(v.to_string()),
+        },
+        CellValue::Float(v) => q.bind(*v),
+        CellValue::Bytes(v) => q.bind(v.clone()),
+        other => q.bind(other.to_string()),
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::types::{CellValue, RowBuffer};
+
+    #[test]
+    fn detail_lines_pretty_print_json_and_split_text() {
+        let json = CellValue::Json(r#"{"tags":["a"]}"#.into());
+        assert_eq!(
+            json.detail_lines(),
+            ["{", r#"  "tags": ["#, r#"    "a""#, "  ]", "}"]
+        );
+        // Text that only looks like JSON is shown as it is.
+        let text = CellValue::Text("[not json\nsecond".into());
+        assert_eq!(text.detail_lines(), ["[not json", "second"]);
+        assert_eq!(CellValue::Text(String::new()).detail_lines(), [""]);
+        assert_eq!(CellValue::Null.detail_lines(), ["NULL"]);
+    }
+
+    #[test]
+    fn detail_lines_dump_bytes_as_hex_with_ascii() {
+        let mut bytes = b"LensQL blob: \xca\xfe".to_vec();
+        bytes.extend([0u8; 4]);
+        assert_eq!(
+            CellValue::Bytes(bytes).detail_lines(),
+            [
+                "00000000  4c 65 6e 73 51 4c 20 62 6c 6f 62 3a 20 ca fe 00  |LensQL blob: ...|",
+                "00000010  00 00 00                                         |...|",
+            ]
+        );
+
+        let long = CellValue::Bytes(vec![b'x'; 16 * 1024 + 5]);
+        let lines = long.detail_lines();
+        assert_eq!(lines.len(), 1025);
+        assert_eq!(lines[1024], "… 5 more bytes");
+    }
+
+    #[test]
+    fn row_buffer_drops_the_oldest_rows_past_its_cap() {
+        let row = |i: i64| vec![CellValue::Int(i)];
+        let size = std::mem::size_of::<Vec<CellValue>>() + CellValue::Int(0).size();
+        let mut buf = RowBuffer::with_cap(2 * size);
+        assert_eq!(buf.push(vec![row(0), row(1), row(2)]), 1);
+        assert_eq!((buf.dropped(), buf.fetched(), buf.len()), (1, 3, 2));
+        assert_eq!(buf.as_slice(), [row(1), row(2)]);
+
+        // A row over the cap on its own is still kept.
+        assert_eq!(buf.push(vec![vec![CellValue::Text("x".repeat(size))]]), 2);
+        assert_eq!((buf.dropped(), buf.len()), (3, 1));
+        assert_eq!(buf.dropped_label().as_deref(), Some("rows 1–3 dropped"));
+    }
+
+    #[test]
+    fn row_buffer_windows_span_the_wrap_of_its_ring() {
+        let row = |i: i64| vec![CellValue::Int(i)];
+        let size = std::mem::size_of::<Vec<CellValue>>() + CellValue::Int(0).size();
+        let mut buf = RowBuffer::with_cap(4 * size);
+        buf.push((0..4).map(row).collect());
+        buf.push(vec![row(4), row(5)]);
+        assert_eq!(buf.dropped_label().as_deref(), Some("rows 1–2 dropped"));
+        assert_eq!(*buf.window(0..4), [row(2), row(3), row(4), row(5)]);
+        assert_eq!(*buf.window(1..2), [row(3)]);
+        assert_eq!(*buf.window(3..4), [row(5)]);
+        assert_eq!(buf.as_slice(), [row(2), row(3), row(4), row(5)]);
+        assert_eq!(buf[0], row(2));
+    }
+}
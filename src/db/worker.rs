@@ -1,143 +1,920 @@
-use crate::app::{
-    command::DbCommand,
-    event::{DbEvent, Event},
+use crate::{
+    app::{
+        command::{ConnectTarget, DbCommand},
+        event::{DbEvent, Event},
+        state::SessionId,
+    },
+    db::{
+        driver::{Driver, RowSink, STREAM_AHEAD},
+        export::{ExportFormat, ExportOptions, ExportSource, ExportWriter, SqlTable},
+        import::{
+            ImportError, ImportOptions, ImportPlan, ImportPreview, ImportReader, PREVIEW_ROWS,
+        },
+        query::{StatementClass, classify},
+        tunnel::Tunnel,
+        types::{CellValue, QueryResult, ResultColumn},
+    },
+    domain::connection::{DbKind, connection_label},
 };
-use sqlx::{MySqlPool, mysql::MySqlPoolOptions};
-use tokio::sync::mpsc;
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+};
+
+/// Why a read-only session turned a statement down.
+const READ_ONLY: &str =
+    "Read-only connection: only SELECT, SHOW, DESCRIBE and EXPLAIN statements may run";
+
+/// A Runner statement, export or import in flight.
+struct Query {
+    task: JoinHandle<()>,
+    demand: watch::Sender<Option<u64>>, // rows the grid wants; `None` stops it
+}
 
-pub async fn run(mut cmd_rx: mpsc::Receiver<DbCommand>, evt_tx: mpsc::Sender<Event>) {
-    let mut pool: Option<MySqlPool> = None;
+/// Hands each command to its session's loop, starting one the first time a
+/// session is seen, so sessions run side by side.
+pub async fn run(mut cmd_rx: mpsc::Receiver<(SessionId, DbCommand)>, evt_tx: mpsc::Sender<Event>) {
+    let mut sessions: HashMap<SessionId, mpsc::Sender<DbCommand>> = HashMap::new();
+    while let Some((session, db)) = cmd_rx.recv().await {
+        let closing = matches!(db, DbCommand::Disconnect);
+        let tx = sessions.entry(session).or_insert_with(|| {
+            let (tx, rx) = mpsc::channel(64);
+            let events = Events {
+                session,
+                tx: evt_tx.clone(),
+            };
+            tokio::spawn(run_session(rx, events));
+            tx
+        });
+        let _ = tx.send(db).await;
+        // Dropping the sender ends that loop once the disconnect is handled.
+        if closing {
+            sessions.remove(&session);
+        }
+    }
+}
+
+/// One session's events, tagged with its id on the way out.
+#[derive(Clone)]
+struct Events {
+    session: SessionId,
+    tx: mpsc::Sender<Event>,
+}
+
+impl Events {
+    async fn send(&self, evt: DbEvent) -> Result<(), mpsc::error::SendError<Event>> {
+        self.tx.send(Event::Db(self.session, evt)).await
+    }
+}
+
+async fn run_session(mut cmd_rx: mpsc::Receiver<DbCommand>, evt_tx: Events) {
+    let mut driver: Option<Arc<dyn Driver>> = None;
+    // (kind, profile name, host or file) of the open connection, for labels.
+    let mut session: Option<(DbKind, String, String)> = None;
+    let mut statement_timeout: Option<Duration> = None;
+    // The profile only reads; anything else is refused before it is sent.
+    let mut read_only = false;
+    // Port-forward the driver talks through, if the profile has a bastion.
+    let mut tunnel: Option<Tunnel> = None;
+    // Runner statements, exports and imports run as tasks, so a slow one
+    // doesn't hold up the rest.
+    let mut queries: HashMap<u64, Query> = HashMap::new();
 
     while let Some(db) = cmd_rx.recv().await {
         match db {
             DbCommand::Connect {
+                id,
                 name,
-                host,
-                port,
-                user,
-                password,
-                db,
-            } => {
-                let url = format!("mysql://{}:{}@{}:{}/{}", user, password, host, port, db);
-                match MySqlPoolOptions::new()
-                    .max_connections(5)
-                    .connect(&url)
-                    .await
-                {
-                    Ok(p) => {
-                        pool = Some(p);
-                        let display = format!("{} ({}/{})", name, host, db);
-                        let _ = evt_tx.send(Event::Db(DbEvent::Connected { display })).await;
+                target,
+                statement_timeout: timeout,
+            } => match open(&target).await {
+                Ok((d, forward)) => {
+                    if let Some(old) = driver.replace(Arc::from(d)) {
+                        stop_queries(&mut queries, old.as_ref()).await;
+                        old.close().await;
+                    }
+                    if let Some(old) = std::mem::replace(&mut tunnel, forward) {
+                        old.close().await;
+                    }
+                    statement_timeout = timeout;
+                    read_only = target.options.read_only;
+                    let ConnectTarget { kind, host, db, .. } = *target;
+                    let location = match kind {
+                        _ if kind.is_file() => db,
+                        DbKind::Postgres if !db.is_empty() => format!("{}/{}", host, db),
+                        _ => host,
+                    };
+                    let display = connection_label(
+                        kind,
+                        &name,
+                        &location,
+                        driver
+                            .as_deref()
+                            .and_then(|d| d.current_schema())
+                            .as_deref(),
+                    );
+                    session = Some((kind, name, location));
+                    let _ = evt_tx.send(DbEvent::Connected { id, display }).await;
+                }
+                Err(message) => {
+                    let _ = evt_tx.send(DbEvent::Error { message }).await;
+                }
+            },
+
+            // Runs beside the session so a slow handshake holds nothing up.
+            DbCommand::TestConnection { target } => {
+                let evt_tx = evt_tx.clone();
+                tokio::spawn(async move {
+                    let started = Instant::now();
+                    let result = match open(&target).await {
+                        Ok((d, forward)) => {
+                            let elapsed = started.elapsed();
+                            d.close().await;
+                            if let Some(t) = forward {
+                                t.close().await;
+                            }
+                            Ok(elapsed)
+                        }
+                        Err(message) => Err(message),
+                    };
+                    let _ = evt_tx.send(DbEvent::ConnectionTested { result }).await;
+                });
+            }
+
+            DbCommand::Disconnect => {
+                if let Some(d) = driver.take() {
+                    stop_queries(&mut queries, d.as_ref()).await;
+                    d.close().await;
+                }
+                if let Some(t) = tunnel.take() {
+                    t.close().await;
+                }
+                session = None;
+                read_only = false;
+                let _ = evt_tx.send(DbEvent::Disconnected).await;
+            }
+
+            DbCommand::LoadSchemas => {
+                let Some(d) = driver.as_deref() else {
+                    let _ = evt_tx
+                        .send(DbEvent::Error {
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                match d.list_schemas().await {
+                    Ok(schemas) => {
+                        let current = d.current_schema();
+                        let _ = evt_tx
+                            .send(DbEvent::SchemasLoaded { schemas, current })
+                            .await;
                     }
                     Err(e) => {
                         let _ = evt_tx
-                            .send(Event::Db(DbEvent::Error {
+                            .send(DbEvent::Error {
                                 message: e.to_string(),
-                            }))
+                            })
                             .await;
                     }
                 }
             }
 
-            DbCommand::Disconnect => {
-                pool = None;
-                let _ = evt_tx.send(Event::Db(DbEvent::Disconnected)).await;
+            DbCommand::UseSchema { name } => {
+                let (Some(d), Some((kind, profile, location))) = (driver.as_deref(), &session)
+                else {
+                    let _ = evt_tx
+                        .send(DbEvent::Error {
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                match d.use_schema(&name).await {
+                    Ok(()) => {
+                        let display = connection_label(*kind, profile, location, Some(&name));
+                        let _ = evt_tx
+                            .send(DbEvent::SchemaChanged {
+                                schema: name,
+                                display,
+                            })
+                            .await;
+                    }
+                    Err(e) => {
+                        let _ = evt_tx
+                            .send(DbEvent::Error {
+                                message: e.to_string(),
+                            })
+                            .await;
+                    }
+                }
             }
 
-            DbCommand::LoadTables => {
-                let Some(p) = pool.as_ref() else {
+            DbCommand::LoadObjects => {
+                let Some(d) = driver.as_deref() else {
                     let _ = evt_tx
-                        .send(Event::Db(DbEvent::Error {
+                        .send(DbEvent::Error {
                             message: "Not connected".into(),
-                        }))
+                        })
                         .await;
                     continue;
                 };
 
-                let res: Result<Vec<String>, sqlx::Error> = sqlx::query_scalar(
-                    r#"
-                    SELECT table_name
-                    FROM information_schema.tables
-                    WHERE table_schema = DATABASE()
-                    ORDER BY table_name
-                    "#,
-                )
-                .fetch_all(p)
-                .await;
-
-                match res {
-                    Ok(tables) => {
+                match d.list_objects().await {
+                    Ok(objects) => {
+                        let _ = evt_tx.send(DbEvent::ObjectsLoaded { objects }).await;
+                    }
+                    Err(e) => {
                         let _ = evt_tx
-                            .send(Event::Db(DbEvent::TablesLoaded { tables }))
+                            .send(DbEvent::Error {
+                                message: e.to_string(),
+                            })
                             .await;
                     }
+                }
+            }
+
+            DbCommand::DescribeTable { table } => {
+                let Some(d) = driver.as_deref() else {
+                    let _ = evt_tx
+                        .send(DbEvent::Error {
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                match d.describe_table(&table).await {
+                    Ok(details) => {
+                        let _ = evt_tx.send(DbEvent::TableDescribed { details }).await;
+                    }
                     Err(e) => {
                         let _ = evt_tx
-                            .send(Event::Db(DbEvent::Error {
+                            .send(DbEvent::Error {
                                 message: e.to_string(),
-                            }))
+                            })
                             .await;
                     }
                 }
             }
 
-            DbCommand::LoadColumns { table } => {
-                let Some(p) = pool.as_ref() else {
+            DbCommand::DescribeDataTable { table } => {
+                let Some(d) = driver.as_deref() else {
                     let _ = evt_tx
-                        .send(Event::Db(DbEvent::Error {
+                        .send(DbEvent::Error {
                             message: "Not connected".into(),
-                        }))
+                        })
                         .await;
                     continue;
                 };
 
-                let res = sqlx::query(
-                    r#"
-                    SELECT column_name, data_type, is_nullable, column_key
-                    FROM information_schema.columns
-                    WHERE table_schema = DATABASE()
-                      AND table_name = ?
-                    ORDER BY ordinal_position
-                    "#,
-                )
-                .bind(&table)
-                .fetch_all(p)
-                .await;
-
-                match res {
-                    Ok(rows) => {
-                        use sqlx::Row;
-                        let cols = rows
-                            .into_iter()
-                            .map(|r| crate::app::state::ColumnInfo {
-                                name: r.try_get::<String, _>("column_name").unwrap_or_default(),
-                                data_type: r.try_get::<String, _>("data_type").unwrap_or_default(),
-                                is_nullable: r
-                                    .try_get::<String, _>("is_nullable")
-                                    .map(|v| v == "YES")
-                                    .unwrap_or(false),
-                                column_key: r
-                                    .try_get::<Option<String>, _>("column_key")
-                                    .unwrap_or(None),
+                let evt = match d.describe_table(&table).await {
+                    Ok(details) => DbEvent::DataTableDescribed { details },
+                    Err(e) => DbEvent::Error {
+                        message: e.to_string(),
+                    },
+                };
+                let _ = evt_tx.send(evt).await;
+            }
+
+            DbCommand::LoadDefinition { object } => {
+                let Some(d) = driver.as_deref() else {
+                    let _ = evt_tx
+                        .send(DbEvent::Error {
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                match d.object_definition(&object).await {
+                    Ok(definition) => {
+                        let _ = evt_tx
+                            .send(DbEvent::DefinitionLoaded { object, definition })
+                            .await;
+                    }
+                    Err(e) => {
+                        let _ = evt_tx
+                            .send(DbEvent::Error {
+                                message: e.to_string(),
                             })
-                            .collect();
+                            .await;
+                    }
+                }
+            }
+
+            DbCommand::LoadRows {
+                table,
+                order_by,
+                filter,
+                offset,
+                limit,
+            } => {
+                let Some(d) = driver.as_deref() else {
+                    let _ = evt_tx
+                        .send(DbEvent::RowsFailed {
+                            table,
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                match d
+                    .fetch_page(&table, order_by.as_deref(), filter.as_ref(), offset, limit)
+                    .await
+                {
+                    Ok(page) => {
+                        // The estimate is best-effort; a failure here shouldn't hide the rows.
+                        // Catalog statistics say nothing about a filtered subset.
+                        let total_estimate = if filter.is_some() {
+                            None
+                        } else {
+                            d.estimate_rows(&table).await.unwrap_or(None)
+                        };
 
                         let _ = evt_tx
-                            .send(Event::Db(DbEvent::ColumnsLoaded {
+                            .send(DbEvent::RowsLoaded {
                                 table,
-                                columns: cols,
-                            }))
+                                columns: page.columns,
+                                rows: page.rows,
+                                offset,
+                                total_estimate,
+                            })
                             .await;
                     }
                     Err(e) => {
                         let _ = evt_tx
-                            .send(Event::Db(DbEvent::Error {
+                            .send(DbEvent::RowsFailed {
+                                table,
                                 message: e.to_string(),
-                            }))
+                            })
                             .await;
                     }
                 }
             }
+
+            DbCommand::Execute { id, sql } => {
+                let Some(d) = driver.clone() else {
+                    let _ = evt_tx
+                        .send(DbEvent::QueryFailed {
+                            id,
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+                if read_only && classify(&sql, d.kind()) != StatementClass::Read {
+                    let message = READ_ONLY.into();
+                    let _ = evt_tx.send(DbEvent::QueryFailed { id, message }).await;
+                    continue;
+                }
+
+                queries.retain(|_, q| !q.task.is_finished());
+                let (demand, wanted) = watch::channel(Some(STREAM_AHEAD));
+                let sink = GridSink {
+                    id,
+                    columns: vec![],
+                    sent: 0,
+                    wanted,
+                    started: Arc::default(),
+                    paused: Arc::default(),
+                    evt_tx: evt_tx.clone(),
+                };
+                let paused = sink.paused.clone();
+                let stopped = sink.wanted.clone();
+                let tx = evt_tx.clone();
+                let task = tokio::spawn(async move {
+                    let evt = match execute(d.as_ref(), id, &sql, statement_timeout, sink).await {
+                        Ok(result) => DbEvent::QueryFinished { id, result },
+                        // Failing straight after a pause, rather than on a
+                        // stop, means the server gave up on the paused read.
+                        Err(_) if paused.load(Ordering::Relaxed) && stopped.borrow().is_some() => {
+                            DbEvent::QueryExpired { id }
+                        }
+                        Err(message) => DbEvent::QueryFailed { id, message },
+                    };
+                    let _ = tx.send(evt).await;
+                });
+                queries.insert(id, Query { task, demand });
+            }
+
+            DbCommand::FetchRows { id, upto } => {
+                if let Some(q) = queries.get(&id) {
+                    q.demand.send_if_modified(|d| match d {
+                        Some(n) if *n < upto => {
+                            *n = upto;
+                            true
+                        }
+                        _ => false,
+                    });
+                }
+            }
+
+            DbCommand::Cancel { id } => {
+                // Gone already: its answer is on the way. Otherwise the task
+                // stops the statement and answers.
+                if let Some(q) = queries.get(&id) {
+                    q.demand.send_replace(None);
+                }
+            }
+
+            DbCommand::Export {
+                id,
+                source,
+                path,
+                options,
+            } => {
+                let path = crate::db::connect::expand_home(&path);
+                let needs_db = matches!(source, ExportSource::Stream(_))
+                    || options.format == ExportFormat::Sql;
+                if driver.is_none() && needs_db {
+                    let _ = evt_tx
+                        .send(DbEvent::ExportFailed {
+                            id,
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                }
+
+                queries.retain(|_, q| !q.task.is_finished());
+                let (demand, stop) = watch::channel(Some(0));
+                let (d, tx) = (driver.clone(), evt_tx.clone());
+                let task = tokio::spawn(async move {
+                    let work = export(d.as_deref(), source, &path, options, &tx);
+                    let evt = match until_stopped(stop, work).await {
+                        Some(Ok(rows)) => DbEvent::ExportFinished { path, rows },
+                        res => {
+                            // Drop the partial file; an existing export is left as it was.
+                            let _ = std::fs::remove_file(partial_path(&path));
+                            let message = match res {
+                                Some(Err(e)) => format!("Export failed: {}", e),
+                                _ => "Export cancelled".into(),
+                            };
+                            DbEvent::ExportFailed { id, message }
+                        }
+                    };
+                    let _ = tx.send(evt).await;
+                });
+                queries.insert(id, Query { task, demand });
+            }
+
+            DbCommand::PreviewImport {
+                table,
+                path,
+                options,
+            } => {
+                let Some(d) = driver.as_deref() else {
+                    let _ = evt_tx
+                        .send(DbEvent::Error {
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                let path = crate::db::connect::expand_home(&path);
+                let evt = match preview_import(d, &table, &path, options).await {
+                    Ok(preview) => DbEvent::ImportPreviewed { preview },
+                    Err(e) => DbEvent::Error {
+                        message: format!("Import failed: {}", e),
+                    },
+                };
+                let _ = evt_tx.send(evt).await;
+            }
+
+            DbCommand::Import {
+                id,
+                plan,
+                path,
+                options,
+            } => {
+                let Some(d) = driver.clone() else {
+                    let _ = evt_tx
+                        .send(DbEvent::ImportFailed {
+                            id,
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                if read_only {
+                    let message = "Read-only connection: nothing was imported".into();
+                    let _ = evt_tx.send(DbEvent::ImportFailed { id, message }).await;
+                    continue;
+                }
+
+                queries.retain(|_, q| !q.task.is_finished());
+                let (demand, stop) = watch::channel(Some(0));
+                let tx = evt_tx.clone();
+                let task = tokio::spawn(async move {
+                    let path = crate::db::connect::expand_home(&path);
+                    let dry_run = options.dry_run;
+                    let work = import(d.as_ref(), &plan, &path, options, &tx);
+                    let evt = match until_stopped(stop, work).await {
+                        Some((rows, Ok(()))) => DbEvent::ImportFinished {
+                            table: plan.table,
+                            rows,
+                            dry_run,
+                        },
+                        // Earlier batches were committed; say how far it got.
+                        Some((rows, Err(e))) if rows > 0 && !dry_run => DbEvent::ImportFailed {
+                            id,
+                            message: format!(
+                                "Import failed after {} rows were written: {}",
+                                rows, e
+                            ),
+                        },
+                        Some((_, Err(e))) => DbEvent::ImportFailed {
+                            id,
+                            message: format!("Import failed: {}", e),
+                        },
+                        // The batch in flight is rolled back; earlier ones stay.
+                        None => DbEvent::ImportFailed {
+                            id,
+                            message: "Import cancelled".into(),
+                        },
+                    };
+                    let _ = tx.send(evt).await;
+                });
+                queries.insert(id, Query { task, demand });
+            }
+
+            DbCommand::ApplyChanges { table, statements } => {
+                let Some(d) = driver.as_deref() else {
+                    let _ = evt_tx
+                        .send(DbEvent::ChangesFailed {
+                            message: "Not connected".into(),
+                        })
+                        .await;
+                    continue;
+                };
+
+                if read_only {
+                    let message = "Read-only connection: nothing was saved".into();
+                    let _ = evt_tx.send(DbEvent::ChangesFailed { message }).await;
+                    continue;
+                }
+
+                let evt = match d.apply_changes(&statements).await {
+                    Ok(()) => DbEvent::ChangesApplied {
+                        table,
+                        count: statements.len(),
+                    },
+                    Err(sqlx::Error::RowNotFound) => DbEvent::ChangesFailed {
+                        message: "Save failed: a key matched no row or several; nothing was saved"
+                            .into(),
+                    },
+                    Err(e) => DbEvent::ChangesFailed {
+                        message: format!("Save failed: {}; nothing was saved", e),
+                    },
+                };
+                let _ = evt_tx.send(evt).await;
+            }
+        }
+    }
+}
+
+/// Writes streamed rows to the export file, reporting progress per batch.
+struct FileSink<'a> {
+    path: &'a Path,
+    options: ExportOptions,
+    table: Option<SqlTable>, // target of SQL statements
+    writer: Option<ExportWriter<BufWriter<File>>>,
+    written: u64,
+    evt_tx: &'a Events,
+}
+
+#[async_trait]
+impl RowSink for FileSink<'_> {
+    async fn columns(&mut self, columns: &[ResultColumn]) -> Result<(), sqlx::Error> {
+        let file = BufWriter::new(File::create(self.path)?);
+        let mut writer = ExportWriter::new(file, self.options.clone(), columns)?;
+        if let Some(table) = self.table.take() {
+            writer = writer.for_table(table)?;
+        }
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    async fn rows(&mut self, rows: Vec<Vec<CellValue>>) -> Result<(), sqlx::Error> {
+        let Some(w) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        for row in &rows {
+            w.write_row(row)?;
+        }
+        self.written += rows.len() as u64;
+        let _ = self
+            .evt_tx
+            .send(DbEvent::ExportProgress { rows: self.written })
+            .await;
+        Ok(())
+    }
+}
+
+/// Sends a Runner statement's rows to the grid, pausing whenever it has sent
+/// as many as the grid wants so far.
+struct GridSink {
+    id: u64,
+    columns: Vec<ResultColumn>,
+    sent: u64,
+    wanted: watch::Receiver<Option<u64>>,
+    started: Arc<AtomicBool>, // rows have begun to arrive
+    paused: Arc<AtomicBool>,  // no rows read since waiting on the grid
+    evt_tx: Events,
+}
+
+#[async_trait]
+impl RowSink for GridSink {
+    async fn columns(&mut self, columns: &[ResultColumn]) -> Result<(), sqlx::Error> {
+        self.columns = columns.to_vec();
+        self.started.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn rows(&mut self, rows: Vec<Vec<CellValue>>) -> Result<(), sqlx::Error> {
+        self.paused.store(false, Ordering::Relaxed);
+        self.sent += rows.len() as u64;
+        let _ = self
+            .evt_tx
+            .send(DbEvent::QueryRows {
+                id: self.id,
+                columns: self.columns.clone(),
+                rows,
+            })
+            .await;
+        let sent = self.sent;
+        if self.wanted.borrow().is_some_and(|n| n <= sent) {
+            self.paused.store(true, Ordering::Relaxed);
+        }
+        let more = self
+            .wanted
+            .wait_for(|w| w.is_none_or(|n| n > sent))
+            .await
+            .is_ok_and(|w| w.is_some());
+        if more {
+            Ok(())
+        } else {
+            Err(sqlx::Error::Protocol("fetch stopped".into()))
+        }
+    }
+}
+
+/// Run a Runner statement, streaming its rows through `sink`. It is cancelled
+/// when the grid asks to stop, or once `timeout` passes before any rows have
+/// arrived; after that the result is read at the grid's pace.
+async fn execute(
+    d: &dyn Driver,
+    id: u64,
+    sql: &str,
+    timeout: Option<Duration>,
+    mut sink: GridSink,
+) -> Result<QueryResult, String> {
+    let mut stop = sink.wanted.clone();
+    let wanted = sink.wanted.clone();
+    let started = sink.started.clone();
+    let evt_tx = sink.evt_tx.clone();
+    let run = d.execute(id, sql, &mut sink);
+    tokio::pin!(run);
+    let deadline = tokio::time::sleep(timeout.unwrap_or(Duration::MAX));
+    tokio::pin!(deadline);
+    let mut armed = timeout.is_some();
+    let mut stopping = false;
+
+    loop {
+        tokio::select! {
+            res = &mut run => {
+                return match res {
+                    Err(_) if wanted.borrow().is_none() => Err("Query cancelled".into()),
+                    res => res.map_err(|e| e.to_string()),
+                };
+            }
+            _ = &mut deadline, if armed => {
+                armed = false;
+                if started.load(Ordering::Relaxed) {
+                    continue;
+                }
+                let _ = d.cancel(id).await;
+                // Let the interrupted statement hand its session back.
+                let _ = run.await;
+                return Err(format!(
+                    "Query timed out after {}s",
+                    timeout.unwrap_or_default().as_secs()
+                ));
+            }
+            _ = async { stop.wait_for(Option::is_none).await.map(|_| ()) }, if !stopping => {
+                stopping = true;
+                // KILL QUERY / pg_cancel_backend go over another pooled
+                // connection, and SQLite interrupts it in place; the statement
+                // then fails, or the sink stops it.
+                if let Err(e) = d.cancel(id).await {
+                    let _ = evt_tx
+                        .send(DbEvent::Error {
+                            message: format!("Cancel failed: {}", e),
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+/// Open the tunnel, if the target has a bastion, then a pool through it. The
+/// tunnel is closed again if the database refuses.
+async fn open(target: &ConnectTarget) -> Result<(Box<dyn Driver>, Option<Tunnel>), String> {
+    let forward = match target.ssh.as_ref().filter(|_| !target.kind.is_file()) {
+        Some(ssh) => Some(
+            Tunnel::open(ssh, &target.ssh_passphrase, &target.host, target.port)
+                .await
+                .map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+    let (host, port) = match forward.as_ref() {
+        Some(t) => ("127.0.0.1", t.port()),
+        None => (target.host.as_str(), target.port),
+    };
+    match crate::db::connect::connect(
+        target.kind,
+        host,
+        port,
+        &target.user,
+        &target.password,
+        &target.db,
+        &target.tls,
+        &target.options,
+    )
+    .await
+    {
+        Ok(d) => Ok((d, forward)),
+        Err(e) => {
+            if let Some(t) = forward {
+                t.close().await;
+            }
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Cancel and drop every running statement before the connection closes,
+/// which would otherwise wait for them.
+async fn stop_queries(queries: &mut HashMap<u64, Query>, d: &dyn Driver) {
+    for (id, q) in queries.drain() {
+        if !q.task.is_finished() {
+            let _ = d.cancel(id).await;
+        }
+        q.task.abort();
+    }
+}
+
+/// Run an export or import until it ends, or until `Cancel` drops it.
+async fn until_stopped<T>(
+    mut stop: watch::Receiver<Option<u64>>,
+    work: impl Future<Output = T>,
+) -> Option<T> {
+    tokio::select! {
+        res = work => Some(res),
+        _ = stop.wait_for(Option::is_none) => None,
+    }
+}
+
+async fn export(
+    driver: Option<&dyn Driver>,
+    source: ExportSource,
+    path: &Path,
+    options: ExportOptions,
+    evt_tx: &Events,
+) -> Result<u64, sqlx::Error> {
+    // SQL statements need the table's generated columns and key.
+    let table = match driver {
+        Some(d) if options.format == ExportFormat::Sql => {
+            let details = d.describe_table(&options.table).await?;
+            Some(SqlTable::new(d.kind(), &details))
+        }
+        _ => None,
+    };
+    let partial = partial_path(path);
+    let mut sink = FileSink {
+        path: &partial,
+        options,
+        table,
+        writer: None,
+        written: 0,
+        evt_tx,
+    };
+    match source {
+        ExportSource::Rows { columns, rows } => {
+            sink.columns(&columns).await?;
+            sink.rows(rows).await?;
+        }
+        ExportSource::Stream(source) => {
+            if let Some(d) = driver {
+                d.stream_rows(&source, &mut sink).await?;
+            }
+        }
+    }
+    let Some(w) = sink.writer else {
+        return Ok(0);
+    };
+    let rows = w.finish()?;
+    std::fs::rename(&partial, path)?;
+    Ok(rows)
+}
+
+/// Where an export is written until it completes, beside its target so the
+/// final rename stays on one filesystem.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// The target table and the first records of the file, for the mapping page.
+async fn preview_import(
+    d: &dyn Driver,
+    table: &str,
+    path: &Path,
+    options: ImportOptions,
+) -> Result<ImportPreview, ImportError> {
+    let table = d.describe_table(table).await?;
+    let mut reader = ImportReader::new(BufReader::new(File::open(path)?), options)?;
+    let mut rows = Vec::new();
+    while rows.len() < PREVIEW_ROWS
+        && let Some(record) = reader.next_record()?
+    {
+        rows.push(record);
+    }
+    Ok(ImportPreview {
+        kind: d.kind(),
+        table,
+        columns: reader.columns().to_vec(),
+        rows,
+    })
+}
+
+/// Coerce and write every record of the file, a transaction per batch,
+/// reporting progress after each. Returns the rows written (or, in a dry
+/// run, checked) before any failure.
+async fn import(
+    d: &dyn Driver,
+    plan: &ImportPlan,
+    path: &Path,
+    options: ImportOptions,
+    evt_tx: &Events,
+) -> (u64, Result<(), ImportError>) {
+    let mut written = 0;
+    let result = async {
+        let file = File::open(path)?;
+        let total_bytes = file.metadata()?.len();
+        let batch_size = options.batch_size.max(1);
+        let dry_run = options.dry_run;
+        let mut reader = ImportReader::new(BufReader::new(file), options)?;
+        let sql = plan.statement();
+
+        let mut batch = Vec::with_capacity(batch_size);
+        loop {
+            let record = reader.next_record()?;
+            if let Some(record) = &record {
+                let row = plan.row(record).map_err(|source| ImportError::Value {
+                    line: reader.record_line(),
+                    source,
+                })?;
+                batch.push(row);
+            }
+            if batch.len() == batch_size || (record.is_none() && !batch.is_empty()) {
+                d.write_rows(&sql, &batch, dry_run).await?;
+                written += batch.len() as u64;
+                batch.clear();
+                let _ = evt_tx
+                    .send(DbEvent::ImportProgress {
+                        rows: written,
+                        bytes: reader.bytes_read(),
+                        total_bytes,
+                    })
+                    .await;
+            }
+            if record.is_none() {
+                return Ok(());
+            }
         }
     }
+    .await;
+    (written, result)
 }
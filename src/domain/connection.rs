@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Which database engine a connection profile talks to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DbKind {
+    #[default]
+    Mysql,
+    Postgres,
+    Sqlite,
+}
+
+impl DbKind {
+    pub const ALL: [DbKind; 3] = [DbKind::Mysql, DbKind::Postgres, DbKind::Sqlite];
+
+    /// File-backed engines keep the database path in the profile's `database`
+    /// field and have no host, port or credentials.
+    pub fn is_file(self) -> bool {
+        matches!(self, DbKind::Sqlite)
+    }
+
+    pub fn default_port(self) -> u16 {
+        match self {
+            DbKind::Mysql => 3306,
+            DbKind::Postgres => 5432,
+            DbKind::Sqlite => 0,
+        }
+    }
+
+    /// Quote an identifier in this engine's dialect.
+    pub fn quote_ident(self, name: &str) -> String {
+        match self {
+            DbKind::Mysql => format!("`{}`", name.replace('`', "``")),
+            DbKind::Postgres | DbKind::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// Bind-parameter placeholder for the `n`th (1-based) parameter.
+    pub fn placeholder(self, n: usize) -> String {
+        match self {
+            DbKind::Postgres => format!("${}", n),
+            DbKind::Mysql | DbKind::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// Next kind in `ALL`, wrapping; used by the modal's kind selector.
+    pub fn cycle(self, forward: bool) -> DbKind {
+        let i = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        let n = Self::ALL.len();
+        Self::ALL[if forward {
+            (i + 1) % n
+        } else {
+            (i + n - 1) % n
+        }]
+    }
+}
+
+impl fmt::Display for DbKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DbKind::Mysql => "MySQL",
+            DbKind::Postgres => "PostgreSQL",
+            DbKind::Sqlite => "SQLite",
+        })
+    }
+}
+
+/// Status-bar label for an open connection. `location` is the host (plus
+/// database for Postgres) or the file path; `schema` is the active schema, if
+/// any. SQLite's default `main` schema is left out.
+pub fn connection_label(kind: DbKind, name: &str, location: &str, schema: Option<&str>) -> String {
+    match schema {
+        Some(s) if kind.is_file() && s != "main" => format!("{} ({}, {})", name, location, s),
+        Some(s) if !kind.is_file() => format!("{} ({}/{})", name, location, s),
+        _ => format!("{} ({})", name, location),
+    }
+}
+
+/// Bastion a server connection is reached through: the worker forwards a
+/// local port to the database host via this SSH server before connecting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshTunnel {
+    pub host: String,
+    pub port: u16,
+    /// Login name; empty leaves it to ssh (`~/.ssh/config` or the local user).
+    #[serde(default)]
+    pub user: String,
+    /// Private key to offer; `None` uses whatever ssh-agent holds.
+    #[serde(default)]
+    pub key_file: Option<String>,
+    /// Accept any host key instead of checking it against known_hosts.
+    #[serde(default)]
+    pub skip_host_key_check: bool,
+}
+
+impl SshTunnel {
+    pub const DEFAULT_PORT: u16 = 22;
+}
+
+/// How hard a server connection insists on TLS, from "never" to "only with
+/// a certificate for this exact host". Named after MySQL's `--ssl-mode`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    Disabled,
+    /// TLS when the server offers it, plaintext otherwise (the drivers' default).
+    #[default]
+    Preferred,
+    Required,
+    /// Required, and the server certificate must chain to the CA.
+    VerifyCa,
+    /// VerifyCa, and the certificate must name the host connected to; through
+    /// an SSH tunnel that host is 127.0.0.1.
+    VerifyIdentity,
+}
+
+impl SslMode {
+    pub const ALL: [SslMode; 5] = [
+        SslMode::Disabled,
+        SslMode::Preferred,
+        SslMode::Required,
+        SslMode::VerifyCa,
+        SslMode::VerifyIdentity,
+    ];
+
+    /// Next mode in `ALL`, wrapping; used by the modal's selector.
+    pub fn cycle(self, forward: bool) -> SslMode {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        let n = Self::ALL.len();
+        Self::ALL[if forward {
+            (i + 1) % n
+        } else {
+            (i + n - 1) % n
+        }]
+    }
+}
+
+impl fmt::Display for SslMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SslMode::Disabled => "disabled",
+            SslMode::Preferred => "preferred",
+            SslMode::Required => "required",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyIdentity => "verify-identity",
+        })
+    }
+}
+
+/// TLS settings of a server connection. Paths may start with `~/`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsSettings {
+    #[serde(default)]
+    pub mode: SslMode,
+    /// PEM file of CAs to trust instead of the built-in roots.
+    #[serde(default)]
+    pub ca: Option<String>,
+    /// PEM client certificate and its key, for servers that require one.
+    #[serde(default)]
+    pub cert: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+/// Driver and pool settings of a connection profile; `None` keeps the
+/// default.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionOptions {
+    /// MySQL character set and collation sent with `SET NAMES`.
+    #[serde(default)]
+    pub charset: Option<String>,
+    #[serde(default)]
+    pub collation: Option<String>,
+    /// Session time zone, e.g. `+02:00` or `Europe/Berlin`. MySQL sessions
+    /// are UTC unless this is set.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Unix socket to use instead of host and port: the socket file on
+    /// MySQL, the directory holding it on Postgres.
+    #[serde(default)]
+    pub socket: Option<String>,
+    /// Seconds to wait for a connection, whether opening one or waiting for
+    /// a free one in the pool.
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub pool_size: Option<u32>,
+    /// Only statements that read may run: the worker refuses the rest and
+    /// the session itself is opened read-only.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl ConnectionOptions {
+    pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+    pub const DEFAULT_POOL_SIZE: u32 = 5;
+}
+
+/// Kind of environment a profile points at. Its colour marks the profile on
+/// the Connections screen and tints the status bar while connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Local,
+    Dev,
+    Staging,
+    Prod,
+}
+
+impl Environment {
+    pub const ALL: [Environment; 4] = [
+        Environment::Local,
+        Environment::Dev,
+        Environment::Staging,
+        Environment::Prod,
+    ];
+
+    pub fn color(self) -> LabelColor {
+        match self {
+            Environment::Local => LabelColor::Blue,
+            Environment::Dev => LabelColor::Green,
+            Environment::Staging => LabelColor::Yellow,
+            Environment::Prod => LabelColor::Red,
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Environment::Local => "local",
+            Environment::Dev => "dev",
+            Environment::Staging => "staging",
+            Environment::Prod => "prod",
+        })
+    }
+}
+
+/// Terminal colours a profile can be marked with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelColor {
+    Red,
+    Yellow,
+    Green,
+    Cyan,
+    Blue,
+    Magenta,
+}
+
+impl LabelColor {
+    pub const ALL: [LabelColor; 6] = [
+        LabelColor::Red,
+        LabelColor::Yellow,
+        LabelColor::Green,
+        LabelColor::Cyan,
+        LabelColor::Blue,
+        LabelColor::Magenta,
+    ];
+}
+
+impl fmt::Display for LabelColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LabelColor::Red => "red",
+            LabelColor::Yellow => "yellow",
+            LabelColor::Green => "green",
+            LabelColor::Cyan => "cyan",
+            LabelColor::Blue => "blue",
+            LabelColor::Magenta => "magenta",
+        })
+    }
+}
+
+/// Step through `None` followed by each of `all`, wrapping; used by the
+/// modal's optional selectors.
+pub fn cycle_option<T: Copy + PartialEq>(
+    all: &[T],
+    current: Option<T>,
+    forward: bool,
+) -> Option<T> {
+    let n = all.len() + 1;
+    let i = current
+        .and_then(|c| all.iter().position(|x| *x == c))
+        .map_or(0, |i| i + 1);
+    let next = if forward {
+        (i + 1) % n
+    } else {
+        (i + n - 1) % n
+    };
+    next.checked_sub(1).map(|j| all[j])
+}
+
+/// How a profile is filed and flagged on the Connections screen.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionLabels {
+    /// Folder the profile is listed under; `None` lists it at the top.
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub environment: Option<Environment>,
+    /// Overrides the environment's colour.
+    #[serde(default)]
+    pub color: Option<LabelColor>,
+}
+
+impl ConnectionLabels {
+    pub fn color(&self) -> Option<LabelColor> {
+        self.color.or(self.environment.map(Environment::color))
+    }
+}
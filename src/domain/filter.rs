@@ -0,0 +1,449 @@
+//! Filtering: incremental fuzzy matching for list screens, and the Data
+//! screen's structured row filter, compiled to a parameterized WHERE clause.
+
+use crate::{app::state::ColumnInfo, db::types::CellValue, domain::connection::DbKind};
+use thiserror::Error;
+
+/// A successful fuzzy match. Higher `score` is better; `positions` are char
+/// indices into the candidate, for highlighting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 15;
+const BOUNDARY: i64 = 10;
+const MAX_LEADING_PENALTY: i64 = 3;
+const UNMATCHED: i64 = i64::MIN / 2;
+
+/// Match `pattern`'s characters in order, but not necessarily adjacent,
+/// against `candidate`, ignoring case and whitespace in the pattern. Runs of
+/// adjacent matches and matches at word starts (`_`, `.`, `-`, space,
+/// camelCase) score higher; skipped characters cost a point each. An empty
+/// pattern matches everything with a score of 0.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pat: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if pat.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: vec![],
+        });
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let (m, n) = (pat.len(), chars.len());
+    if m > n {
+        return None;
+    }
+
+    // score[i][j]: best score with pat[i] matched at chars[j]; from[i][j] is
+    // where pat[i - 1] matched on that best path.
+    let mut score = vec![vec![UNMATCHED; n]; m];
+    let mut from = vec![vec![0; n]; m];
+    for i in 0..m {
+        // Best earlier match of pat[i - 1] that leaves a gap before j, kept as
+        // score + position so the gap penalty can be applied in O(1).
+        let mut gap_best = (UNMATCHED, 0);
+        for j in 0..n {
+            if i > 0 && j >= 2 {
+                let k = j - 2;
+                if score[i - 1][k] > UNMATCHED && score[i - 1][k] + k as i64 > gap_best.0 {
+                    gap_best = (score[i - 1][k] + k as i64, k);
+                }
+            }
+            if lower[j] != pat[i] {
+                continue;
+            }
+
+            let base = MATCH + boundary_bonus(&chars, j);
+            if i == 0 {
+                score[0][j] = base - (j as i64).min(MAX_LEADING_PENALTY);
+                continue;
+            }
+
+            let mut best = UNMATCHED;
+            if j >= 1 && score[i - 1][j - 1] > UNMATCHED {
+                best = score[i - 1][j - 1] + CONSECUTIVE;
+                from[i][j] = j - 1;
+            }
+            if gap_best.0 > UNMATCHED {
+                let gapped = gap_best.0 + 1 - j as i64;
+                if gapped > best {
+                    best = gapped;
+                    from[i][j] = gap_best.1;
+                }
+            }
+            if best > UNMATCHED {
+                score[i][j] = base + best;
+            }
+        }
+    }
+
+    let (mut j, best) = score[m - 1]
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| **s > UNMATCHED)
+        .max_by_key(|(j, s)| (**s, std::cmp::Reverse(*j)))
+        .map(|(j, s)| (j, *s))?;
+
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch {
+        score: best,
+        positions,
+    })
+}
+
+fn boundary_bonus(chars: &[char], j: usize) -> i64 {
+    let Some(prev) = j.checked_sub(1).map(|p| chars[p]) else {
+        return BOUNDARY;
+    };
+    let separator = matches!(prev, '_' | '-' | '.' | ' ' | '/' | ':');
+    let camel = prev.is_lowercase() && chars[j].is_uppercase();
+    if separator || camel { BOUNDARY } else { 0 }
+}
+
+/// Indices of the `candidates` matching `pattern`, best first; on equal
+/// scores shorter candidates win, then original order. An empty pattern keeps
+/// every candidate in order.
+pub fn rank<'a>(pattern: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    if pattern.trim().is_empty() {
+        return candidates.into_iter().enumerate().map(|(i, _)| i).collect();
+    }
+    let mut hits: Vec<(usize, i64, usize)> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, c)| fuzzy_match(pattern, c).map(|m| (i, m.score, c.chars().count())))
+        .collect();
+    hits.sort_by_key(|(i, score, len)| (std::cmp::Reverse(*score), *len, *i));
+    hits.into_iter().map(|(i, _, _)| i).collect()
+}
+
+/// How a condition combines with the ones before it. AND binds tighter than
+/// OR, as in SQL.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Join {
+    #[default]
+    And,
+    Or,
+}
+
+impl Join {
+    pub fn toggle(self) -> Join {
+        match self {
+            Join::And => Join::Or,
+            Join::Or => Join::And,
+        }
+    }
+
+    pub fn sql(self) -> &'static str {
+        match self {
+            Join::And => "AND",
+            Join::Or => "OR",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    #[default]
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Like,
+    NotLike,
+    IsNull,
+    IsNotNull,
+}
+
+impl FilterOp {
+    pub const ALL: [FilterOp; 10] = [
+        FilterOp::Eq,
+        FilterOp::Ne,
+        FilterOp::Lt,
+        FilterOp::Le,
+        FilterOp::Gt,
+        FilterOp::Ge,
+        FilterOp::Like,
+        FilterOp::NotLike,
+        FilterOp::IsNull,
+        FilterOp::IsNotNull,
+    ];
+
+    pub fn sql(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::Ne => "<>",
+            FilterOp::Lt => "<",
+            FilterOp::Le => "<=",
+            FilterOp::Gt => ">",
+            FilterOp::Ge => ">=",
+            FilterOp::Like => "LIKE",
+            FilterOp::NotLike => "NOT LIKE",
+            FilterOp::IsNull => "IS NULL",
+            FilterOp::IsNotNull => "IS NOT NULL",
+        }
+    }
+
+    /// NULL checks compare against nothing.
+    pub fn takes_value(self) -> bool {
+        !matches!(self, FilterOp::IsNull | FilterOp::IsNotNull)
+    }
+
+    fn is_pattern(self) -> bool {
+        matches!(self, FilterOp::Like | FilterOp::NotLike)
+    }
+
+    /// Next operator in `ALL`, wrapping.
+    pub fn cycle(self, forward: bool) -> FilterOp {
+        let i = Self::ALL.iter().position(|o| *o == self).unwrap_or(0);
+        let n = Self::ALL.len();
+        Self::ALL[if forward {
+            (i + 1) % n
+        } else {
+            (i + n - 1) % n
+        }]
+    }
+}
+
+/// One `column op value` test. `value` is what the user typed; it is parsed
+/// according to the column's type when the filter is compiled.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub join: Join, // ignored on the first condition
+    pub column: String,
+    pub op: FilterOp,
+    pub value: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+}
+
+/// Conditions plus a multi-column sort for browsing a table's rows.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RowFilter {
+    pub conditions: Vec<Condition>,
+    pub sort: Vec<SortKey>,
+}
+
+/// A [`RowFilter`] rendered for one engine. `where_sql` and `order_sql` come
+/// without their keywords and are empty when there is nothing to add;
+/// `params` bind to the placeholders in `where_sql`, in order.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CompiledFilter {
+    pub where_sql: String,
+    pub order_sql: String,
+    pub params: Vec<CellValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FilterError {
+    #[error("Unknown column '{0}'")]
+    UnknownColumn(String),
+
+    #[error("{column} expects {expected}, got '{value}'")]
+    InvalidValue {
+        column: String,
+        expected: &'static str,
+        value: String,
+    },
+}
+
+/// How a column's values are bound, from its declared type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValueKind {
+    Int,
+    Float,
+    Bool,
+    Temporal,
+    Text,
+    Other, // uuid, json, enums, arrays … compared as text
+}
+
+pub(crate) fn value_kind(data_type: &str) -> ValueKind {
+    let t = data_type.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| t.contains(w));
+    if has(&["interval", "point"]) {
+        ValueKind::Other
+    } else if has(&["int", "serial"]) {
+        ValueKind::Int
+    } else if has(&["bool"]) {
+        ValueKind::Bool
+    } else if has(&["float", "double", "real", "decimal", "numeric"]) {
+        ValueKind::Float
+    } else if has(&["date", "time", "year"]) {
+        ValueKind::Temporal
+    } else if has(&["char", "text", "clob", "string", "enum", "set", "name"]) {
+        ValueKind::Text
+    } else {
+        ValueKind::Other
+    }
+}
+
+fn parse_value(column: &str, kind: ValueKind, raw: &str) -> Result<CellValue, FilterError> {
+    let invalid = |expected| FilterError::InvalidValue {
+        column: column.to_string(),
+        expected,
+        value: raw.to_string(),
+    };
+    let v = raw.trim();
+    Ok(match kind {
+        ValueKind::Int => CellValue::Int(v.parse().map_err(|_| invalid("an integer"))?),
+        ValueKind::Float => CellValue::Float(v.parse().map_err(|_| invalid("a number"))?),
+        ValueKind::Bool => CellValue::Bool(match v.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "1" => true,
+            "false" | "f" | "no" | "0" => false,
+            _ => return Err(invalid("true or false")),
+        }),
+        ValueKind::Temporal | ValueKind::Text | ValueKind::Other => {
+            CellValue::Text(raw.to_string())
+        }
+    })
+}
+
+/// Postgres type matching how `bind_cell` encodes `value`.
+fn pg_bind_type(value: &CellValue) -> &'static str {
+    match value {
+        CellValue::Int(_) => "BIGINT",
+        CellValue::Float(_) => "DOUBLE PRECISION",
+        CellValue::Bool(_) => "BOOLEAN",
+        _ => "TEXT",
+    }
+}
+
+impl RowFilter {
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty() && self.sort.is_empty()
+    }
+
+    /// Render for `kind` against the table's `columns`. Values are never
+    /// spliced into the SQL: each becomes a bound parameter, typed from the
+    /// column (integers, numbers and booleans are parsed; the rest bind as
+    /// text). On Postgres each placeholder is cast to its bound type, and since
+    /// text won't compare with other types implicitly, temporal values are
+    /// then cast to the column type and other non-text columns compared as text.
+    pub fn compile(
+        &self,
+        kind: DbKind,
+        columns: &[ColumnInfo],
+    ) -> Result<CompiledFilter, FilterError> {
+        let find = |name: &str| {
+            columns
+                .iter()
+                .find(|c| c.name == name)
+                .ok_or_else(|| FilterError::UnknownColumn(name.to_string()))
+        };
+
+        let mut out = CompiledFilter::default();
+        for (i, cond) in self.conditions.iter().enumerate() {
+            let col = find(&cond.column)?;
+            let vk = value_kind(&col.data_type);
+            if i > 0 {
+                out.where_sql.push_str(&format!(" {} ", cond.join.sql()));
+            }
+
+            let mut lhs = kind.quote_ident(&col.name);
+            if !cond.op.takes_value() {
+                out.where_sql
+                    .push_str(&format!("{} {}", lhs, cond.op.sql()));
+                continue;
+            }
+
+            let value = if cond.op.is_pattern() {
+                CellValue::Text(cond.value.clone())
+            } else {
+                parse_value(&col.name, vk, &cond.value)?
+            };
+            let mut rhs = kind.placeholder(out.params.len() + 1);
+
+            if kind == DbKind::Postgres {
+                // Pin the parameter to the type it is bound as; an inferred
+                // type (say `int4` or `date`) would reject the encoded value.
+                rhs = format!("CAST({} AS {})", rhs, pg_bind_type(&value));
+                let as_text = if cond.op.is_pattern() {
+                    vk != ValueKind::Text
+                } else {
+                    vk == ValueKind::Other
+                };
+                if as_text {
+                    lhs = format!("CAST({} AS TEXT)", lhs);
+                } else if vk == ValueKind::Temporal {
+                    rhs = format!("CAST({} AS {})", rhs, col.data_type);
+                }
+            }
+            out.params.push(value);
+            out.where_sql
+                .push_str(&format!("{} {} {}", lhs, cond.op.sql(), rhs));
+        }
+
+        out.order_sql = self
+            .sort
+            .iter()
+            .map(|k| {
+                find(&k.column).map(|c| {
+                    format!(
+                        "{} {}",
+                        kind.quote_ident(&c.name),
+                        if k.descending { "DESC" } else { "ASC" }
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", ");
+
+        Ok(out)
+    }
+
+    /// One-line description for titles, e.g. "status = active AND age > 30 · ↓created_at".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        let conds: Vec<String> = self
+            .conditions
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let join = if i > 0 {
+                    format!("{} ", c.join.sql())
+                } else {
+                    String::new()
+                };
+                if c.op.takes_value() {
+                    format!("{}{} {} {}", join, c.column, c.op.sql(), c.value)
+                } else {
+                    format!("{}{} {}", join, c.column, c.op.sql())
+                }
+            })
+            .collect();
+        if !conds.is_empty() {
+            parts.push(conds.join(" "));
+        }
+        if !self.sort.is_empty() {
+            let keys: Vec<String> = self
+                .sort
+                .iter()
+                .map(|k| format!("{}{}", if k.descending { "↓" } else { "↑" }, k.column))
+                .collect();
+            parts.push(keys.join(", "));
+        }
+        parts.join(" · ")
+    }
+}
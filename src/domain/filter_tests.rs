@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use crate::app::state::ColumnInfo;
+    use crate::db::types::CellValue;
+    use crate::domain::connection::DbKind;
+    use crate::domain::filter::{
+        Condition, FilterError, FilterOp, Join, RowFilter, SortKey, fuzzy_match, rank,
+    };
+
+    fn col(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            column_key: None,
+        }
+    }
+
+    fn cond(join: Join, column: &str, op: FilterOp, value: &str) -> Condition {
+        Condition {
+            join,
+            column: column.to_string(),
+            op,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_subsequence_ignoring_case_and_reports_positions() {
+        let m = fuzzy_match("OrIt", "order_items").unwrap();
+        assert_eq!(m.positions, [0, 1, 6, 7]);
+        assert!(fuzzy_match("oi", "order_items").is_some());
+        assert!(fuzzy_match("io", "oi").is_none());
+        assert!(fuzzy_match("toolong", "tool").is_none());
+
+        let all = fuzzy_match("  ", "anything").unwrap();
+        assert_eq!((all.score, all.positions.len()), (0, 0));
+    }
+
+    #[test]
+    fn prefers_word_starts_and_runs_over_scattered_hits() {
+        // "ui" should land on user_items' word starts, not the 'i' inside "user".
+        let m = fuzzy_match("ui", "user_items").unwrap();
+        assert_eq!(m.positions, [0, 5]);
+
+        // camelCase humps count as word starts too.
+        let m = fuzzy_match("oi", "orderItems").unwrap();
+        assert_eq!(m.positions, [0, 5]);
+
+        let names = ["audit_log", "user_accounts", "users", "bus_stops"];
+        assert_eq!(rank("us", names), [2, 1, 3]);
+        assert_eq!(rank("", names), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn compiles_bound_conditions_and_multi_column_sort() {
+        let columns = [
+            col("id", "int"),
+            col("name", "varchar"),
+            col("born", "date"),
+            col("meta", "jsonb"),
+        ];
+        let filter = RowFilter {
+            conditions: vec![
+                cond(Join::And, "id", FilterOp::Ge, " 10 "),
+                cond(Join::Or, "name", FilterOp::Like, "a%"),
+                cond(Join::And, "born", FilterOp::IsNull, "ignored"),
+                cond(Join::And, "meta", FilterOp::Eq, "{}"),
+            ],
+            sort: vec![
+                SortKey {
+                    column: "name".into(),
+                    descending: true,
+                },
+                SortKey {
+                    column: "id".into(),
+                    descending: false,
+                },
+            ],
+        };
+
+        let my = filter.compile(DbKind::Mysql, &columns).unwrap();
+        assert_eq!(
+            my.where_sql,
+            "`id` >= ? OR `name` LIKE ? AND `born` IS NULL AND `meta` = ?"
+        );
+        assert_eq!(my.order_sql, "`name` DESC, `id` ASC");
+        assert_eq!(
+            my.params,
+            [
+                CellValue::Int(10),
+                CellValue::Text("a%".into()),
+                CellValue::Text("{}".into()),
+            ]
+        );
+
+        // Postgres numbers its placeholders, pins their types and casts where
+        // it won't coerce.
+        let pg = filter.compile(DbKind::Postgres, &columns).unwrap();
+        assert_eq!(
+            pg.where_sql,
+            "\"id\" >= CAST($1 AS BIGINT) OR \"name\" LIKE CAST($2 AS TEXT) \
+             AND \"born\" IS NULL AND CAST(\"meta\" AS TEXT) = CAST($3 AS TEXT)"
+        );
+        let dated = RowFilter {
+            conditions: vec![cond(Join::And, "born", FilterOp::Lt, "1900-01-01")],
+            sort: vec![],
+        };
+        assert_eq!(
+            dated.compile(DbKind::Postgres, &columns).unwrap().where_sql,
+            "\"born\" < CAST(CAST($1 AS TEXT) AS date)"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_columns_and_unparsable_values() {
+        let columns = [col("id", "bigint"), col("active", "boolean")];
+        let bad = |column: &str, value: &str| RowFilter {
+            conditions: vec![cond(Join::And, column, FilterOp::Eq, value)],
+            sort: vec![],
+        };
+
+        assert_eq!(
+            bad("nope", "1").compile(DbKind::Sqlite, &columns),
+            Err(FilterError::UnknownColumn("nope".into()))
+        );
+        let err = bad("id", "1.5")
+            .compile(DbKind::Sqlite, &columns)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "id expects an integer, got '1.5'");
+        assert!(
+            bad("active", "maybe")
+                .compile(DbKind::Sqlite, &columns)
+                .is_err()
+        );
+        assert_eq!(
+            bad("active", "Yes")
+                .compile(DbKind::Sqlite, &columns)
+                .unwrap()
+                .params,
+            [CellValue::Bool(true)]
+        );
+    }
+}
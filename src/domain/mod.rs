@@ -0,0 +1,6 @@
+pub mod connection;
+pub mod filter;
+pub mod querylib;
+
+#[cfg(test)]
+mod filter_tests;
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A named SQL snippet in the query library (`queries.json`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub id: String, // ULID
+    pub name: String,
+    pub sql: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    pub created_at: u64, // unix seconds
+    pub updated_at: u64,
+}
+
+impl SavedQuery {
+    pub fn new(name: &str, sql: &str, tags: Vec<String>, connection_id: Option<String>) -> Self {
+        let now = now_unix();
+        Self {
+            id: ulid::Ulid::new().to_string(),
+            name: name.to_string(),
+            sql: sql.to_string(),
+            tags,
+            connection_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Case-insensitive substring match over name, tags and SQL.
+    pub fn matches(&self, needle: &str) -> bool {
+        let needle = needle.trim().to_lowercase();
+        if needle.is_empty() {
+            return true;
+        }
+        self.name.to_lowercase().contains(&needle)
+            || self.tags.iter().any(|t| t.to_lowercase().contains(&needle))
+            || self.sql.to_lowercase().contains(&needle)
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = now_unix();
+    }
+}
+
+/// Split a "a, b ,c" tag input into trimmed, non-empty, de-duplicated tags.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for t in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|x| x == t) {
+            tags.push(t.to_string());
+        }
+    }
+    tags
+}
+
+pub fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn format_unix(ts: u64) -> String {
+    sqlx::types::chrono::DateTime::from_timestamp(ts as i64, 0)
+        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
@@ -1,11 +1,15 @@
 pub mod app;
 pub mod db;
+pub mod domain;
 pub mod errors;
 pub mod storage;
 pub mod ui;
 
 use crate::app::action::Action;
-use crate::storage::{file_repo::FileConnectionRepo, repo::ConnectionRepo};
+use crate::storage::{
+    file_repo::{FileConnectionRepo, FileQueryRepo},
+    repo::{ConnectionRepo, QueryRepo},
+};
 use crossterm::event::{self, Event as CEvent};
 use directories::ProjectDirs;
 use std::time::{Duration, Instant};
@@ -49,30 +53,40 @@ pub async fn run() -> Result<(), errors::AppError> {
                 );
             }
             let password = password_opt.unwrap_or_default();
+            let ssh_passphrase = match c.ssh {
+                Some(_) => secrets.get_ssh_passphrase(&c.id)?.unwrap_or_default(),
+                None => String::new(),
+            };
 
-            Ok(app::state::ConnectionItem {
+            Ok(app::state::ConnectionItem::from_profile(
                 id,
-                name: c.name,
-                host: c.host,
-                port: c.port,
-                user: c.user,
+                c,
                 password,
-                db: c.database,
-            })
+                ssh_passphrase,
+            ))
         })
         .collect::<Result<Vec<_>, errors::AppError>>()?;
 
+    // --- saved queries live next to connections.json ---
+    let query_repo = FileQueryRepo::new(config_dir.join("queries.json"));
+    state.queries.items = query_repo.load_queries()?;
+
     // --- channels ---
     let (cmd_tx, mut cmd_rx) = mpsc::channel::<app::command::Command>(256);
-    let (db_tx, db_rx) = mpsc::channel::<app::command::DbCommand>(256);
+    let (db_tx, db_rx) = mpsc::channel::<(app::state::SessionId, app::command::DbCommand)>(256);
     let (st_tx, st_rx) = mpsc::channel::<app::command::StorageCommand>(256);
     let (evt_tx, mut evt_rx) = mpsc::channel::<app::event::Event>(256);
 
     // --- spawn workers ---
     tokio::spawn(db::worker::run(db_rx, evt_tx.clone()));
-    tokio::spawn(storage::worker::run(st_rx, evt_tx.clone(), repo));
-
-    // tick for UI refresh (spinners etc later)
+    tokio::spawn(storage::worker::run(
+        st_rx,
+        evt_tx.clone(),
+        repo,
+        query_repo,
+    ));
+
+    // tick for UI refresh (spinners)
     let tick_rate = Duration::from_millis(100);
     let mut last_tick = Instant::now();
 
@@ -91,8 +105,8 @@ pub async fn run() -> Result<(), errors::AppError> {
         // Route commands to the right worker
         while let Ok(cmd) = cmd_rx.try_recv() {
             match cmd {
-                app::command::Command::Db(c) => {
-                    let _ = db_tx.try_send(c);
+                app::command::Command::Db(session, c) => {
+                    let _ = db_tx.try_send((session, c));
                 }
                 app::command::Command::Storage(c) => {
                     let _ = st_tx.try_send(c);
@@ -105,23 +119,23 @@ pub async fn run() -> Result<(), errors::AppError> {
             .checked_sub(last_tick.elapsed())
             .unwrap_or(Duration::from_secs(0));
 
-        if event::poll(timeout)? {
-            if let CEvent::Key(key) = event::read()? {
-                if let Some(action) = app::keymap::map_key(&state, key) {
-                    if matches!(action, Action::Quit) {
-                        break;
-                    }
-
-                    let cmds = app::reducer::reduce_action(&mut state, action);
-                    for c in cmds {
-                        let _ = cmd_tx.try_send(c);
-                    }
-                }
+        if event::poll(timeout)?
+            && let CEvent::Key(key) = event::read()?
+            && let Some(action) = app::keymap::map_key(&state, key)
+        {
+            if matches!(action, Action::Quit) {
+                break;
+            }
+
+            let cmds = app::reducer::reduce_action(&mut state, action);
+            for c in cmds {
+                let _ = cmd_tx.try_send(c);
             }
         }
 
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
+            let _ = app::reducer::reduce_action(&mut state, Action::Tick);
         }
     }
 
@@ -1,5 +1,11 @@
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // ssh runs us as its askpass helper to unlock a tunnel's key.
+    if let Some(passphrase) = lensql::db::tunnel::askpass_reply() {
+        println!("{}", passphrase);
+        return Ok(());
+    }
+
     if std::env::var_os("RUST_LOG").is_some() {
         tracing_subscriber::fmt()
             .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -0,0 +1,444 @@
+//! Connection profiles from elsewhere: DSNs (including TablePlus's "Copy as
+//! URL"), MySQL option files such as `~/.my.cnf`, `mysql_config_editor`'s
+//! encrypted `~/.mylogin.cnf` or its `print --all` output, and DBeaver's
+//! `data-sources.json`. TablePlus's own export files are encrypted with a
+//! passphrase of the user's choosing and are not read. Passwords come back
+//! next to the profile, never in it.
+
+use crate::{
+    db::connect::expand_home,
+    domain::connection::{
+        ConnectionLabels, ConnectionOptions, DbKind, Environment, SslMode, TlsSettings,
+    },
+    storage::model::ConnectionProfile,
+};
+use aes::{
+    Aes128,
+    cipher::{BlockDecrypt, KeyInit, generic_array::GenericArray},
+};
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
+use std::{collections::BTreeMap, io, path::Path};
+use thiserror::Error;
+use url::Url;
+
+/// Where the import prompt reads from when left blank.
+pub const DEFAULT_SOURCE: &str = "~/.my.cnf";
+
+#[derive(Debug, Error)]
+pub enum ExternalError {
+    #[error("Could not read {path}: {source}")]
+    Io { path: String, source: io::Error },
+
+    #[error("Not a DBeaver data-sources.json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Bad DSN: {0}")]
+    Dsn(String),
+
+    #[error("No connections found in {0}")]
+    Empty(String),
+
+    #[error("Could not decrypt {0} as a mysql_config_editor login file")]
+    LoginFile(String),
+}
+
+/// A profile found by an import, with the password that came with it. The
+/// profile's id is blank until it is added.
+#[derive(Debug, Clone)]
+pub struct ImportedConnection {
+    pub profile: ConnectionProfile,
+    pub password: String,
+}
+
+/// Read `source`: a DSN, or a path to a file of DSNs (one per line), a MySQL
+/// option or login file or a DBeaver export.
+pub fn read_connections(source: &str) -> Result<Vec<ImportedConnection>, ExternalError> {
+    let source = match source.trim() {
+        "" => DEFAULT_SOURCE,
+        s => s,
+    };
+    if is_dsn(source) {
+        return Ok(vec![parse_dsn(source)?]);
+    }
+    let bytes = std::fs::read(expand_home(source)).map_err(|e| ExternalError::Io {
+        path: source.to_string(),
+        source: e,
+    })?;
+    let text = if is_login_file(&bytes) {
+        decrypt_login_file(&bytes).ok_or_else(|| ExternalError::LoginFile(source.to_string()))?
+    } else {
+        String::from_utf8(bytes).map_err(|e| ExternalError::Io {
+            path: source.to_string(),
+            source: io::Error::new(io::ErrorKind::InvalidData, e),
+        })?
+    };
+    let found = if text.trim_start().starts_with('{') {
+        parse_dbeaver(&text)?
+    } else if text.lines().map(str::trim).any(is_dsn) {
+        text.lines()
+            .map(str::trim)
+            .filter(|l| is_dsn(l))
+            .map(parse_dsn)
+            .collect::<Result<_, _>>()?
+    } else {
+        parse_option_file(&text)
+    };
+    if found.is_empty() {
+        return Err(ExternalError::Empty(source.to_string()));
+    }
+    Ok(found)
+}
+
+fn is_dsn(s: &str) -> bool {
+    s.contains("://") || s.starts_with("sqlite:")
+}
+
+fn profile(kind: DbKind, name: String) -> ConnectionProfile {
+    ConnectionProfile {
+        id: String::new(),
+        kind,
+        name,
+        host: String::new(),
+        port: kind.default_port(),
+        user: String::new(),
+        database: None,
+        statement_timeout_secs: None,
+        ssh: None,
+        tls: TlsSettings::default(),
+        options: ConnectionOptions::default(),
+        labels: ConnectionLabels::default(),
+        production: false,
+    }
+}
+
+/// Name for a profile that came without one: `host/db`, or the host.
+fn default_name(p: &ConnectionProfile) -> String {
+    match p.database.as_deref() {
+        Some(db) if p.kind.is_file() => Path::new(db)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| db.to_string()),
+        Some(db) => format!("{}/{}", p.host, db),
+        None => p.host.clone(),
+    }
+}
+
+/// Environment for the labels other tools use: TablePlus writes `local`,
+/// `development`, `testing`, `staging` or `production`, DBeaver's connection
+/// types are `dev`, `test` and `prod`.
+fn environment(label: &str) -> Option<Environment> {
+    match label.to_ascii_lowercase().as_str() {
+        "local" => Some(Environment::Local),
+        "dev" | "development" => Some(Environment::Dev),
+        "test" | "testing" | "staging" => Some(Environment::Staging),
+        "prod" | "production" => Some(Environment::Prod),
+        _ => None,
+    }
+}
+
+/// `mysql://`, `mariadb://`, `postgres://`, `postgresql://` or `sqlite:`.
+/// Query parameters may name the profile (`name`, as TablePlus writes it),
+/// set the SSL mode and CA, charset, time zone and connect timeout, under
+/// either engine's spelling, and mark the environment (`env`).
+pub fn parse_dsn(dsn: &str) -> Result<ImportedConnection, ExternalError> {
+    let bad = |m: &str| ExternalError::Dsn(format!("{} in '{}'", m, redact(dsn)));
+    let (scheme, rest) = dsn.split_once(':').ok_or_else(|| bad("no scheme"))?;
+    let kind = match scheme.to_ascii_lowercase().as_str() {
+        "mysql" | "mariadb" => DbKind::Mysql,
+        "postgres" | "postgresql" => DbKind::Postgres,
+        "sqlite" => DbKind::Sqlite,
+        other => return Err(bad(&format!("unsupported scheme '{}'", other))),
+    };
+
+    if kind.is_file() {
+        let path = rest.strip_prefix("//").unwrap_or(rest);
+        let path = path.split('?').next().unwrap_or_default();
+        if path.is_empty() {
+            return Err(bad("no file path"));
+        }
+        let mut p = profile(kind, String::new());
+        p.database = Some(decode(path));
+        p.name = default_name(&p);
+        return Ok(ImportedConnection {
+            profile: p,
+            password: String::new(),
+        });
+    }
+
+    let url = Url::parse(dsn).map_err(|e| bad(&e.to_string()))?;
+    let mut p = profile(kind, String::new());
+    p.host = url
+        .host_str()
+        .map(|h| h.trim_start_matches('[').trim_end_matches(']').to_string())
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| bad("no host"))?;
+    p.port = url.port().unwrap_or(p.port);
+    p.user = decode(url.username());
+    let db = decode(url.path().trim_start_matches('/'));
+    p.database = (!db.is_empty()).then_some(db);
+
+    let mut name = None;
+    for (key, value) in url.query_pairs() {
+        let value = value.into_owned();
+        match key.as_ref() {
+            "name" => name = Some(value),
+            "sslmode" | "ssl-mode" | "ssl_mode" => {
+                p.tls.mode = match value.to_ascii_lowercase().replace('_', "-").as_str() {
+                    "disable" | "disabled" => SslMode::Disabled,
+                    "require" | "required" => SslMode::Required,
+                    "verify-ca" => SslMode::VerifyCa,
+                    "verify-full" | "verify-identity" => SslMode::VerifyIdentity,
+                    _ => SslMode::Preferred,
+                }
+            }
+            "sslrootcert" | "ssl-ca" | "ssl_ca" => p.tls.ca = Some(value),
+            "sslcert" | "ssl-cert" | "ssl_cert" => p.tls.cert = Some(value),
+            "sslkey" | "ssl-key" | "ssl_key" => p.tls.key = Some(value),
+            "charset" => p.options.charset = Some(value),
+            "timezone" | "time_zone" => p.options.timezone = Some(value),
+            "connect_timeout" | "connect-timeout" => {
+                p.options.connect_timeout_secs = value.parse().ok()
+            }
+            "env" | "environment" => p.labels.environment = environment(&value),
+            _ => {}
+        }
+    }
+    p.name = name.unwrap_or_else(|| default_name(&p));
+    Ok(ImportedConnection {
+        profile: p,
+        password: url.password().map(decode).unwrap_or_default(),
+    })
+}
+
+fn decode(s: &str) -> String {
+    percent_decode_str(s).decode_utf8_lossy().into_owned()
+}
+
+/// `dsn` with any password masked, for error messages.
+fn redact(dsn: &str) -> String {
+    let Some((scheme, rest)) = dsn.split_once("://") else {
+        return dsn.to_string();
+    };
+    let authority = &rest[..rest.find(['/', '?']).unwrap_or(rest.len())];
+    match authority
+        .rfind('@')
+        .and_then(|at| Some((authority[..at].find(':')?, at)))
+    {
+        Some((colon, at)) => format!("{}://{}:***{}", scheme, &rest[..colon], &rest[at..]),
+        None => dsn.to_string(),
+    }
+}
+
+/// Option groups every client reads.
+const DEFAULT_GROUPS: [&str; 4] = ["client", "mysql", "client-mariadb", "mariadb-client"];
+
+/// Option groups read by servers and tools rather than by clients.
+fn is_server_group(group: &str) -> bool {
+    const TOOLS: [&str; 9] = [
+        "mysqldump",
+        "mysqladmin",
+        "mysqlimport",
+        "mysqlshow",
+        "mysqlcheck",
+        "mysqlslap",
+        "mysql_upgrade",
+        "myisamchk",
+        "client-server",
+    ];
+    group.starts_with("mysqld")
+        || group.ends_with("mysqld")
+        || group.starts_with("server")
+        || (group.starts_with("mariadb") && !group.ends_with("-client"))
+        || group.starts_with("galera")
+        || group.starts_with("embedded")
+        || TOOLS.contains(&group)
+}
+
+/// MySQL option file groups as client profiles. `[client]` and `[mysql]`
+/// (and MariaDB's `[client-mariadb]` and `[mariadb-client]`) hold the
+/// defaults; every other client group (`[client_prod]`, or a login
+/// path such as `[prod]`) is a profile of its own on top of them. Without
+/// such groups the defaults themselves are the one profile. Masked
+/// passwords (`*****`, as `mysql_config_editor print` shows them) are left
+/// out.
+pub fn parse_option_file(text: &str) -> Vec<ImportedConnection> {
+    let mut groups: Vec<(String, BTreeMap<String, String>)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', ';', '!']) {
+            continue;
+        }
+        if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            groups.push((group.trim().to_ascii_lowercase(), BTreeMap::new()));
+            continue;
+        }
+        let Some((_, values)) = groups.last_mut() else {
+            continue; // options before any group are ignored by mysql too
+        };
+        let (key, value) = line.split_once('=').unwrap_or((line, ""));
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        values.insert(key.trim().replace('_', "-"), value.to_string());
+    }
+
+    let mut defaults = BTreeMap::new();
+    let mut named = Vec::new();
+    for (group, values) in groups {
+        if DEFAULT_GROUPS.contains(&group.as_str()) {
+            defaults.extend(values);
+        } else if !is_server_group(&group) {
+            let name = group
+                .strip_prefix("client")
+                .or_else(|| group.strip_prefix("mysql"))
+                .map(|s| s.trim_start_matches(['_', '-']))
+                .filter(|s| !s.is_empty())
+                .unwrap_or(&group)
+                .to_string();
+            named.push((name, values));
+        }
+    }
+    if named.is_empty() && !defaults.is_empty() {
+        named.push(("client".to_string(), BTreeMap::new()));
+    }
+
+    named
+        .into_iter()
+        .map(|(name, values)| {
+            let mut values = values;
+            for (k, v) in &defaults {
+                values.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+            let get = |k: &str| values.get(k).filter(|v| !v.is_empty()).cloned();
+            let mut p = profile(DbKind::Mysql, name);
+            p.host = get("host").unwrap_or_else(|| "localhost".to_string());
+            p.port = get("port").and_then(|v| v.parse().ok()).unwrap_or(p.port);
+            p.user = get("user").unwrap_or_default();
+            p.database = get("database");
+            p.options.socket = get("socket");
+            p.options.charset = get("default-character-set");
+            p.options.connect_timeout_secs = get("connect-timeout").and_then(|v| v.parse().ok());
+            if let Some(mode) = get("ssl-mode") {
+                p.tls.mode = match mode.to_ascii_uppercase().as_str() {
+                    "DISABLED" => SslMode::Disabled,
+                    "REQUIRED" => SslMode::Required,
+                    "VERIFY_CA" => SslMode::VerifyCa,
+                    "VERIFY_IDENTITY" => SslMode::VerifyIdentity,
+                    _ => SslMode::Preferred,
+                };
+            }
+            p.tls.ca = get("ssl-ca");
+            p.tls.cert = get("ssl-cert");
+            p.tls.key = get("ssl-key");
+            let password = get("password")
+                .filter(|pw| !pw.chars().all(|c| c == '*'))
+                .unwrap_or_default();
+            ImportedConnection {
+                profile: p,
+                password,
+            }
+        })
+        .collect()
+}
+
+/// `mysql_config_editor` files start with four zero bytes, which no text
+/// file does.
+fn is_login_file(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0; 4])
+}
+
+/// The option file text inside a `~/.mylogin.cnf`: after the four unused
+/// bytes comes a 20-byte key, folded into an AES-128 key, then one chunk per
+/// line, each a little-endian length and that many bytes of AES-128-ECB
+/// ciphertext padded to the block size. `None` if it does not decrypt.
+pub fn decrypt_login_file(bytes: &[u8]) -> Option<String> {
+    let mut key = [0u8; 16];
+    for (i, b) in bytes.get(4..24)?.iter().enumerate() {
+        key[i % 16] ^= b;
+    }
+    let cipher = Aes128::new(&key.into());
+
+    let mut rest = &bytes[24..];
+    let mut text = Vec::new();
+    while let Some((len, tail)) = rest.split_first_chunk::<4>() {
+        let len = u32::from_le_bytes(*len) as usize;
+        if len == 0 || !len.is_multiple_of(16) || len > tail.len() {
+            return None;
+        }
+        let (chunk, tail) = tail.split_at(len);
+        let mut line = chunk.to_vec();
+        for block in line.chunks_exact_mut(16) {
+            cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+        let pad = *line.last()? as usize;
+        if !(1..=16).contains(&pad) {
+            return None;
+        }
+        line.truncate(len - pad);
+        text.extend(line);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+    String::from_utf8(text).ok()
+}
+
+/// The connections of a DBeaver `data-sources.json`. MySQL, MariaDB,
+/// Postgres and SQLite sources are kept; others are skipped. DBeaver keeps
+/// saved credentials in a separate encrypted file, so passwords only come
+/// along when the export has them in plain text. Folders become groups and
+/// connection types the environment.
+pub fn parse_dbeaver(json: &str) -> Result<Vec<ImportedConnection>, ExternalError> {
+    let root: Value = serde_json::from_str(json)?;
+    let Some(connections) = root.get("connections").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+    let text = |v: &Value, k: &str| match v.get(k) {
+        Some(Value::String(s)) if !s.is_empty() => Some(s.clone()),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let mut found = Vec::new();
+    for (id, conn) in connections {
+        let provider = text(conn, "provider").unwrap_or_default();
+        let driver = text(conn, "driver").unwrap_or_default();
+        let kind = if driver.contains("sqlite") {
+            DbKind::Sqlite
+        } else if ["mysql", "mariadb"].contains(&provider.as_str()) {
+            DbKind::Mysql
+        } else if provider == "postgresql" {
+            DbKind::Postgres
+        } else {
+            continue;
+        };
+        let config = conn.get("configuration").unwrap_or(&Value::Null);
+        let mut p = profile(kind, text(conn, "name").unwrap_or_else(|| id.clone()));
+        p.labels.group = text(conn, "folder");
+        p.labels.environment = text(config, "type").and_then(|t| environment(&t));
+        if kind.is_file() {
+            p.database = text(config, "database").or_else(|| {
+                text(config, "url").and_then(|u| u.strip_prefix("jdbc:sqlite:").map(str::to_string))
+            });
+            if p.database.is_none() {
+                continue;
+            }
+        } else {
+            p.host = text(config, "host").unwrap_or_else(|| "localhost".to_string());
+            p.port = text(config, "port")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(p.port);
+            p.user = text(config, "user").unwrap_or_default();
+            p.database = text(config, "database");
+        }
+        found.push(ImportedConnection {
+            profile: p,
+            password: text(config, "password").unwrap_or_default(),
+        });
+    }
+    Ok(found)
+}
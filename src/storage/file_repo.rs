@@ -1,5 +1,8 @@
-use super::{model::ConnectionProfile, repo::ConnectionRepo};
-use crate::errors::AppError;
+use super::{
+    model::ConnectionProfile,
+    repo::{ConnectionRepo, QueryRepo},
+};
+use crate::{domain::querylib::SavedQuery, errors::AppError};
 use std::{fs, path::PathBuf};
 
 pub struct FileConnectionRepo {
@@ -30,3 +33,32 @@ impl ConnectionRepo for FileConnectionRepo {
         Ok(())
     }
 }
+
+pub struct FileQueryRepo {
+    path: PathBuf,
+}
+
+impl FileQueryRepo {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl QueryRepo for FileQueryRepo {
+    fn load_queries(&self) -> Result<Vec<SavedQuery>, AppError> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        let data = fs::read_to_string(&self.path)?;
+        let queries = serde_json::from_str(&data).map_err(|e| AppError::Config(e.to_string()))?;
+        Ok(queries)
+    }
+
+    fn save_queries(&self, queries: &[SavedQuery]) -> Result<(), AppError> {
+        let data =
+            serde_json::to_string_pretty(queries).map_err(|e| AppError::Config(e.to_string()))?;
+        fs::write(&self.path, data)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
+pub mod external;
 pub mod file_repo;
 pub mod model;
 pub mod repo;
 pub mod secrets;
 pub mod worker;
+
+#[cfg(test)]
+mod external_tests;
@@ -1,11 +1,34 @@
+use crate::domain::connection::{
+    ConnectionLabels, ConnectionOptions, DbKind, SshTunnel, TlsSettings,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionProfile {
     pub id: String, // uuid later
+    #[serde(default)] // profiles saved before Postgres support are MySQL
+    pub kind: DbKind,
     pub name: String,
     pub host: String,
     pub port: u16,
     pub user: String,
-    pub database: String,
+    /// Default database/schema; `None` connects without one and leaves the
+    /// choice to the Schema screen's picker.
+    #[serde(default)]
+    pub database: Option<String>,
+    /// Seconds a Runner statement may take before it is cancelled.
+    #[serde(default)]
+    pub statement_timeout_secs: Option<u64>,
+    /// Bastion to forward through; its key passphrase lives in the keyring.
+    #[serde(default)]
+    pub ssh: Option<SshTunnel>,
+    #[serde(default)]
+    pub tls: TlsSettings,
+    #[serde(default)]
+    pub options: ConnectionOptions,
+    #[serde(default)]
+    pub labels: ConnectionLabels,
+    /// Destructive statements only run once the profile's name is typed.
+    #[serde(default)]
+    pub production: bool,
 }
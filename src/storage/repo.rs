@@ -1,7 +1,13 @@
 use super::model::ConnectionProfile;
+use crate::domain::querylib::SavedQuery;
 use crate::errors::AppError;
 
 pub trait ConnectionRepo {
     fn load_connections(&self) -> Result<Vec<ConnectionProfile>, AppError>;
     fn save_connections(&self, conns: &[ConnectionProfile]) -> Result<(), AppError>;
 }
+
+pub trait QueryRepo {
+    fn load_queries(&self) -> Result<Vec<SavedQuery>, AppError>;
+    fn save_queries(&self, queries: &[SavedQuery]) -> Result<(), AppError>;
+}
@@ -6,6 +6,12 @@ pub struct ConnectionSecrets {
     service: String,
 }
 
+impl Default for ConnectionSecrets {
+    fn default() -> Self {
+        Self::new("lensql")
+    }
+}
+
 impl ConnectionSecrets {
     pub fn new(service: impl Into<String>) -> Self {
         Self {
@@ -13,10 +19,6 @@ impl ConnectionSecrets {
         }
     }
 
-    pub fn default() -> Self {
-        Self::new("lensql")
-    }
-
     fn entry(&self, connection_id: &str) -> Result<Entry, AppError> {
         if connection_id.trim().is_empty() {
             return Err(AppError::Config(
@@ -29,6 +31,8 @@ impl ConnectionSecrets {
             connection_id = %connection_id,
             "keyring: creating entry"
         );
+        #[cfg(test)]
+        use_mock_store();
         Entry::new(&self.service, connection_id)
             .map_err(|e| AppError::Other(format!("keyring entry error: {}", e)))
     }
@@ -106,4 +110,38 @@ impl ConnectionSecrets {
     pub fn delete_password(&self, connection_id: &str) -> Result<(), AppError> {
         self.set_password(connection_id, "")
     }
+
+    /// The SSH key passphrase sits in its own entry next to the password.
+    pub fn set_ssh_passphrase(
+        &self,
+        connection_id: &str,
+        passphrase: &str,
+    ) -> Result<(), AppError> {
+        self.set_password(&ssh_account(connection_id), passphrase)
+    }
+
+    pub fn get_ssh_passphrase(&self, connection_id: &str) -> Result<Option<String>, AppError> {
+        self.get_password(&ssh_account(connection_id))
+    }
+
+    pub fn delete_ssh_passphrase(&self, connection_id: &str) -> Result<(), AppError> {
+        self.set_ssh_passphrase(connection_id, "")
+    }
+}
+
+/// Unit tests run the reducer, which saves secrets as it goes; those land in
+/// keyring's in-memory mock instead of the developer's OS keyring.
+#[cfg(test)]
+fn use_mock_store() {
+    static MOCK: std::sync::Once = std::sync::Once::new();
+    MOCK.call_once(|| {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder())
+    });
+}
+
+fn ssh_account(connection_id: &str) -> String {
+    if connection_id.trim().is_empty() {
+        return String::new(); // rejected by `entry`
+    }
+    format!("{}:ssh", connection_id)
 }
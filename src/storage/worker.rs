@@ -5,13 +5,18 @@ use crate::{
         command::StorageCommand,
         event::{Event, StorageEvent},
     },
-    storage::{file_repo::FileConnectionRepo, repo::ConnectionRepo},
+    storage::{
+        external::read_connections,
+        file_repo::{FileConnectionRepo, FileQueryRepo},
+        repo::{ConnectionRepo, QueryRepo},
+    },
 };
 
 pub async fn run(
     mut rx: mpsc::Receiver<StorageCommand>,
     tx: mpsc::Sender<Event>,
     repo: FileConnectionRepo,
+    query_repo: FileQueryRepo,
 ) {
     while let Some(cmd) = rx.recv().await {
         match cmd {
@@ -32,6 +37,32 @@ pub async fn run(
                     }
                 }
             }
+
+            StorageCommand::SaveQueries { queries } => {
+                let res = query_repo.save_queries(&queries);
+                match res {
+                    Ok(_) => {
+                        let _ = tx.send(Event::Storage(StorageEvent::QueriesSaved)).await;
+                    }
+                    Err(e) => {
+                        let _ = tx
+                            .send(Event::Storage(StorageEvent::Error {
+                                message: e.to_string(),
+                            }))
+                            .await;
+                    }
+                }
+            }
+
+            StorageCommand::ImportConnections { source } => {
+                let evt = match read_connections(&source) {
+                    Ok(connections) => StorageEvent::ConnectionsImported { connections },
+                    Err(e) => StorageEvent::Error {
+                        message: e.to_string(),
+                    },
+                };
+                let _ = tx.send(Event::Storage(evt)).await;
+            }
         }
     }
 }
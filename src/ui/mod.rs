@@ -1,18 +1,37 @@
+pub mod layout;
 pub mod screens;
 pub mod terminal;
+pub mod widgets;
 
 use crate::app::{screen::Screen, state::AppState};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
-    widgets::{Block, Borders, Paragraph},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Tabs},
 };
+use widgets::list::label_color;
 
 pub fn render(f: &mut Frame, state: &AppState) {
+    // A row of session tabs once more than one connection is open.
+    let mut area = f.area();
+    if state.sessions.tabs.len() > 1 {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+            .split(area);
+        let active = state.sessions.index(state.sessions.active).unwrap_or(0);
+        let tabs = Tabs::new(state.session_titles())
+            .select(active)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_widget(tabs, rows[0]);
+        area = rows[1];
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(1), Constraint::Length(2)].as_ref())
-        .split(f.size());
+        .split(area);
 
     // main content
     match state.screen {
@@ -24,16 +43,58 @@ pub fn render(f: &mut Frame, state: &AppState) {
     }
 
     // status bar
+    let mut message = state.status.message.clone();
+    if state.screen == Screen::Data {
+        let range = state.data.range_label();
+        if !range.is_empty() {
+            message = format!("{}  |  {}", range, message);
+        }
+    }
+    if let Some(t) = state.runner.task.as_ref() {
+        let spinner = screens::runner::spinner(state.status.tick);
+        let streaming = !state.runner.running;
+        if let Some(result) = state.runner.result.as_ref().filter(|_| streaming) {
+            message = format!(
+                "{} {} rows fetched (Ctrl+X: stop)  |  {}",
+                spinner,
+                result.rows.fetched(),
+                message
+            );
+        } else if state.screen != Screen::Runner {
+            message = format!(
+                "{} query {:.0}s  |  {}",
+                spinner,
+                t.started.elapsed().as_secs_f64(),
+                message
+            );
+        }
+    }
+
+    // A labelled profile tints the bar while connected, so prod stands out.
+    let active = state
+        .connections
+        .active_item()
+        .filter(|_| !state.status.connection_label.is_empty());
+    let labels = active.map(|c| &c.labels);
+    let mut connection = match labels.and_then(|l| l.environment) {
+        _ if state.status.connection_label.is_empty() => "Not connected".to_string(),
+        Some(env) => format!("Connected: {} [{}]", state.status.connection_label, env),
+        None => format!("Connected: {}", state.status.connection_label),
+    };
+    if active.is_some_and(|c| c.options.read_only) {
+        connection.push_str(" [read-only]");
+    }
     let status = format!(
-        " {}  |  {}  |  (1)Conn (2)Schema (3)Data (4)Queries (5)Runner | q:quit ",
-        if state.status.connection_label.is_empty() {
-            "Not connected".to_string()
-        } else {
-            format!("Connected: {}", state.status.connection_label)
-        },
-        state.status.message
+        " {}  |  {}  |  (1)Conn (2)Schema (3)Data (4)Queries (5)Runner | [ ]:tabs q:quit ",
+        connection, message
     );
+    let style = match labels.and_then(|l| l.color()) {
+        Some(c) => Style::default().bg(label_color(c)).fg(Color::Black),
+        None => Style::default(),
+    };
 
-    let bar = Paragraph::new(status).block(Block::default().borders(Borders::TOP));
+    let bar = Paragraph::new(status)
+        .style(style)
+        .block(Block::default().borders(Borders::TOP));
     f.render_widget(bar, chunks[1]);
 }
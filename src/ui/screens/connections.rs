@@ -1,38 +1,102 @@
-use crate::app::state::AppState;
+use crate::{
+    app::state::{AppState, ConnectionRow, NewConnectionDraft},
+    domain::connection::{ConnectionOptions, DbKind},
+    storage::external::DEFAULT_SOURCE,
+    ui::{
+        layout::centered_rect,
+        widgets::list::{filter_line, highlighted, label_color},
+    },
+};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs},
 };
 
 pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let conns = &state.connections;
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(area);
+    f.render_widget(filter_line(&conns.filter, conns.filtering), parts[0]);
+
     // --- list ---
-    let items: Vec<ListItem> = state
-        .connections
-        .items
+    let rows = conns.rows();
+    let items: Vec<ListItem> = rows
         .iter()
-        .map(|c| {
-            ListItem::new(format!(
-                "{}   {}:{}   {}   {}",
-                c.name, c.host, c.port, c.user, c.db
-            ))
+        .map(|row| {
+            let i = match *row {
+                ConnectionRow::Group {
+                    name,
+                    count,
+                    collapsed,
+                } => {
+                    return ListItem::new(format!(
+                        "{} {} ({})",
+                        if collapsed { "▸" } else { "▾" },
+                        name,
+                        count
+                    ))
+                    .style(Style::default().add_modifier(Modifier::BOLD));
+                }
+                ConnectionRow::Item(i) => i,
+            };
+            let c = &conns.items[i];
+            let rest = if c.kind.is_file() {
+                format!("   [{}]   {}", c.kind, c.db)
+            } else {
+                format!(
+                    "   [{}]   {}:{}   {}   {}",
+                    c.kind, c.host, c.port, c.user, c.db
+                )
+            };
+            let rest = match c.ssh.as_ref() {
+                Some(ssh) => format!("{}   via {}", rest, ssh.host),
+                None => rest,
+            };
+            let indent = if c.labels.group.is_some() { "  " } else { "" };
+            let mut spans = vec![Span::raw(indent)];
+            spans.extend(highlighted(&c.name, &conns.filter));
+            if let Some(env) = c.labels.environment {
+                let color = label_color(c.labels.color().unwrap_or(env.color()));
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(
+                    format!("[{}]", env),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ));
+            } else if let Some(color) = c.labels.color {
+                spans.push(Span::styled(" ●", Style::default().fg(label_color(color))));
+            }
+            spans.push(Span::raw(rest));
+            if !c.labels.tags.is_empty() {
+                let tags: Vec<String> = c.labels.tags.iter().map(|t| format!("#{}", t)).collect();
+                spans.push(Span::styled(
+                    format!("   {}", tags.join(" ")),
+                    Style::default().add_modifier(Modifier::DIM),
+                ));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let list = List::new(items)
         .block(
             Block::default()
-                .title("Connections (a:add, e:edit, d:delete, Enter:connect)")
+                .title(
+                    "Connections (a:add, e:edit, d:delete, i:import, /:filter, Enter:connect/fold, t:new tab)",
+                )
                 .borders(Borders::ALL),
         )
         .highlight_symbol("> ");
 
     let mut ls = ListState::default();
-    if !state.connections.items.is_empty() {
-        ls.select(Some(state.connections.selected));
+    if !rows.is_empty() {
+        ls.select(Some(conns.selected));
     }
-    f.render_stateful_widget(list, area, &mut ls);
+    f.render_stateful_widget(list, parts[1], &mut ls);
 
     // --- modal: add connection ---
     if let Some(d) = state.connections.adding.as_ref() {
@@ -54,6 +118,9 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
         let rows = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
@@ -67,12 +134,14 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
         let field = |idx: usize, label: &str, value: String| {
             let is_active = d.field == idx;
             let mut v = value;
-            if idx == 4 {
+            if idx == NewConnectionDraft::PASSWORD_FIELD
+                || idx == NewConnectionDraft::SSH_PASSPHRASE_FIELD
+            {
                 // password masked
                 v = "*".repeat(v.chars().count());
             }
 
-            let line = format!("{:<10} {}", format!("{}:", label), v);
+            let line = format!("{:<12} {}", format!("{}:", label), v);
             let style = if is_active {
                 Style::default().add_modifier(Modifier::REVERSED)
             } else {
@@ -81,16 +150,131 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
             Paragraph::new(line).style(style)
         };
 
-        f.render_widget(field(0, "Name", d.name.clone()), rows[0]);
-        f.render_widget(field(1, "Host", d.host.clone()), rows[1]);
-        f.render_widget(field(2, "Port", d.port.clone()), rows[2]);
-        f.render_widget(field(3, "User", d.user.clone()), rows[3]);
-        f.render_widget(field(4, "Password", d.password.clone()), rows[4]);
-        f.render_widget(field(5, "Database", d.database.clone()), rows[5]);
+        let pages = d.pages();
+        let tabs = Tabs::new(pages.iter().map(|&p| NewConnectionDraft::PAGES[p]))
+            .select(pages.iter().position(|&p| p == d.page))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Block::default().borders(Borders::BOTTOM));
+        f.render_widget(tabs, rows[0]);
 
-        let help = Paragraph::new("Tab/Shift+Tab: move • Enter: save • Esc: cancel")
-            .alignment(Alignment::Left);
-        f.render_widget(help, rows[6]);
+        let fields = d.fields();
+        for (row, &idx) in fields.iter().enumerate() {
+            let (label, value) = match idx {
+                NewConnectionDraft::NAME_FIELD => ("Name", d.name.clone()),
+                NewConnectionDraft::KIND_FIELD => ("Kind", format!("< {} >", d.kind)),
+                NewConnectionDraft::HOST_FIELD => ("Host", d.host.clone()),
+                NewConnectionDraft::PORT_FIELD => ("Port", d.port.clone()),
+                NewConnectionDraft::USER_FIELD => ("User", d.user.clone()),
+                NewConnectionDraft::PASSWORD_FIELD => ("Password", d.password.clone()),
+                NewConnectionDraft::TIMEOUT_FIELD if d.timeout.trim().is_empty() => {
+                    ("Timeout", "none (seconds)".to_string())
+                }
+                NewConnectionDraft::TIMEOUT_FIELD => ("Timeout", format!("{}s", d.timeout)),
+                NewConnectionDraft::SSH_HOST_FIELD if d.ssh_host.is_empty() => {
+                    ("SSH host", "none (direct connection)".to_string())
+                }
+                NewConnectionDraft::SSH_HOST_FIELD => ("SSH host", d.ssh_host.clone()),
+                NewConnectionDraft::SSH_PORT_FIELD => ("SSH port", d.ssh_port.clone()),
+                NewConnectionDraft::SSH_USER_FIELD if d.ssh_user.is_empty() => {
+                    ("SSH user", "ssh default".to_string())
+                }
+                NewConnectionDraft::SSH_USER_FIELD => ("SSH user", d.ssh_user.clone()),
+                NewConnectionDraft::SSH_KEY_FIELD if d.ssh_key.is_empty() => {
+                    ("Key file", "none (ssh-agent)".to_string())
+                }
+                NewConnectionDraft::SSH_KEY_FIELD => ("Key file", d.ssh_key.clone()),
+                NewConnectionDraft::SSH_PASSPHRASE_FIELD => {
+                    ("Passphrase", d.ssh_passphrase.clone())
+                }
+                NewConnectionDraft::SSH_CHECK_FIELD if d.ssh_skip_host_key_check => {
+                    ("Host key", "< don't check >".to_string())
+                }
+                NewConnectionDraft::SSH_CHECK_FIELD => {
+                    ("Host key", "< check known_hosts >".to_string())
+                }
+                NewConnectionDraft::SSL_MODE_FIELD => ("SSL mode", format!("< {} >", d.tls_mode)),
+                NewConnectionDraft::TLS_CA_FIELD if d.tls_ca.is_empty() => {
+                    ("CA file", "none (built-in roots)".to_string())
+                }
+                NewConnectionDraft::TLS_CA_FIELD => ("CA file", d.tls_ca.clone()),
+                NewConnectionDraft::TLS_CERT_FIELD if d.tls_cert.is_empty() => {
+                    ("Cert file", "none".to_string())
+                }
+                NewConnectionDraft::TLS_CERT_FIELD => ("Cert file", d.tls_cert.clone()),
+                NewConnectionDraft::TLS_KEY_FIELD if d.tls_key.is_empty() => {
+                    ("Key file", "none".to_string())
+                }
+                NewConnectionDraft::TLS_KEY_FIELD => ("Key file", d.tls_key.clone()),
+                NewConnectionDraft::CHARSET_FIELD if d.charset.is_empty() => {
+                    ("Charset", "utf8mb4".to_string())
+                }
+                NewConnectionDraft::CHARSET_FIELD => ("Charset", d.charset.clone()),
+                NewConnectionDraft::COLLATION_FIELD if d.collation.is_empty() => {
+                    ("Collation", "charset default".to_string())
+                }
+                NewConnectionDraft::COLLATION_FIELD => ("Collation", d.collation.clone()),
+                NewConnectionDraft::TIMEZONE_FIELD
+                    if d.timezone.is_empty() && d.kind == DbKind::Mysql =>
+                {
+                    ("Time zone", "+00:00".to_string())
+                }
+                NewConnectionDraft::TIMEZONE_FIELD if d.timezone.is_empty() => {
+                    ("Time zone", "server default".to_string())
+                }
+                NewConnectionDraft::TIMEZONE_FIELD => ("Time zone", d.timezone.clone()),
+                NewConnectionDraft::SOCKET_FIELD if d.socket.is_empty() => {
+                    ("Socket", "none (TCP)".to_string())
+                }
+                NewConnectionDraft::SOCKET_FIELD => ("Socket", d.socket.clone()),
+                NewConnectionDraft::CONNECT_TIMEOUT_FIELD if d.connect_timeout.is_empty() => (
+                    "Connect in",
+                    format!("{}s", ConnectionOptions::DEFAULT_CONNECT_TIMEOUT_SECS),
+                ),
+                NewConnectionDraft::CONNECT_TIMEOUT_FIELD => {
+                    ("Connect in", format!("{}s", d.connect_timeout))
+                }
+                NewConnectionDraft::POOL_SIZE_FIELD if d.pool_size.is_empty() => (
+                    "Pool size",
+                    ConnectionOptions::DEFAULT_POOL_SIZE.to_string(),
+                ),
+                NewConnectionDraft::POOL_SIZE_FIELD => ("Pool size", d.pool_size.clone()),
+                NewConnectionDraft::GROUP_FIELD if d.group.is_empty() => {
+                    ("Group", "none (top level)".to_string())
+                }
+                NewConnectionDraft::GROUP_FIELD => ("Group", d.group.clone()),
+                NewConnectionDraft::TAGS_FIELD if d.tags.is_empty() => {
+                    ("Tags", "none (comma-separated)".to_string())
+                }
+                NewConnectionDraft::TAGS_FIELD => ("Tags", d.tags.clone()),
+                NewConnectionDraft::ENVIRONMENT_FIELD => match d.environment {
+                    Some(env) => ("Environment", format!("< {} >", env)),
+                    None => ("Environment", "< none >".to_string()),
+                },
+                NewConnectionDraft::COLOR_FIELD => match (d.color, d.environment) {
+                    (Some(c), _) => ("Colour", format!("< {} >", c)),
+                    (None, Some(env)) => ("Colour", format!("< auto ({}) >", env.color())),
+                    (None, None) => ("Colour", "< none >".to_string()),
+                },
+                NewConnectionDraft::READ_ONLY_FIELD if d.read_only => {
+                    ("Read-only", "< yes: only reads run >".to_string())
+                }
+                NewConnectionDraft::READ_ONLY_FIELD => ("Read-only", "< no >".to_string()),
+                NewConnectionDraft::PRODUCTION_FIELD if d.production => (
+                    "Production",
+                    "< yes: confirm destructive statements >".to_string(),
+                ),
+                NewConnectionDraft::PRODUCTION_FIELD => ("Production", "< no >".to_string()),
+                _ if d.kind.is_file() => ("File", d.database.clone()),
+                _ => ("Database", d.database.clone()),
+            };
+            f.render_widget(field(idx, label, value), rows[row + 1]);
+        }
+
+        let help = Paragraph::new(
+            "Tab/Shift+Tab: move • ←/→: change • PgUp/PgDn: page • Ctrl+T: test • Enter: save • Esc: cancel",
+        )
+        .alignment(Alignment::Left);
+        f.render_widget(help, rows[fields.len() + 1]);
     }
 
     // --- modal: delete confirm ---
@@ -125,24 +309,50 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
             .alignment(Alignment::Left);
         f.render_widget(help, rows[1]);
     }
-}
+    // --- modal: import prompt ---
+    if let Some(source) = state.connections.importing.as_ref() {
+        let popup = centered_rect(70, 40, area);
+        f.render_widget(Clear, popup);
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+        let block = Block::default()
+            .title("Import Connections")
+            .borders(Borders::ALL);
+        f.render_widget(block, popup);
+
+        let inner = Rect {
+            x: popup.x + 2,
+            y: popup.y + 2,
+            width: popup.width - 4,
+            height: popup.height - 4,
+        };
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let input = if source.is_empty() {
+            format!("{:<12} {}", "Source:", DEFAULT_SOURCE)
+        } else {
+            format!("{:<12} {}", "Source:", source)
+        };
+        f.render_widget(
+            Paragraph::new(input).style(Style::default().add_modifier(Modifier::REVERSED)),
+            rows[0],
+        );
+
+        let body = "\nA mysql://, postgres:// or sqlite: DSN (TablePlus \"Copy as URL\" too),\n\
+                    a file of such URLs, a MySQL option file such as ~/.my.cnf,\n\
+                    ~/.mylogin.cnf, or a DBeaver data-sources.json.\n\
+                    TablePlus export files are not supported; copy each as a URL.\n\
+                    Passwords go to the keyring.";
+        f.render_widget(Paragraph::new(body), rows[1]);
+
+        let help = Paragraph::new("Enter: import  •  Esc: cancel").alignment(Alignment::Left);
+        f.render_widget(help, rows[2]);
+    }
 }
@@ -1,14 +1,278 @@
-use crate::app::state::AppState;
+use crate::{
+    app::state::{AppState, CellEditor, ChangeReview, DataState, FilterDraft},
+    ui::{
+        layout::centered_rect,
+        widgets::{export_dialog, import_dialog, popup, table_grid::TableGrid},
+    },
+};
 use ratatui::{
     Frame,
     layout::Rect,
-    widgets::{Block, Borders, Paragraph},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
-pub fn render(f: &mut Frame, area: Rect, _state: &AppState) {
-    let w = Paragraph::new(
-        "Data view placeholder.\n\nNext: table rows grid + paging + column jump (g c).",
-    )
-    .block(Block::default().title("Data").borders(Borders::ALL));
-    f.render_widget(w, area);
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let data = &state.data;
+
+    let Some(table) = data.table.as_deref() else {
+        let w = Paragraph::new("No table open.\n\nSelect a table on Schema and press t.")
+            .block(Block::default().title("Data").borders(Borders::ALL));
+        f.render_widget(w, area);
+        return;
+    };
+
+    if data.columns.is_empty() {
+        // Tables opened through a reference are described before they load.
+        let body = if data.loading || !data.trail.is_empty() {
+            "Loading rows…"
+        } else {
+            "(No columns)"
+        };
+        let w = Paragraph::new(body).block(
+            Block::default()
+                .title(format!("Data: {}", data.breadcrumb()))
+                .borders(Borders::ALL),
+        );
+        f.render_widget(w, area);
+    } else {
+        let table = data.breadcrumb();
+        let pending = data.changes.count();
+        let title = if pending > 0 {
+            format!(
+                "Data: {} [{} pending] (s: review and save, u: discard)",
+                table, pending
+            )
+        } else if data.filter.is_empty() {
+            format!(
+                "Data: {} (PgUp/PgDn page, ←/→ columns, Enter: edit, v: view, g/r: references, \
+                 n: new row, d: delete, f: filter, Space: mark, e: export, i: import)",
+                table
+            )
+        } else {
+            let back = if data.trail.is_empty() {
+                ""
+            } else {
+                ", Esc: back"
+            };
+            format!(
+                "Data: {} [{}] (f: edit, x: clear, g/r: references{})",
+                table,
+                data.filter.summary(),
+                back
+            )
+        };
+        let grid = TableGrid {
+            title,
+            columns: &data.columns,
+            rows: &data.rows,
+            selected_row: data.selected_row,
+            col_offset: data.col_offset,
+            frozen: data.frozen_index(),
+            selected_col: Some(data.selected_col),
+            changes: Some(&data.changes),
+            marked: Some(&data.marked),
+        };
+        grid.render(f, area);
+    }
+
+    if let Some(draft) = data.filter_draft.as_ref() {
+        render_builder(f, area, draft);
+    }
+    if let Some(draft) = data.export.as_ref() {
+        let all = if data.filter.is_empty() {
+            "whole table"
+        } else {
+            "all matching rows"
+        };
+        let shown = match data.marked.len() {
+            0 => "shown".to_string(),
+            n => format!("{} marked", n),
+        };
+        export_dialog::render(f, area, draft, &shown, all);
+    }
+    if let Some(draft) = data.import.as_ref() {
+        import_dialog::render(f, area, draft, table);
+    }
+    if let Some(detail) = data.detail.as_ref()
+        && let Some(row) = data.record(data.selected_row)
+    {
+        let title = format!(
+            "{}: row {} of {}",
+            table,
+            data.selected_row + 1,
+            data.rows.len()
+        );
+        popup::render_record(f, area, &title, &data.columns, &row, detail);
+    }
+    if let Some(selected) = data.reference_picker {
+        render_reference_picker(f, area, data, selected);
+    }
+    if let Some(ed) = data.cell_editor.as_ref() {
+        render_cell_editor(f, area, data, ed);
+    }
+    if let Some(review) = data.review.as_ref() {
+        render_review(f, area, review, table);
+    }
+}
+
+/// Tables with a foreign key to the open one; Enter opens the rows that
+/// reference the selected row.
+fn render_reference_picker(f: &mut Frame, area: Rect, data: &DataState, selected: usize) {
+    let popup = centered_rect(50, 40, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .title("Referenced by (Enter: open rows, Esc: cancel)")
+        .borders(Borders::ALL);
+    let items: Vec<ListItem> = data
+        .referenced_by
+        .iter()
+        .map(|r| {
+            ListItem::new(format!(
+                "{} ({} → {})",
+                r.table,
+                r.key.columns.join(", "),
+                r.key.ref_columns.join(", ")
+            ))
+        })
+        .collect();
+    let list = List::new(items).block(block).highlight_symbol("> ");
+    let mut ls = ListState::default();
+    ls.select(Some(selected));
+    f.render_stateful_widget(list, popup, &mut ls);
+}
+
+fn render_cell_editor(f: &mut Frame, area: Rect, data: &DataState, ed: &CellEditor) {
+    let popup = centered_rect(60, 20, area);
+    f.render_widget(Clear, popup);
+    let column = data.edit_columns().swap_remove(ed.col);
+    let value = match ed.value.as_deref() {
+        Some(v) => Line::from(format!("{}_", v)),
+        None => Line::styled("NULL", Style::default().add_modifier(Modifier::DIM)),
+    };
+    let lines = vec![
+        value,
+        Line::from(""),
+        Line::styled(
+            "Enter: stage • Del: NULL • Esc: cancel",
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    ];
+    let w = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(
+                "Edit {} ({}), row {}",
+                column.name,
+                column.data_type,
+                ed.row + 1
+            ))
+            .borders(Borders::ALL),
+    );
+    f.render_widget(w, popup);
+}
+
+fn render_review(f: &mut Frame, area: Rect, review: &ChangeReview, table: &str) {
+    let popup = centered_rect(80, 60, area);
+    f.render_widget(Clear, popup);
+
+    let mut lines: Vec<Line> = review
+        .statements
+        .iter()
+        .skip(review.scroll)
+        .map(|s| Line::from(format!("{};", s.preview)))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        if review.saving {
+            "Saving…"
+        } else {
+            "↑/↓ scroll • Enter: save in one transaction • Esc: back"
+        },
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+
+    let w = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(format!(
+                "Save {} changes to {}",
+                review.statements.len(),
+                table
+            ))
+            .borders(Borders::ALL),
+    );
+    f.render_widget(w, popup);
+}
+
+fn render_builder(f: &mut Frame, area: Rect, draft: &FilterDraft) {
+    let popup = centered_rect(70, 60, area);
+    f.render_widget(Clear, popup);
+
+    let field = |row: usize, idx: usize, text: String| {
+        if draft.row == row && draft.field == idx {
+            Span::styled(text, Style::default().add_modifier(Modifier::REVERSED))
+        } else {
+            Span::raw(text)
+        }
+    };
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![Line::styled("Where", bold)];
+    if draft.filter.conditions.is_empty() {
+        lines.push(Line::from("  (all rows)"));
+    }
+    for (i, c) in draft.filter.conditions.iter().enumerate() {
+        let join = if i == 0 { "WHERE" } else { c.join.sql() };
+        let mut spans = vec![
+            Span::raw(if draft.row == i { "> " } else { "  " }),
+            field(i, 0, format!("{:<5}", join)),
+            Span::raw(" "),
+            field(i, 1, c.column.clone()),
+            Span::raw(" "),
+            field(i, 2, c.op.sql().to_string()),
+        ];
+        if c.op.takes_value() {
+            let cursor = if draft.row == i && draft.field == FilterDraft::VALUE_FIELD {
+                "_"
+            } else {
+                ""
+            };
+            spans.push(Span::raw(" "));
+            spans.push(field(i, 3, format!("'{}{}'", c.value, cursor)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::styled("Order by", bold));
+    if draft.filter.sort.is_empty() {
+        lines.push(Line::from("  (primary key)"));
+    }
+    let offset = draft.filter.conditions.len();
+    for (i, k) in draft.filter.sort.iter().enumerate() {
+        let row = offset + i;
+        lines.push(Line::from(vec![
+            Span::raw(if draft.row == row { "> " } else { "  " }),
+            field(row, 0, k.column.clone()),
+            Span::raw(" "),
+            field(
+                row,
+                1,
+                if k.descending { "DESC" } else { "ASC" }.to_string(),
+            ),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        "↑/↓ row • Tab field • ←/→ change • Ctrl+N condition • Ctrl+O sort • Ctrl+D remove",
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+
+    let w = Paragraph::new(lines).block(
+        Block::default()
+            .title("Filter rows (Enter: apply, Esc: cancel)")
+            .borders(Borders::ALL),
+    );
+    f.render_widget(w, popup);
 }
@@ -1,13 +1,150 @@
-use crate::app::state::AppState;
+use crate::{
+    app::state::AppState,
+    domain::querylib::format_unix,
+    ui::{
+        layout::centered_rect,
+        widgets::list::{filter_line, highlighted},
+    },
+};
 use ratatui::{
     Frame,
-    layout::Rect,
-    widgets::{Block, Borders, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
-pub fn render(f: &mut Frame, area: Rect, _state: &AppState) {
-    let w =
-        Paragraph::new("Query library placeholder.\n\nNext: saved queries list + preview + run.")
-            .block(Block::default().title("Queries").borders(Borders::ALL));
-    f.render_widget(w, area);
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let q = &state.queries;
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+        .split(area);
+
+    // ---- Left: filter line + list ----
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(chunks[0]);
+
+    f.render_widget(filter_line(&q.filter, q.filtering), left[0]);
+
+    let visible = q.visible();
+    let items: Vec<ListItem> = visible
+        .iter()
+        .map(|&i| {
+            let item = &q.items[i];
+            let mut spans = highlighted(&item.name, &q.filter);
+            if !item.tags.is_empty() {
+                spans.push(Span::raw(format!("  [{}]", item.tags.join(", "))));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(
+                    "Queries {}/{} (Enter:open, r:rename, d:delete)",
+                    visible.len(),
+                    q.items.len()
+                ))
+                .borders(Borders::ALL),
+        )
+        .highlight_symbol("> ");
+
+    let mut ls = ListState::default();
+    if !visible.is_empty() {
+        ls.select(Some(q.selected));
+    }
+    f.render_stateful_widget(list, left[1], &mut ls);
+
+    // ---- Right: preview ----
+    let (title, body) = match q.selected_item() {
+        None if q.items.is_empty() => (
+            "Preview".to_string(),
+            "No saved queries yet.\n\nWrite SQL in the Runner (5) and press Ctrl+S to save it."
+                .to_string(),
+        ),
+        None => ("Preview".to_string(), "(No matches)".to_string()),
+        Some(item) => {
+            let connection = item
+                .connection_id
+                .as_deref()
+                .and_then(|id| {
+                    state
+                        .connections
+                        .items
+                        .iter()
+                        .find(|c| c.id.to_string() == id)
+                })
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "any".to_string());
+
+            let meta = format!(
+                "Tags: {}\nConnection: {}\nCreated: {}   Updated: {}\n\n",
+                if item.tags.is_empty() {
+                    "-".to_string()
+                } else {
+                    item.tags.join(", ")
+                },
+                connection,
+                format_unix(item.created_at),
+                format_unix(item.updated_at),
+            );
+            (format!("Query: {}", item.name), meta + &item.sql)
+        }
+    };
+
+    let preview = Paragraph::new(body)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(preview, chunks[1]);
+
+    // --- modal: rename ---
+    if let Some(name) = q.renaming.as_ref() {
+        let popup = centered_rect(60, 20, area);
+        f.render_widget(Clear, popup);
+        let block = Block::default().title("Rename Query").borders(Borders::ALL);
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+            .split(inner);
+        f.render_widget(
+            Paragraph::new(format!("Name: {}", name))
+                .style(Style::default().add_modifier(Modifier::REVERSED)),
+            rows[0],
+        );
+        f.render_widget(
+            Paragraph::new("Enter: save • Esc: cancel").alignment(Alignment::Left),
+            rows[1],
+        );
+    }
+
+    // --- modal: delete confirm ---
+    if let Some(c) = q.delete_confirm.as_ref() {
+        let popup = centered_rect(60, 25, area);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .title("Delete Query?")
+            .borders(Borders::ALL);
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+            .split(inner);
+        let body = format!(
+            "Are you sure you want to delete:\n\n{}\n\nThis cannot be undone.",
+            c.name
+        );
+        f.render_widget(Paragraph::new(body), rows[0]);
+        f.render_widget(Paragraph::new("y/Enter: delete  •  n/Esc: cancel"), rows[1]);
+    }
 }
@@ -1,12 +1,270 @@
-use crate::app::state::AppState;
+use crate::{
+    app::{
+        editor::TextBuffer,
+        state::{AppState, RunnerFocus},
+    },
+    ui::{
+        layout::centered_rect,
+        widgets::{export_dialog, popup, table_grid::TableGrid},
+    },
+};
 use ratatui::{
     Frame,
-    layout::Rect,
-    widgets::{Block, Borders, Paragraph},
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 
-pub fn render(f: &mut Frame, area: Rect, _state: &AppState) {
-    let w = Paragraph::new("Query runner placeholder.\n\nNext: editor + results view + save (s).")
-        .block(Block::default().title("Runner").borders(Borders::ALL));
-    f.render_widget(w, area);
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    render_panes(f, area, state);
+
+    // --- modal: record view ---
+    if let Some(detail) = state.runner.detail.as_ref()
+        && let Some(result) = state.runner.result.as_ref()
+        && let Some(row) = result.rows.get(state.runner.selected_row)
+    {
+        let title = format!(
+            "Result: row {} of {}",
+            result.rows.dropped() + state.runner.selected_row as u64 + 1,
+            result.rows.fetched()
+        );
+        popup::render_record(f, area, &title, &result.columns, row, detail);
+    }
+
+    // --- modal: export ---
+    if let Some(d) = state.runner.export.as_ref() {
+        export_dialog::render(f, area, d, "shown", "all (re-run query)");
+    }
+
+    // --- modal: save query ---
+    if let Some(d) = state.runner.save_prompt.as_ref() {
+        let popup = centered_rect(60, 30, area);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .title(if d.id.is_some() {
+                "Update Saved Query"
+            } else {
+                "Save Query"
+            })
+            .borders(Borders::ALL);
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .split(inner);
+
+        let field = |idx: usize, label: &str, value: &str| {
+            let style = if d.field == idx {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Paragraph::new(format!("{:<6} {}", format!("{}:", label), value)).style(style)
+        };
+        f.render_widget(field(0, "Name", &d.name), rows[0]);
+        f.render_widget(field(1, "Tags", &d.tags), rows[1]);
+        f.render_widget(
+            Paragraph::new("Tags are comma-separated • Tab: move • Enter: save • Esc: cancel"),
+            rows[2],
+        );
+    }
+
+    // --- modal: destructive statement on a production connection ---
+    if let Some(c) = state.runner.destructive_confirm.as_ref() {
+        let popup = centered_rect(60, 40, area);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .title("Destructive Statement")
+            .borders(Borders::ALL);
+        let inner = block.inner(popup);
+        f.render_widget(block, popup);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let body = format!(
+            "{} is a production connection. This may drop, truncate, alter or\n\
+             rewrite every row:\n\n{}\n\nType the connection name to run it.",
+            c.name, c.sql
+        );
+        f.render_widget(Paragraph::new(body).wrap(Wrap { trim: false }), rows[0]);
+        f.render_widget(
+            Paragraph::new(format!("{:<6} {}", "Name:", c.typed))
+                .style(Style::default().add_modifier(Modifier::REVERSED)),
+            rows[1],
+        );
+        f.render_widget(Paragraph::new("Enter: run • Esc: cancel"), rows[2]);
+    }
+}
+
+fn render_panes(f: &mut Frame, area: Rect, state: &AppState) {
+    let runner = &state.runner;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)].as_ref())
+        .split(area);
+
+    // ---- Top: editor ----
+    let editing = runner.focus == RunnerFocus::Editor;
+    let title = if editing {
+        "SQL (Ctrl+R/F5 run • Ctrl+Z/Y undo/redo • Ctrl+S save • Esc results)"
+    } else {
+        "SQL (i: edit • s: save • e: export • v: view row)"
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let inner = block.inner(chunks[0]);
+    f.render_widget(block, chunks[0]);
+
+    let (lines, cursor) = wrap_editor(&runner.editor, inner.width.max(1) as usize);
+    let scroll = cursor
+        .0
+        .saturating_sub(inner.height.saturating_sub(1) as usize);
+    f.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner);
+
+    if editing && runner.save_prompt.is_none() {
+        f.set_cursor_position(Position::new(
+            inner.x + cursor.1 as u16,
+            inner.y + (cursor.0 - scroll) as u16,
+        ));
+    }
+
+    // ---- Bottom: results ----
+    if runner.running {
+        let body = match &runner.task {
+            Some(t) if t.cancelling => "Cancelling…".to_string(),
+            Some(t) => format!(
+                "{} Running… {:.1}s (Ctrl+X: cancel)",
+                spinner(state.status.tick),
+                t.started.elapsed().as_secs_f64()
+            ),
+            None => "Running…".to_string(),
+        };
+        let w = Paragraph::new(body).block(Block::default().title("Results").borders(Borders::ALL));
+        f.render_widget(w, chunks[1]);
+        return;
+    }
+
+    if let Some(err) = runner.error.as_deref() {
+        let w = Paragraph::new(err.to_string())
+            .block(Block::default().title("Error").borders(Borders::ALL));
+        f.render_widget(w, chunks[1]);
+        return;
+    }
+
+    let Some(result) = runner.result.as_ref() else {
+        let w = Paragraph::new("Run a statement to see results here.")
+            .block(Block::default().title("Results").borders(Borders::ALL));
+        f.render_widget(w, chunks[1]);
+        return;
+    };
+
+    let elapsed = result.elapsed.as_millis();
+    if result.columns.is_empty() {
+        let w = Paragraph::new(format!("{} row(s) affected", result.rows_affected)).block(
+            Block::default()
+                .title(format!("Results ({} ms)", elapsed))
+                .borders(Borders::ALL),
+        );
+        f.render_widget(w, chunks[1]);
+        return;
+    }
+
+    // Rows still streaming in are counted with a `+`; ones dropped from the
+    // front of the buffer are no longer shown, and the title says so, as it
+    // does when the rest of the result can no longer be read.
+    let more = if runner.task.is_some() { "+" } else { "" };
+    let mut title = match result.rows.dropped_label() {
+        None => format!(
+            "Results ({}{} rows, {} ms)",
+            result.rows.fetched(),
+            more,
+            elapsed
+        ),
+        Some(dropped) => format!(
+            "Results ({}; {}–{}{} shown, {} ms)",
+            dropped,
+            result.rows.dropped() + 1,
+            result.rows.fetched(),
+            more,
+            elapsed
+        ),
+    };
+    if runner.expired {
+        title += " — stream expired, re-run to continue";
+    }
+
+    // Lay out only the rows around the cursor, so a long result stays cheap
+    // to draw; the grid keeps the selection in view either way.
+    let height = chunks[1].height as usize;
+    let end = (runner.selected_row + height).min(result.rows.len());
+    let start = runner.selected_row.saturating_sub(height).min(end);
+    let rows = result.rows.window(start..end);
+    let grid = TableGrid {
+        title,
+        columns: &result.columns,
+        rows: &rows,
+        selected_row: runner.selected_row - start,
+        col_offset: runner.col_offset,
+        frozen: None,
+        selected_col: None,
+        changes: None,
+        marked: None,
+    };
+    grid.render(f, chunks[1]);
+}
+
+/// Braille spinner frame for `tick`.
+pub fn spinner(tick: usize) -> char {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    FRAMES[tick % FRAMES.len()]
+}
+
+/// Soft-wrap the buffer to `width` and return the visual lines plus the cursor's
+/// visual `(row, col)`. Every logical line gets `len / width + 1` rows, so a cursor
+/// sitting at the end of a full-width line still has a row to land on.
+fn wrap_editor(buf: &TextBuffer, width: usize) -> (Vec<Line<'static>>, (usize, usize)) {
+    let selection = buf.selection();
+    let (cur_row, cur_col) = buf.cursor();
+    let selected = |pos: (usize, usize)| selection.is_some_and(|(a, b)| pos >= a && pos < b);
+
+    let mut out = Vec::new();
+    let mut cursor = (0, 0);
+
+    for (row, line) in buf.lines().iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if row == cur_row {
+            cursor = (out.len() + cur_col / width, cur_col % width);
+        }
+
+        for chunk_start in (0..=chars.len()).step_by(width) {
+            let chunk_end = (chunk_start + width).min(chars.len());
+            let spans: Vec<Span> = (chunk_start..chunk_end)
+                .map(|col| {
+                    let span = Span::raw(chars[col].to_string());
+                    if selected((row, col)) {
+                        span.style(Style::default().add_modifier(Modifier::REVERSED))
+                    } else {
+                        span
+                    }
+                })
+                .collect();
+            out.push(Line::from(spans));
+        }
+    }
+
+    (out, cursor)
 }
@@ -1,75 +1,369 @@
-use crate::app::state::AppState;
+use crate::{
+    app::state::{AppState, DatabasePicker, SchemaRow, SchemaTab},
+    db::introstect::{ObjectKind, SchemaObject, TableDetails},
+    ui::{
+        layout::centered_rect,
+        widgets::list::{filter_line, highlighted},
+    },
+};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs},
 };
 
 pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    render_panes(f, area, state);
+
+    if let Some(picker) = state.schema.picker.as_ref() {
+        render_picker(f, area, picker);
+    }
+}
+
+/// Modal list of the server's databases (schemas); the active one is marked.
+fn render_picker(f: &mut Frame, area: Rect, picker: &DatabasePicker) {
+    let popup = centered_rect(40, 50, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .title("Switch database (Enter: use, Esc: cancel)")
+        .borders(Borders::ALL);
+
+    if picker.loading {
+        f.render_widget(Paragraph::new("Loading…").block(block), popup);
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .items
+        .iter()
+        .map(|name| {
+            if picker.current.as_deref() == Some(name.as_str()) {
+                ListItem::new(format!("{} (current)", name))
+                    .style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                ListItem::new(name.as_str())
+            }
+        })
+        .collect();
+    let list = List::new(items).block(block).highlight_symbol("> ");
+
+    let mut ls = ListState::default();
+    if !picker.items.is_empty() {
+        ls.select(Some(picker.selected));
+    }
+    f.render_stateful_widget(list, popup, &mut ls);
+}
+
+fn render_panes(f: &mut Frame, area: Rect, state: &AppState) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
         .split(area);
 
-    // ---- Left: Tables list ----
-    let table_items: Vec<ListItem> = state
-        .schema
-        .tables
+    // ---- Left: filter line + object tree ----
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(chunks[0]);
+    f.render_widget(
+        filter_line(&state.schema.filter, state.schema.filtering),
+        left[0],
+    );
+
+    let rows = state.schema.rows();
+    let items: Vec<ListItem> = rows
         .iter()
-        .map(|t| ListItem::new(t.clone()))
+        .map(|row| match row {
+            SchemaRow::Group {
+                kind,
+                count,
+                collapsed,
+            } => ListItem::new(format!(
+                "{} {} ({})",
+                if *collapsed { "▸" } else { "▾" },
+                kind.group_title(),
+                count
+            ))
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+            SchemaRow::Object(o) => {
+                let mut spans = vec![Span::raw(format!("  {} ", marker(o.kind)))];
+                spans.extend(highlighted(&o.name, &state.schema.filter));
+                let item = ListItem::new(Line::from(spans));
+                if o.kind == ObjectKind::View {
+                    item.style(Style::default().add_modifier(Modifier::ITALIC))
+                } else {
+                    item
+                }
+            }
+        })
         .collect();
 
-    let tables = List::new(table_items)
-        .block(Block::default().title("Tables").borders(Borders::ALL))
+    let tree = List::new(items)
+        .block(
+            Block::default()
+                .title("Objects (Enter: fold, t: rows, d: database, /: filter)")
+                .borders(Borders::ALL),
+        )
         .highlight_symbol("> ");
 
     let mut ls = ListState::default();
-    if !state.schema.tables.is_empty() {
-        ls.select(Some(state.schema.selected_table));
+    if !rows.is_empty() {
+        ls.select(Some(state.schema.selected));
     }
 
-    f.render_stateful_widget(tables, chunks[0], &mut ls);
+    f.render_stateful_widget(tree, left[1], &mut ls);
 
-    // ---- Right: Columns / details ----
-    let selected_table = state
-        .schema
-        .tables
-        .get(state.schema.selected_table)
-        .cloned()
-        .unwrap_or_default();
+    // ---- Right: details ----
+    let selected = state.schema.selected_row();
+
+    let (label, selected_table) = match selected {
+        Some(SchemaRow::Object(o)) if !o.kind.is_relation() => {
+            render_definition(f, chunks[1], state, o);
+            return;
+        }
+        Some(SchemaRow::Group { kind, count, .. }) => {
+            let body = format!(
+                "{} {}.\n\nEnter: collapse/expand • ↑/↓: move",
+                count,
+                kind.group_title().to_lowercase()
+            );
+            let w = Paragraph::new(body).block(
+                Block::default()
+                    .title(kind.group_title())
+                    .borders(Borders::ALL),
+            );
+            f.render_widget(w, chunks[1]);
+            return;
+        }
+        Some(SchemaRow::Object(o)) if o.kind == ObjectKind::View => ("View", o.name.clone()),
+        Some(SchemaRow::Object(o)) => ("Table", o.name.clone()),
+        None => ("", String::new()),
+    };
 
     let title = if selected_table.is_empty() {
         "Details".to_string()
     } else {
-        format!("Table: {}", selected_table)
+        format!("{}: {} (←/→ tabs, PgUp/PgDn scroll)", label, selected_table)
     };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let inner = block.inner(chunks[1]);
+    f.render_widget(block, chunks[1]);
 
-    let body = if selected_table.is_empty() {
-        "Connect to a DB to load tables.\n\nEnter on Connections connects.\n".to_string()
-    } else if state.schema.columns_table.as_deref() != Some(&selected_table) {
-        "Loading columns…".to_string()
-    } else if state.schema.columns.is_empty() {
-        "(No columns)".to_string()
-    } else {
-        let mut lines = Vec::new();
-        for c in &state.schema.columns {
-            let nullability = if c.is_nullable { "NULL" } else { "NOT NULL" };
-            let key = c.column_key.as_deref().unwrap_or("");
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)].as_ref())
+        .split(inner);
 
-            if key.is_empty() {
-                lines.push(format!("{}  ({}, {})", c.name, c.data_type, nullability));
-            } else {
-                // PRI / MUL / UNI etc.
-                lines.push(format!(
-                    "{}  ({}, {}, {})",
-                    c.name, c.data_type, nullability, key
-                ));
-            }
+    let tabs = Tabs::new(SchemaTab::ALL.iter().map(|t| t.title()))
+        .select(state.schema.tab.index())
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(tabs, parts[0]);
+
+    let details = state
+        .schema
+        .details
+        .as_ref()
+        .filter(|d| d.table == selected_table);
+
+    let body: Vec<Line> = match details {
+        None if selected_table.is_empty() => {
+            vec![
+                "Connect to a DB to load tables.".into(),
+                "".into(),
+                "Enter on Connections connects.".into(),
+            ]
         }
-        lines.join("\n")
+        None => vec!["Loading columns…".into()],
+        Some(d) => match state.schema.tab {
+            SchemaTab::Columns => columns_lines(d),
+            SchemaTab::Indexes => indexes_lines(d),
+            SchemaTab::ForeignKeys => foreign_keys_lines(d),
+            SchemaTab::Ddl => d.ddl.lines().map(|l| Line::from(l.to_string())).collect(),
+        },
+    };
+
+    let detail = Paragraph::new(body).scroll((state.schema.detail_scroll, 0));
+    f.render_widget(detail, parts[1]);
+}
+
+/// Source of a routine, trigger or event.
+fn render_definition(f: &mut Frame, area: Rect, state: &AppState, object: &SchemaObject) {
+    let body = match state.schema.definition.as_ref() {
+        Some((o, def)) if o == object && def.is_empty() => "(No definition)".to_string(),
+        Some((o, def)) if o == object => def.clone(),
+        _ => "Loading definition…".to_string(),
+    };
+    let title = format!(
+        "{}: {} (PgUp/PgDn scroll)",
+        match object.kind {
+            ObjectKind::Procedure => "Procedure",
+            ObjectKind::Function => "Function",
+            ObjectKind::Trigger => "Trigger",
+            _ => "Event",
+        },
+        object.name
+    );
+    let w = Paragraph::new(body)
+        .scroll((state.schema.detail_scroll, 0))
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(w, area);
+}
+
+fn marker(kind: ObjectKind) -> &'static str {
+    match kind {
+        ObjectKind::Table => "▦",
+        ObjectKind::View => "◇",
+        ObjectKind::Procedure => "▷",
+        ObjectKind::Function => "ƒ",
+        ObjectKind::Trigger => "↯",
+        ObjectKind::Event => "◷",
+    }
+}
+
+fn columns_lines(d: &TableDetails) -> Vec<Line<'static>> {
+    if d.columns.is_empty() {
+        return vec!["(No columns)".into()];
+    }
+    let rows = d
+        .columns
+        .iter()
+        .map(|c| {
+            let collation = match (&c.charset, &c.collation) {
+                (Some(cs), Some(co)) => format!("{}/{}", cs, co),
+                (None, Some(co)) => co.clone(),
+                (Some(cs), None) => cs.clone(),
+                (None, None) => String::new(),
+            };
+            vec![
+                c.name.clone(),
+                c.column_type.clone(),
+                if c.is_nullable { "NULL" } else { "NOT NULL" }.to_string(),
+                c.column_key.clone().unwrap_or_default(),
+                c.default.clone().unwrap_or_default(),
+                c.extra.clone(),
+                collation,
+                c.comment.clone(),
+            ]
+        })
+        .collect();
+    aligned(
+        &[
+            "Name",
+            "Type",
+            "Null",
+            "Key",
+            "Default",
+            "Extra",
+            "Collation",
+            "Comment",
+        ],
+        rows,
+    )
+}
+
+fn indexes_lines(d: &TableDetails) -> Vec<Line<'static>> {
+    if d.indexes.is_empty() {
+        return vec!["(No indexes)".into()];
+    }
+    let rows = d
+        .indexes
+        .iter()
+        .map(|ix| {
+            let kind = if ix.primary {
+                "PRIMARY"
+            } else if ix.unique {
+                "UNIQUE"
+            } else {
+                ""
+            };
+            vec![
+                ix.name.clone(),
+                ix.columns.join(", "),
+                kind.to_string(),
+                ix.index_type.clone(),
+            ]
+        })
+        .collect();
+    aligned(&["Name", "Columns", "Kind", "Type"], rows)
+}
+
+fn foreign_keys_lines(d: &TableDetails) -> Vec<Line<'static>> {
+    let mut lines = if d.foreign_keys.is_empty() {
+        vec!["(No foreign keys)".into()]
+    } else {
+        let rows = d
+            .foreign_keys
+            .iter()
+            .map(|fk| {
+                vec![
+                    fk.name.clone(),
+                    fk.columns.join(", "),
+                    format!("{}({})", fk.ref_table, fk.ref_columns.join(", ")),
+                    fk.on_update.clone(),
+                    fk.on_delete.clone(),
+                ]
+            })
+            .collect();
+        aligned(
+            &["Name", "Columns", "References", "On update", "On delete"],
+            rows,
+        )
     };
+    if !d.referenced_by.is_empty() {
+        let rows = d
+            .referenced_by
+            .iter()
+            .map(|r| {
+                vec![
+                    r.key.name.clone(),
+                    format!("{}({})", r.table, r.key.columns.join(", ")),
+                    r.key.ref_columns.join(", "),
+                    r.key.on_update.clone(),
+                    r.key.on_delete.clone(),
+                ]
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.extend(aligned(
+            &["Name", "Referenced by", "Columns", "On update", "On delete"],
+            rows,
+        ));
+    }
+    lines
+}
 
-    let detail = Paragraph::new(body).block(Block::default().title(title).borders(Borders::ALL));
+/// Lay `rows` out in space-padded columns under a bold header.
+fn aligned(header: &[&str], rows: Vec<Vec<String>>) -> Vec<Line<'static>> {
+    const MAX_WIDTH: usize = 40;
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count()).min(MAX_WIDTH);
+        }
+    }
+
+    let fmt = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| {
+                let c: String = c.chars().take(*w).collect();
+                format!("{:<w$}", c, w = *w)
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
 
-    f.render_widget(detail, chunks[1]);
+    let mut out = vec![Line::styled(
+        fmt(header.iter().map(|h| h.to_string()).collect()),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    out.extend(rows.into_iter().map(|r| Line::from(fmt(r))));
+    out
 }
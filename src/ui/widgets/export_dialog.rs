@@ -0,0 +1,56 @@
+use crate::{app::state::ExportDraft, ui::layout::centered_rect};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// The export dialog shared by the Data and Runner grids. `shown_label` and
+/// `all_rows_label` name the two row choices on that screen.
+pub fn render(f: &mut Frame, area: Rect, d: &ExportDraft, shown_label: &str, all_rows_label: &str) {
+    let popup = centered_rect(60, 50, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default().title("Export").borders(Borders::ALL);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let fields = d.fields();
+    let mut constraints = vec![Constraint::Length(1); fields.len()];
+    constraints.push(Constraint::Min(1));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(inner);
+
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    for (row, &idx) in fields.iter().enumerate() {
+        let (label, value) = match idx {
+            ExportDraft::PATH_FIELD => ("File", format!("{}_", d.path)),
+            ExportDraft::FORMAT_FIELD => ("Format", format!("< {} >", d.options.format.label())),
+            ExportDraft::ROWS_FIELD if d.all_rows => ("Rows", format!("< {} >", all_rows_label)),
+            ExportDraft::ROWS_FIELD => ("Rows", format!("< {} >", shown_label)),
+            ExportDraft::HEADER_FIELD => ("Header", format!("< {} >", yes_no(d.options.header))),
+            ExportDraft::DELIMITER_FIELD => ("Delimiter", d.options.delimiter.to_string()),
+            ExportDraft::NULL_FIELD => ("NULL as", format!("{}_", d.options.null)),
+            ExportDraft::QUOTE_FIELD => ("Quoting", format!("< {} >", d.options.quote.label())),
+            ExportDraft::TABLE_FIELD => ("Table", format!("{}_", d.options.table)),
+            ExportDraft::STATEMENT_FIELD => {
+                ("Statement", format!("< {} >", d.options.statement.label()))
+            }
+            _ => ("Batch size", d.options.batch_size.to_string()),
+        };
+        let style = if d.field == idx {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        let line = format!("{:<11} {}", format!("{}:", label), value);
+        f.render_widget(Paragraph::new(line).style(style), rows[row]);
+    }
+
+    f.render_widget(
+        Paragraph::new("Tab/Shift+Tab: move • ←/→: change • Enter: export • Esc: cancel"),
+        rows[fields.len()],
+    );
+}
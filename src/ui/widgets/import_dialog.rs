@@ -0,0 +1,200 @@
+use crate::{
+    app::state::ImportDraft,
+    db::{
+        import::{ImportPreview, coerce},
+        types::CellValue,
+    },
+    ui::layout::centered_rect,
+};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row, Table,
+    },
+};
+
+/// The import dialog over the Data grid: file options, then the column
+/// mapping with a preview of the first rows once the file has been read.
+pub fn render(f: &mut Frame, area: Rect, d: &ImportDraft, table: &str) {
+    match d.preview.as_ref() {
+        Some(preview) => render_mapping(f, area, d, preview, table),
+        None => render_options(f, area, d, table),
+    }
+}
+
+fn render_options(f: &mut Frame, area: Rect, d: &ImportDraft, table: &str) {
+    let popup = centered_rect(60, 50, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .title(format!("Import into {}", table))
+        .borders(Borders::ALL);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let fields = d.fields();
+    let mut constraints = vec![Constraint::Length(1); fields.len()];
+    constraints.push(Constraint::Min(1));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(inner);
+
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    for (row, &idx) in fields.iter().enumerate() {
+        let (label, value) = match idx {
+            ImportDraft::PATH_FIELD => ("File", format!("{}_", d.path)),
+            ImportDraft::FORMAT_FIELD => ("Format", format!("< {} >", d.options.format.label())),
+            ImportDraft::HEADER_FIELD => ("Header", format!("< {} >", yes_no(d.options.header))),
+            ImportDraft::DELIMITER_FIELD => ("Delimiter", d.options.delimiter.to_string()),
+            ImportDraft::NULL_FIELD => ("NULL as", format!("{}_", d.options.null)),
+            ImportDraft::MODE_FIELD => ("Mode", format!("< {} >", d.options.mode.label())),
+            ImportDraft::BATCH_FIELD => ("Batch size", d.options.batch_size.to_string()),
+            _ => ("Dry run", format!("< {} >", yes_no(d.options.dry_run))),
+        };
+        let style = if d.field == idx {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        let line = format!("{:<11} {}", format!("{}:", label), value);
+        f.render_widget(Paragraph::new(line).style(style), rows[row]);
+    }
+
+    f.render_widget(
+        Paragraph::new("Tab/Shift+Tab: move • ←/→: change • Enter: read file • Esc: cancel"),
+        rows[fields.len()],
+    );
+}
+
+fn render_mapping(
+    f: &mut Frame,
+    area: Rect,
+    d: &ImportDraft,
+    preview: &ImportPreview,
+    table: &str,
+) {
+    let popup = centered_rect(80, 80, area);
+    f.render_widget(Clear, popup);
+    let title = if d.options.dry_run {
+        format!("Import into {} (dry run)", table)
+    } else {
+        format!("Import into {}", table)
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let columns = &preview.table.columns;
+    let list_height = (columns.len() as u16).clamp(1, 10);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(list_height),
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    // Table column ← file column.
+    let width = columns
+        .iter()
+        .map(|c| c.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = columns
+        .iter()
+        .zip(&d.mapping)
+        .map(|(col, src)| {
+            let source = match src {
+                _ if col.is_generated() => "(generated)".to_string(),
+                Some(i) => preview.columns[*i].clone(),
+                None => "(not imported)".to_string(),
+            };
+            let style = if src.is_some() {
+                Style::default()
+            } else {
+                Style::default().add_modifier(Modifier::DIM)
+            };
+            ListItem::new(format!(
+                "{:<width$}  {:<12} ← {}",
+                col.name,
+                col.data_type,
+                source,
+                width = width
+            ))
+            .style(style)
+        })
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut list_state = ListState::default().with_selected(Some(d.selected));
+    f.render_stateful_widget(list, parts[0], &mut list_state);
+
+    // First rows as they would be written; values that don't fit are flagged.
+    let mapped: Vec<(usize, usize)> = d
+        .mapping
+        .iter()
+        .enumerate()
+        .filter_map(|(i, src)| Some((i, (*src)?)))
+        .filter(|(i, _)| !columns[*i].is_generated())
+        .collect();
+    let bad = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    let mut first_error = None;
+    let rows: Vec<Row> = preview
+        .rows
+        .iter()
+        .map(|record| {
+            Row::new(mapped.iter().map(|&(i, src)| {
+                let raw = record.get(src).and_then(|v| v.as_deref());
+                match coerce(&columns[i], raw) {
+                    Ok(CellValue::Null) => {
+                        Cell::from("NULL").style(Style::default().add_modifier(Modifier::DIM))
+                    }
+                    Ok(v) => Cell::from(v.to_string()),
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                        Cell::from(raw.unwrap_or_default().to_string()).style(bad)
+                    }
+                }
+            }))
+        })
+        .collect();
+    let header = Row::new(
+        mapped
+            .iter()
+            .map(|&(i, _)| Cell::from(columns[i].name.clone())),
+    )
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = vec![Constraint::Fill(1); mapped.len().max(1)];
+    f.render_widget(
+        Paragraph::new(Line::styled(
+            format!("Preview (first {} rows)", preview.rows.len()),
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+        parts[1],
+    );
+    f.render_widget(
+        Table::new(rows, widths).header(header).column_spacing(1),
+        parts[2],
+    );
+    if let Some(e) = first_error {
+        f.render_widget(Paragraph::new(e.to_string()).style(bad), parts[3]);
+    }
+
+    match d.progress {
+        Some(p) => {
+            let gauge = Gauge::default()
+                .ratio(p.ratio.clamp(0.0, 1.0))
+                .label(format!("{} rows • Esc: cancel", p.rows));
+            f.render_widget(gauge, parts[4]);
+        }
+        None => f.render_widget(
+            Paragraph::new("↑/↓: column • ←/→: file column • Enter: import • Esc: back"),
+            parts[4],
+        ),
+    }
+}
@@ -0,0 +1,69 @@
+use crate::domain::{connection::LabelColor, filter::fuzzy_match};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::Paragraph,
+};
+
+/// The `/` filter input line shown above a filterable list.
+pub fn filter_line(filter: &str, filtering: bool) -> Paragraph<'static> {
+    let text = if filtering {
+        format!("/{}_", filter)
+    } else if filter.is_empty() {
+        "/ filter".to_string()
+    } else {
+        format!("/{}", filter)
+    };
+    let style = if filtering {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().add_modifier(Modifier::DIM)
+    };
+    Paragraph::new(text).style(style)
+}
+
+/// `text` split into spans with the characters matched by `filter` emphasised.
+pub fn highlighted(text: &str, filter: &str) -> Vec<Span<'static>> {
+    let positions = fuzzy_match(filter, text)
+        .map(|m| m.positions)
+        .unwrap_or_default();
+    if positions.is_empty() {
+        return vec![Span::raw(text.to_string())];
+    }
+
+    let hit = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_hit = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_hit = positions.contains(&i);
+        if is_hit != run_hit && !run.is_empty() {
+            let s = std::mem::take(&mut run);
+            spans.push(if run_hit {
+                Span::styled(s, hit)
+            } else {
+                Span::raw(s)
+            });
+        }
+        run_hit = is_hit;
+        run.push(c);
+    }
+    spans.push(if run_hit {
+        Span::styled(run, hit)
+    } else {
+        Span::raw(run)
+    });
+    spans
+}
+
+/// Terminal colour for a connection's label colour.
+pub fn label_color(c: LabelColor) -> Color {
+    match c {
+        LabelColor::Red => Color::Red,
+        LabelColor::Yellow => Color::Yellow,
+        LabelColor::Green => Color::Green,
+        LabelColor::Cyan => Color::Cyan,
+        LabelColor::Blue => Color::Blue,
+        LabelColor::Magenta => Color::Magenta,
+    }
+}
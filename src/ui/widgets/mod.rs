@@ -0,0 +1,5 @@
+pub mod export_dialog;
+pub mod import_dialog;
+pub mod list;
+pub mod popup;
+pub mod table_grid;
@@ -0,0 +1,79 @@
+use crate::{
+    app::state::RecordDetail,
+    db::types::{CellValue, ResultColumn},
+    ui::layout::centered_rect,
+};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+/// One row shown vertically as field/value pairs, over the Data and Runner
+/// grids. Values spanning several lines (pretty JSON, hex dumps, multi-line
+/// text) are indented below their field name.
+pub fn render_record(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    columns: &[ResultColumn],
+    row: &[CellValue],
+    detail: &RecordDetail,
+) {
+    let popup = centered_rect(80, 80, area);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let width = columns
+        .iter()
+        .map(|c| c.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(24);
+
+    let mut lines = Vec::new();
+    for (col, value) in columns.iter().zip(row) {
+        let name = Span::styled(
+            format!("{:<width$} ", col.name, width = width),
+            Style::default().add_modifier(Modifier::BOLD),
+        );
+        let kind = Span::styled(format!("{} ", col.type_name.to_lowercase()), dim);
+        let value_style = if value.is_null() {
+            dim
+        } else {
+            Style::default()
+        };
+        let mut values = value.detail_lines();
+        if values.len() == 1 {
+            let v = Span::styled(values.remove(0), value_style);
+            lines.push(Line::from(vec![name, v, Span::raw(" "), kind]));
+        } else {
+            lines.push(Line::from(vec![name, kind]));
+            lines.extend(
+                values
+                    .into_iter()
+                    .map(|v| Line::styled(format!("  {}", v), value_style)),
+            );
+        }
+    }
+
+    // Scroll by whole lines before wrapping, so the last one is always reachable.
+    let shown: Vec<Line> = lines.into_iter().skip(detail.scroll).collect();
+    f.render_widget(Paragraph::new(shown).wrap(Wrap { trim: false }), parts[0]);
+    f.render_widget(
+        Paragraph::new("↑/↓ PgUp/PgDn scroll • ←/→ previous/next row • Esc close").style(dim),
+        parts[1],
+    );
+}
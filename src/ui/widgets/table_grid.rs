@@ -0,0 +1,157 @@
+use std::collections::BTreeSet;
+
+use crate::db::{
+    edit::PendingChanges,
+    types::{CellValue, ResultColumn},
+};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+};
+
+const MAX_COL_WIDTH: usize = 40;
+const MIN_COL_WIDTH: usize = 4;
+
+/// Scrollable result grid: rows scroll vertically with the selection, columns scroll
+/// horizontally from `col_offset`, and an optional frozen column always stays on the left.
+/// With a `selected_col` the grid scrolls further right when needed to show that cell, and
+/// `changes` overlays staged edits on the rows and `marked` tints rows picked for export.
+pub struct TableGrid<'a> {
+    pub title: String,
+    pub columns: &'a [ResultColumn],
+    pub rows: &'a [Vec<CellValue>],
+    pub selected_row: usize,
+    pub col_offset: usize,
+    pub frozen: Option<usize>,
+    pub selected_col: Option<usize>,
+    pub changes: Option<&'a PendingChanges>,
+    pub marked: Option<&'a BTreeSet<usize>>,
+}
+
+impl TableGrid<'_> {
+    pub fn render(self, f: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(self.title.clone())
+            .borders(Borders::ALL);
+
+        // Frozen column first, then scrollable ones starting at the offset,
+        // moved right until the selected column fits.
+        let scrollable: Vec<usize> = (0..self.columns.len())
+            .filter(|&i| Some(i) != self.frozen)
+            .collect();
+        let available = area.width.saturating_sub(2) as usize;
+        let mut start = self.col_offset;
+        let mut visible = self.fit(&scrollable[start.min(scrollable.len())..], available);
+        if let Some(p) = self
+            .selected_col
+            .and_then(|c| scrollable.iter().position(|&i| i == c))
+        {
+            while start < p && !visible.iter().any(|&(i, _)| i == scrollable[p]) {
+                start += 1;
+                visible = self.fit(&scrollable[start..], available);
+            }
+        }
+
+        let header = Row::new(
+            visible
+                .iter()
+                .map(|&(i, _)| Cell::from(self.columns[i].name.clone())),
+        )
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let selected_row = self.selected_row.min(self.rows.len().saturating_sub(1));
+        let rows = self.rows.iter().enumerate().map(|(n, r)| {
+            let staged = |i| self.changes.and_then(|c| c.cell(n, i));
+            let row = Row::new(visible.iter().map(|&(i, w)| {
+                let v = staged(i).unwrap_or(r.get(i).unwrap_or(&CellValue::Null));
+                let cell = Cell::from(truncate(&v.to_string(), w));
+                let mut style = if staged(i).is_some() {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else if v.is_null() {
+                    Style::default().add_modifier(Modifier::DIM)
+                } else if Some(i) == self.frozen {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                if n == selected_row && Some(i) == self.selected_col {
+                    style = style.add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+                }
+                cell.style(style)
+            }));
+            match self.changes {
+                Some(c) if c.deleted.contains(&n) => row.style(
+                    Style::default()
+                        .fg(Color::Red)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                ),
+                Some(c) if c.is_new(n) => row.style(Style::default().fg(Color::Green)),
+                _ if self.marked.is_some_and(|m| m.contains(&n)) => {
+                    row.style(Style::default().fg(Color::Cyan))
+                }
+                _ => row,
+            }
+        });
+
+        let widths: Vec<Constraint> = visible
+            .iter()
+            .map(|&(_, w)| Constraint::Length(w as u16))
+            .collect();
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .column_spacing(1)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut ts = TableState::default();
+        if !self.rows.is_empty() {
+            ts.select(Some(self.selected_row.min(self.rows.len() - 1)));
+        }
+        f.render_stateful_widget(table, area, &mut ts);
+    }
+
+    /// The frozen column and as many of `scrollable` as fit in `available`;
+    /// always at least one.
+    fn fit(&self, scrollable: &[usize], available: usize) -> Vec<(usize, usize)> {
+        let mut visible = Vec::new();
+        let mut used = 0;
+        for &idx in self.frozen.iter().chain(scrollable) {
+            let w = self.column_width(idx);
+            if !visible.is_empty() && used + w > available {
+                break;
+            }
+            used += w + 1;
+            visible.push((idx, w));
+        }
+        visible
+    }
+
+    fn column_width(&self, idx: usize) -> usize {
+        let header = self.columns[idx].name.chars().count();
+        let widest = self
+            .rows
+            .iter()
+            .filter_map(|r| r.get(idx))
+            .map(|v| v.to_string().chars().count())
+            .max()
+            .unwrap_or(0);
+        header.max(widest).clamp(MIN_COL_WIDTH, MAX_COL_WIDTH)
+    }
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    // Multi-line values would break the row layout.
+    let flat = s.replace(['\n', '\r', '\t'], " ");
+    if flat.chars().count() <= width {
+        flat
+    } else {
+        let mut out: String = flat.chars().take(width.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+}
@@ -0,0 +1,642 @@
+//! Integration tests against a live Postgres. Skipped unless `LENSQL_TEST_PG_HOST`
+//! is set, e.g.:
+//!
+//! ```sh
+//! docker run --rm -e POSTGRES_PASSWORD=pw -p 5432:5432 postgres:16
+//! LENSQL_TEST_PG_HOST=127.0.0.1 LENSQL_TEST_PG_PASSWORD=pw cargo test --test postgres
+//! ```
+//!
+//! Setting `LENSQL_TEST_SSH_HOST` (plus optionally `_PORT`, `_USER` and `_KEY`)
+//! also connects through that SSH server, e.g. a local sshd, with the database
+//! host resolved on its side.
+
+use async_trait::async_trait;
+use lensql::{
+    db::{
+        connect::connect,
+        driver::{Driver, RowSink, RowSource},
+        edit::{PendingChanges, row_key},
+        export::{ExportFormat, ExportOptions, ExportWriter, SqlStatement, SqlTable},
+        import::{ImportMode, ImportPlan, ImportPreview},
+        introstect::ObjectKind,
+        tunnel::Tunnel,
+        types::{CellValue, QueryResult, ResultColumn},
+    },
+    domain::{
+        connection::{ConnectionOptions, DbKind, SshTunnel, SslMode, TlsSettings},
+        filter::{Condition, FilterOp, Join, RowFilter, SortKey},
+    },
+};
+use std::env;
+
+#[derive(Default)]
+struct Collect {
+    columns: usize,
+    rows: Vec<Vec<CellValue>>,
+}
+
+#[async_trait]
+impl RowSink for Collect {
+    async fn columns(&mut self, columns: &[ResultColumn]) -> Result<(), sqlx::Error> {
+        self.columns = columns.len();
+        Ok(())
+    }
+
+    async fn rows(&mut self, rows: Vec<Vec<CellValue>>) -> Result<(), sqlx::Error> {
+        self.rows.extend(rows);
+        Ok(())
+    }
+}
+
+/// Run `sql` with its rows collected into the result.
+async fn run(db: &dyn Driver, sql: &str) -> Result<QueryResult, sqlx::Error> {
+    let mut sink = Collect::default();
+    let mut res = db.execute(0, sql, &mut sink).await?;
+    res.rows = sink.rows.into();
+    Ok(res)
+}
+
+async fn driver() -> Option<Box<dyn Driver>> {
+    let host = env::var("LENSQL_TEST_PG_HOST").ok()?;
+    let var = |k: &str, d: &str| env::var(k).unwrap_or_else(|_| d.to_string());
+    let port = var("LENSQL_TEST_PG_PORT", "5432").parse().expect("port");
+    let driver = connect(
+        DbKind::Postgres,
+        &host,
+        port,
+        &var("LENSQL_TEST_PG_USER", "postgres"),
+        &var("LENSQL_TEST_PG_PASSWORD", ""),
+        &var("LENSQL_TEST_PG_DB", "postgres"),
+        &TlsSettings::default(),
+        &ConnectionOptions::default(),
+    )
+    .await
+    .expect("connect to test postgres");
+    Some(driver)
+}
+
+#[tokio::test]
+async fn browses_and_queries_a_table() {
+    let Some(db) = driver().await else {
+        eprintln!("LENSQL_TEST_PG_HOST not set; skipping");
+        return;
+    };
+
+    run(&*db, "DROP TABLE IF EXISTS lensql_it_people CASCADE")
+        .await
+        .unwrap();
+    run(
+        &*db,
+        "CREATE TABLE lensql_it_people (id serial PRIMARY KEY, name text NOT NULL, \
+         born date, score numeric(6,2), meta jsonb)",
+    )
+    .await
+    .unwrap();
+    let res = run(
+        &*db,
+        "INSERT INTO lensql_it_people (name, born, score, meta) VALUES \
+             ('ada', '1815-12-10', 99.50, '{\"a\":1}'), ('bob', NULL, -1.25, NULL)",
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.rows_affected, 2);
+
+    let details = db.describe_table("lensql_it_people").await.unwrap();
+    let cols: Vec<_> = details.columns.iter().map(|c| c.info()).collect();
+    let names: Vec<_> = cols.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["id", "name", "born", "score", "meta"]);
+    assert_eq!(cols[0].column_key.as_deref(), Some("PRI"));
+
+    run(
+        &*db,
+        "COMMENT ON COLUMN lensql_it_people.name IS 'display name'",
+    )
+    .await
+    .unwrap();
+    run(
+        &*db,
+        "CREATE UNIQUE INDEX lensql_it_people_name ON lensql_it_people (lower(name))",
+    )
+    .await
+    .unwrap();
+    let details = db.describe_table("lensql_it_people").await.unwrap();
+    assert_eq!(details.columns[0].extra, "");
+    assert!(
+        details.columns[0]
+            .default
+            .as_deref()
+            .unwrap()
+            .starts_with("nextval")
+    );
+    assert_eq!(details.columns[1].comment, "display name");
+    assert_eq!(details.columns[3].column_type, "numeric(6,2)");
+    assert!(details.indexes[0].primary);
+    assert_eq!(details.indexes[1].columns, ["(expr)"]);
+    assert!(
+        details
+            .ddl
+            .contains("CONSTRAINT \"lensql_it_people_pkey\" PRIMARY KEY (id)")
+    );
+    assert!(
+        details
+            .ddl
+            .contains("CREATE UNIQUE INDEX lensql_it_people_name")
+    );
+
+    let page = db
+        .fetch_page("lensql_it_people", Some("id"), None, 1, 10)
+        .await
+        .unwrap();
+    assert_eq!(page.rows.len(), 1);
+    assert_eq!(page.rows[0][1], CellValue::Text("bob".into()));
+    assert!(page.rows[0][2].is_null());
+
+    // Filter values are bound: ints against int4, dates cast to the column
+    // type, jsonb compared as text.
+    let filter = RowFilter {
+        conditions: vec![
+            Condition {
+                column: "born".into(),
+                op: FilterOp::Lt,
+                value: "1900-01-01".into(),
+                ..Default::default()
+            },
+            Condition {
+                join: Join::Or,
+                column: "meta".into(),
+                op: FilterOp::Like,
+                value: "%\"a\"%".into(),
+            },
+            Condition {
+                column: "id".into(),
+                op: FilterOp::Ge,
+                value: "1".into(),
+                ..Default::default()
+            },
+        ],
+        sort: vec![SortKey {
+            column: "score".into(),
+            descending: true,
+        }],
+    }
+    .compile(DbKind::Postgres, &cols)
+    .unwrap();
+    let page = db
+        .fetch_page("lensql_it_people", Some("id"), Some(&filter), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(page.rows.len(), 1);
+    assert_eq!(page.rows[0][1], CellValue::Text("ada".into()));
+
+    // Streaming takes the same filter, and whole queries too.
+    let mut sink = Collect::default();
+    let source = RowSource::Table {
+        table: "lensql_it_people".into(),
+        order_by: Some("id".into()),
+        filter: Some(filter),
+    };
+    assert_eq!(db.stream_rows(&source, &mut sink).await.unwrap(), 1);
+    assert_eq!(sink.columns, 5);
+    let mut sink = Collect::default();
+    let source = RowSource::Query {
+        sql: "SELECT name, score FROM lensql_it_people ORDER BY id".into(),
+    };
+    assert_eq!(db.stream_rows(&source, &mut sink).await.unwrap(), 2);
+    assert_eq!(sink.rows[1][1].to_string(), "-1.25");
+
+    // SQL upserts replay cleanly and leave generated columns to the engine.
+    run(&*db, "CREATE TABLE lensql_it_tags (id int PRIMARY KEY, label text, \
+         shout text GENERATED ALWAYS AS (upper(label)) STORED, raw bytea, ok boolean); \
+         INSERT INTO lensql_it_tags (id, label, raw, ok) VALUES (1, 'it''s \\ ok', '\\xcafe', true)",
+    )
+    .await
+    .unwrap();
+    let tags = db.describe_table("lensql_it_tags").await.unwrap();
+    let res = run(&*db, "SELECT * FROM lensql_it_tags").await.unwrap();
+    let options = ExportOptions {
+        format: ExportFormat::Sql,
+        statement: SqlStatement::Upsert,
+        ..Default::default()
+    };
+    let mut script = Vec::new();
+    let mut w = ExportWriter::new(&mut script, options, &res.columns)
+        .unwrap()
+        .for_table(SqlTable::new(DbKind::Postgres, &tags))
+        .unwrap();
+    w.write_row(&res.rows[0]).unwrap();
+    w.finish().unwrap();
+    run(
+        &*db,
+        "UPDATE lensql_it_tags SET label = 'x', raw = NULL, ok = false",
+    )
+    .await
+    .unwrap();
+    run(&*db, &String::from_utf8(script).unwrap())
+        .await
+        .unwrap();
+    let after = run(&*db, "SELECT * FROM lensql_it_tags").await.unwrap();
+    assert_eq!(after.rows, res.rows);
+
+    // Imports bind text and cast it to each column type; dry runs roll back.
+    let preview = ImportPreview {
+        kind: DbKind::Postgres,
+        table: tags.clone(),
+        columns: vec!["id".into(), "label".into(), "raw".into(), "ok".into()],
+        rows: vec![],
+    };
+    let plan = ImportPlan::new(&preview, &preview.auto_map(true), ImportMode::Upsert).unwrap();
+    let rows = vec![
+        plan.row(&[
+            Some("1".into()),
+            Some("one".into()),
+            None,
+            Some("no".into()),
+        ])
+        .unwrap(),
+        plan.row(&[
+            Some("2".into()),
+            Some("two".into()),
+            Some("0xBEEF".into()),
+            None,
+        ])
+        .unwrap(),
+    ];
+    db.write_rows(&plan.statement(), &rows, true).await.unwrap();
+    let count = run(&*db, "SELECT * FROM lensql_it_tags").await.unwrap();
+    assert_eq!(count.rows, res.rows);
+    db.write_rows(&plan.statement(), &rows, false)
+        .await
+        .unwrap();
+    let after = run(
+        &*db,
+        "SELECT shout, raw, ok FROM lensql_it_tags ORDER BY id",
+    )
+    .await
+    .unwrap();
+    assert_eq!(after.rows[0][0], CellValue::Text("ONE".into()));
+    assert_eq!(after.rows[0][2], CellValue::Bool(false));
+    assert_eq!(after.rows[1][1], CellValue::Bytes(vec![0xBE, 0xEF]));
+    assert!(after.rows[1][2].is_null());
+
+    // Grid edits find rows by key and cast each bound value to its column.
+    let columns: Vec<_> = tags.columns.iter().map(|c| c.info()).collect();
+    let page = db
+        .fetch_page("lensql_it_tags", Some("id"), None, 0, 10)
+        .await
+        .unwrap();
+    let mut rows = page.rows;
+    let mut changes = PendingChanges::default();
+    changes.stage(0, 3, CellValue::Bytes(vec![1, 2]), &rows[0][3]);
+    changes.stage(0, 4, CellValue::Bool(true), &rows[0][4]);
+    changes.toggle_delete(1);
+    changes.new_from = Some(rows.len());
+    rows.push(vec![CellValue::Null; columns.len()]);
+    changes.stage(2, 0, CellValue::Int(3), &CellValue::Null);
+    changes.stage(2, 1, CellValue::Text("three".into()), &CellValue::Null);
+    let statements = changes
+        .statements(
+            DbKind::Postgres,
+            "lensql_it_tags",
+            &columns,
+            &row_key(&columns, &tags.key_columns()),
+            &rows,
+        )
+        .unwrap();
+    db.apply_changes(&statements).await.unwrap();
+    let after = run(
+        &*db,
+        "SELECT id, shout, raw, ok FROM lensql_it_tags ORDER BY id",
+    )
+    .await
+    .unwrap();
+    assert_eq!(after.rows.len(), 2);
+    assert_eq!(after.rows[0][2], CellValue::Bytes(vec![1, 2]));
+    assert_eq!(after.rows[0][3], CellValue::Bool(true));
+    assert_eq!(after.rows[1][1], CellValue::Text("THREE".into()));
+    // Nothing matches the deleted row any more, so replaying fails as a whole.
+    let err = db.apply_changes(&statements).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::RowNotFound));
+    run(&*db, "DROP TABLE lensql_it_tags").await.unwrap();
+
+    // Without a primary key, rows are found by every column of a composite
+    // unique key, not just its first; a statement matching several rows
+    // undoes the whole save.
+    run(
+        &*db,
+        "DROP TABLE IF EXISTS lensql_it_pairs; \
+         CREATE TABLE lensql_it_pairs (a int NOT NULL, b int NOT NULL, note text, UNIQUE (a, b)); \
+         INSERT INTO lensql_it_pairs VALUES (1, 1, 'x'), (1, 2, 'y')",
+    )
+    .await
+    .unwrap();
+    let pairs = db.describe_table("lensql_it_pairs").await.unwrap();
+    assert_eq!(pairs.key_columns(), ["a", "b"]);
+    let columns: Vec<_> = pairs.columns.iter().map(|c| c.info()).collect();
+    let rows = db
+        .fetch_page("lensql_it_pairs", Some("b"), None, 0, 10)
+        .await
+        .unwrap()
+        .rows;
+    let mut changes = PendingChanges::default();
+    changes.stage(0, 2, CellValue::Text("z".into()), &rows[0][2]);
+    let save = |key: &[String]| {
+        changes
+            .statements(
+                DbKind::Postgres,
+                "lensql_it_pairs",
+                &columns,
+                &row_key(&columns, key),
+                &rows,
+            )
+            .unwrap()
+    };
+    let err = db
+        .apply_changes(&save(&["a".to_string()]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::RowNotFound));
+    db.apply_changes(&save(&pairs.key_columns())).await.unwrap();
+    let mut notes = run(&*db, "SELECT note FROM lensql_it_pairs ORDER BY b")
+        .await
+        .unwrap();
+    assert_eq!(
+        notes.rows.as_slice(),
+        [[CellValue::Text("z".into())], [CellValue::Text("y".into())]]
+    );
+    run(&*db, "DROP TABLE lensql_it_pairs").await.unwrap();
+
+    // Foreign keys are read from both ends.
+    run(
+        &*db,
+        "DROP TABLE IF EXISTS lensql_it_pets; \
+         CREATE TABLE lensql_it_pets (id int PRIMARY KEY, \
+         owner int REFERENCES lensql_it_people (id) ON DELETE CASCADE)",
+    )
+    .await
+    .unwrap();
+    let people = db.describe_table("lensql_it_people").await.unwrap();
+    let r = &people.referenced_by[0];
+    assert_eq!(r.table, "lensql_it_pets");
+    assert_eq!(r.key.columns, ["owner"]);
+    assert_eq!(r.key.ref_columns, ["id"]);
+    assert_eq!(r.key.on_delete, "CASCADE");
+    run(&*db, "DROP TABLE lensql_it_pets").await.unwrap();
+
+    let res = run(
+        &*db,
+        "SELECT name, score, meta FROM lensql_it_people WHERE id = 1",
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.rows.len(), 1);
+    assert_eq!(res.rows[0][1].to_string(), "99.50");
+    assert_eq!(res.rows[0][2].to_string(), "{\"a\": 1}");
+
+    run(
+        &*db,
+        "CREATE VIEW lensql_it_names AS SELECT name FROM lensql_it_people; \
+         CREATE FUNCTION lensql_it_touch() RETURNS trigger LANGUAGE plpgsql \
+           AS $$ BEGIN RETURN NEW; END $$; \
+         CREATE TRIGGER lensql_it_people_touch BEFORE UPDATE ON lensql_it_people \
+           FOR EACH ROW EXECUTE FUNCTION lensql_it_touch()",
+    )
+    .await
+    .unwrap();
+    let objects = db.list_objects().await.unwrap();
+    let find = |name: &str| objects.iter().find(|o| o.name == name).cloned().unwrap();
+    assert_eq!(find("lensql_it_people").kind, ObjectKind::Table);
+    assert_eq!(find("lensql_it_names").kind, ObjectKind::View);
+    let func = find("lensql_it_touch()");
+    assert_eq!(func.kind, ObjectKind::Function);
+    assert!(
+        db.object_definition(&func)
+            .await
+            .unwrap()
+            .contains("RETURN NEW")
+    );
+    let trigger = find("lensql_it_people_touch");
+    assert!(
+        db.object_definition(&trigger)
+            .await
+            .unwrap()
+            .starts_with("CREATE TRIGGER lensql_it_people_touch")
+    );
+    let view = db.describe_table("lensql_it_names").await.unwrap();
+    assert!(view.ddl.starts_with("CREATE VIEW \"lensql_it_names\" AS"));
+
+    run(
+        &*db,
+        "DROP TABLE lensql_it_people CASCADE; DROP FUNCTION lensql_it_touch()",
+    )
+    .await
+    .unwrap();
+    db.close().await;
+}
+
+#[tokio::test]
+async fn switches_schema_without_reconnecting() {
+    let Some(db) = driver().await else {
+        eprintln!("LENSQL_TEST_PG_HOST not set; skipping");
+        return;
+    };
+
+    run(
+        &*db,
+        "DROP SCHEMA IF EXISTS lensql_it_other CASCADE; CREATE SCHEMA lensql_it_other; \
+         CREATE TABLE lensql_it_other.gadgets (id int PRIMARY KEY, label text); \
+         INSERT INTO lensql_it_other.gadgets VALUES (1, 'cog')",
+    )
+    .await
+    .unwrap();
+    let home = db.current_schema().unwrap();
+    assert!(
+        db.list_schemas()
+            .await
+            .unwrap()
+            .contains(&"lensql_it_other".to_string())
+    );
+    assert!(db.use_schema("lensql_it_missing").await.is_err());
+    assert_eq!(db.current_schema().as_deref(), Some(home.as_str()));
+
+    db.use_schema("lensql_it_other").await.unwrap();
+    let objects = db.list_objects().await.unwrap();
+    let names: Vec<_> = objects.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, ["gadgets"]);
+    let details = db.describe_table("gadgets").await.unwrap();
+    assert_eq!(details.columns[0].column_key.as_deref(), Some("PRI"));
+    let page = db
+        .fetch_page("gadgets", Some("id"), None, 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(page.rows[0][1], CellValue::Text("cog".into()));
+    // Unqualified names in the runner resolve against the new schema too.
+    let res = run(&*db, "SELECT label FROM gadgets").await.unwrap();
+    assert_eq!(res.rows[0][0], CellValue::Text("cog".into()));
+
+    db.use_schema(&home).await.unwrap();
+    run(&*db, "DROP SCHEMA lensql_it_other CASCADE")
+        .await
+        .unwrap();
+    db.close().await;
+}
+
+#[tokio::test]
+async fn cancel_stops_only_the_statement_it_names() {
+    let Some(db) = driver().await else {
+        eprintln!("LENSQL_TEST_PG_HOST not set; skipping");
+        return;
+    };
+
+    let (mut slow_sink, mut quick_sink) = (Collect::default(), Collect::default());
+    let slow = db.execute(1, "SELECT pg_sleep(30)", &mut slow_sink);
+    let quick = async {
+        // The second statement starts and finishes while the first runs; its
+        // end must not forget the first, and cancelling the first must not
+        // touch it.
+        let res = db.execute(2, "SELECT 'second'", &mut quick_sink).await;
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        db.cancel(2).await.unwrap();
+        db.cancel(1).await.unwrap();
+        res
+    };
+    let (slow, quick) = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        tokio::join!(slow, quick)
+    })
+    .await
+    .expect("the first statement was not cancelled");
+
+    assert!(quick.is_ok());
+    assert_eq!(quick_sink.rows, [[CellValue::Text("second".into())]]);
+    let err = slow.unwrap_err().to_string();
+    assert!(err.contains("canceling statement"), "{}", err);
+    db.close().await;
+}
+
+#[tokio::test]
+async fn connects_through_an_ssh_tunnel() {
+    let (Ok(pg), Ok(bastion)) = (
+        env::var("LENSQL_TEST_PG_HOST"),
+        env::var("LENSQL_TEST_SSH_HOST"),
+    ) else {
+        eprintln!("LENSQL_TEST_PG_HOST or LENSQL_TEST_SSH_HOST not set; skipping");
+        return;
+    };
+    let var = |k: &str, d: &str| env::var(k).unwrap_or_else(|_| d.to_string());
+    let ssh = SshTunnel {
+        host: bastion,
+        port: var("LENSQL_TEST_SSH_PORT", "22").parse().expect("ssh port"),
+        user: var("LENSQL_TEST_SSH_USER", ""),
+        key_file: env::var("LENSQL_TEST_SSH_KEY").ok(),
+        skip_host_key_check: true,
+    };
+    let port = var("LENSQL_TEST_PG_PORT", "5432").parse().expect("port");
+    let tunnel = Tunnel::open(&ssh, "", &pg, port)
+        .await
+        .expect("open tunnel");
+    let db = connect(
+        DbKind::Postgres,
+        "127.0.0.1",
+        tunnel.port(),
+        &var("LENSQL_TEST_PG_USER", "postgres"),
+        &var("LENSQL_TEST_PG_PASSWORD", ""),
+        &var("LENSQL_TEST_PG_DB", "postgres"),
+        &TlsSettings::default(),
+        &ConnectionOptions::default(),
+    )
+    .await
+    .expect("connect through the tunnel");
+
+    let result = run(&*db, "SELECT 40 + 2").await.unwrap();
+    assert_eq!(result.rows[0][0], CellValue::Int(42));
+
+    db.close().await;
+    tunnel.close().await;
+}
+
+#[tokio::test]
+async fn ssl_mode_decides_whether_the_session_is_encrypted() {
+    let Ok(host) = env::var("LENSQL_TEST_PG_HOST") else {
+        eprintln!("LENSQL_TEST_PG_HOST not set; skipping");
+        return;
+    };
+    let var = |k: &str, d: &str| env::var(k).unwrap_or_else(|_| d.to_string());
+    let port = var("LENSQL_TEST_PG_PORT", "5432").parse().expect("port");
+    let open = |mode| {
+        let tls = TlsSettings {
+            mode,
+            ..TlsSettings::default()
+        };
+        let (host, user) = (host.clone(), var("LENSQL_TEST_PG_USER", "postgres"));
+        let (password, db) = (
+            var("LENSQL_TEST_PG_PASSWORD", ""),
+            var("LENSQL_TEST_PG_DB", "postgres"),
+        );
+        let options = ConnectionOptions::default();
+        async move {
+            connect(
+                DbKind::Postgres,
+                &host,
+                port,
+                &user,
+                &password,
+                &db,
+                &tls,
+                &options,
+            )
+            .await
+        }
+    };
+    let encrypted = "SELECT ssl::text FROM pg_stat_ssl WHERE pid = pg_backend_pid()";
+
+    let plain = open(SslMode::Disabled).await.unwrap();
+    let result = run(&*plain, encrypted).await.unwrap();
+    assert_eq!(result.rows[0][0], CellValue::Text("false".into()));
+    let server_ssl = run(&*plain, "SHOW ssl").await.unwrap().rows[0][0].clone();
+    plain.close().await;
+
+    match open(SslMode::Required).await {
+        Ok(db) => {
+            assert_eq!(server_ssl, CellValue::Text("on".into()));
+            let result = run(&*db, encrypted).await.unwrap();
+            assert_eq!(result.rows[0][0], CellValue::Text("true".into()));
+            db.close().await;
+        }
+        Err(e) => assert_eq!(server_ssl, CellValue::Text("off".into()), "{}", e),
+    }
+}
+
+#[tokio::test]
+async fn options_set_the_session_time_zone_and_read_only() {
+    let Ok(host) = env::var("LENSQL_TEST_PG_HOST") else {
+        eprintln!("LENSQL_TEST_PG_HOST not set; skipping");
+        return;
+    };
+    let var = |k: &str, d: &str| env::var(k).unwrap_or_else(|_| d.to_string());
+    let options = ConnectionOptions {
+        timezone: Some("Pacific/Chatham".into()),
+        connect_timeout_secs: Some(5),
+        pool_size: Some(1),
+        read_only: true,
+        ..ConnectionOptions::default()
+    };
+    let db = connect(
+        DbKind::Postgres,
+        &host,
+        var("LENSQL_TEST_PG_PORT", "5432").parse().expect("port"),
+        &var("LENSQL_TEST_PG_USER", "postgres"),
+        &var("LENSQL_TEST_PG_PASSWORD", ""),
+        &var("LENSQL_TEST_PG_DB", "postgres"),
+        &TlsSettings::default(),
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let result = run(&*db, "SHOW TimeZone").await.unwrap();
+    assert_eq!(result.rows[0][0], CellValue::Text("Pacific/Chatham".into()));
+    let err = run(&*db, "CREATE TABLE lensql_ro (id int)")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("read-only transaction"), "{}", err);
+    db.close().await;
+}
@@ -0,0 +1,892 @@
+//! End-to-end run of the Schema, Data and Runner flows against a throwaway
+//! SQLite file: actions go through the reducer, DB commands through the real
+//! worker, and the resulting events back through the reducer.
+
+use lensql::{
+    app::{
+        action::Action,
+        command::Command,
+        event::{DbEvent, Event},
+        reducer::{reduce_action, reduce_event},
+        screen::Screen,
+        state::{AppState, ConnectionItem, SchemaRow, SessionId},
+    },
+    db::{import::ImportMode, introstect::ObjectKind, types::CellValue, worker},
+    domain::{
+        connection::DbKind,
+        filter::{Condition, FilterOp, Join, SortKey},
+    },
+};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::{path::PathBuf, time::Duration};
+use tokio::sync::mpsc;
+
+struct Harness {
+    state: AppState,
+    db_tx: mpsc::Sender<(SessionId, lensql::app::command::DbCommand)>,
+    evt_rx: mpsc::Receiver<Event>,
+}
+
+impl Harness {
+    fn new(path: &str) -> Self {
+        let (db_tx, db_rx) = mpsc::channel(16);
+        let (evt_tx, evt_rx) = mpsc::channel(16);
+        tokio::spawn(worker::run(db_rx, evt_tx));
+
+        let mut item = ConnectionItem::new("local", "", 0, "", "", path);
+        item.kind = DbKind::Sqlite;
+        let mut state = AppState::new();
+        state.screen = Screen::Connections;
+        state.connections.items = vec![item];
+
+        Self {
+            state,
+            db_tx,
+            evt_rx,
+        }
+    }
+
+    /// Reduce `action`, then run every resulting DB command (and the ones its
+    /// events trigger) to completion. Each DB command answers with one event,
+    /// after any progress reports.
+    async fn dispatch(&mut self, action: Action) {
+        let mut pending = reduce_action(&mut self.state, action);
+        while !pending.is_empty() {
+            let mut next = Vec::new();
+            for cmd in pending {
+                if let Command::Db(session, cmd) = cmd {
+                    self.db_tx.send((session, cmd)).await.unwrap();
+                    loop {
+                        let evt = self.evt_rx.recv().await.unwrap();
+                        let progress = matches!(
+                            evt,
+                            Event::Db(
+                                _,
+                                DbEvent::ExportProgress { .. }
+                                    | DbEvent::ImportProgress { .. }
+                                    | DbEvent::QueryRows { .. }
+                            )
+                        );
+                        next.extend(reduce_event(&mut self.state, evt));
+                        if !progress {
+                            break;
+                        }
+                    }
+                }
+            }
+            pending = next;
+        }
+    }
+
+    /// Reduce `action` and send its DB commands without waiting for answers.
+    async fn send(&mut self, action: Action) {
+        for cmd in reduce_action(&mut self.state, action) {
+            if let Command::Db(session, cmd) = cmd {
+                self.db_tx.send((session, cmd)).await.unwrap();
+            }
+        }
+    }
+
+    /// Reduce events until the worker goes quiet, as when a streamed result
+    /// waits for the grid to scroll on.
+    async fn settle(&mut self) {
+        let quiet = Duration::from_millis(300);
+        while let Ok(Some(evt)) = tokio::time::timeout(quiet, self.evt_rx.recv()).await {
+            let _ = reduce_event(&mut self.state, evt);
+        }
+    }
+}
+
+async fn seed() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lensql-e2e-{}.db", ulid::Ulid::new()));
+    let pool = SqlitePoolOptions::new()
+        .connect_with(
+            SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap();
+    sqlx::raw_sql(
+        "CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE);
+         CREATE TABLE books (id INTEGER PRIMARY KEY,
+                             author_id INTEGER REFERENCES authors(id) ON DELETE CASCADE,
+                             title TEXT, price REAL DEFAULT 0, cover BLOB);
+         CREATE INDEX books_by_author ON books (author_id, title);
+         CREATE VIEW cheap_books AS SELECT title FROM books WHERE price < 10;
+         CREATE TRIGGER authors_trim AFTER INSERT ON authors
+             BEGIN UPDATE authors SET name = trim(name) WHERE id = NEW.id; END;
+         INSERT INTO authors (name) VALUES ('Le Guin'), ('Pratchett');
+         INSERT INTO books (author_id, title, price, cover) VALUES
+             (1, 'The Dispossessed', 9.5, x'CAFE'), (2, 'Mort', NULL, NULL);",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+    path
+}
+
+#[tokio::test]
+async fn schema_data_and_runner_against_sqlite() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+
+    // Connect lands on Schema with tables and the first table's columns loaded.
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(h.state.screen, Screen::Schema);
+    assert!(h.state.status.connection_label.contains("local"));
+    let tables: Vec<_> = h
+        .state
+        .schema
+        .objects
+        .iter()
+        .filter(|o| o.kind == ObjectKind::Table)
+        .map(|o| o.name.as_str())
+        .collect();
+    assert_eq!(tables, ["authors", "books"]);
+    assert_eq!(h.state.schema.selected_relation(), Some("authors"));
+    let cols: Vec<_> = h
+        .state
+        .schema
+        .columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.column_key.as_deref()))
+        .collect();
+    assert_eq!(cols, [("id", Some("PRI")), ("name", Some("UNI"))]);
+
+    // Moving to `books` describes it: indexes, foreign keys and DDL.
+    h.dispatch(Action::Down).await;
+    assert_eq!(h.state.schema.columns_table.as_deref(), Some("books"));
+    let details = h.state.schema.details.as_ref().unwrap();
+    assert_eq!(details.columns[1].column_key.as_deref(), Some("MUL"));
+    assert_eq!(details.columns[3].default.as_deref(), Some("0"));
+    assert_eq!(details.indexes[0].name, "books_by_author");
+    assert_eq!(details.indexes[0].columns, ["author_id", "title"]);
+    let fk = &details.foreign_keys[0];
+    assert_eq!(
+        (fk.ref_table.as_str(), fk.on_delete.as_str()),
+        ("authors", "CASCADE")
+    );
+    assert!(details.ddl.starts_with("CREATE TABLE books"));
+    assert!(details.ddl.contains("CREATE INDEX books_by_author"));
+
+    // Data: open the rows of `books`.
+    h.dispatch(Action::OpenTableData).await;
+    assert_eq!(h.state.screen, Screen::Data);
+    assert_eq!(h.state.data.pk_column.as_deref(), Some("id"));
+    assert_eq!(h.state.data.total_estimate, Some(2));
+    assert_eq!(h.state.data.rows.len(), 2);
+    assert_eq!(
+        h.state.data.rows[0],
+        [
+            CellValue::Int(1),
+            CellValue::Int(1),
+            CellValue::Text("The Dispossessed".into()),
+            CellValue::Float(9.5),
+            CellValue::Bytes(vec![0xCA, 0xFE]),
+        ]
+    );
+    assert!(h.state.data.rows[1][3].is_null());
+
+    // Runner: a write, then a read of the statement under the cursor.
+    h.dispatch(Action::GoRunner).await;
+    h.state
+        .runner
+        .editor
+        .set_text("UPDATE books SET price = 12 WHERE id = 2");
+    h.dispatch(Action::RunQuery).await;
+    assert!(!h.state.runner.running);
+    assert_eq!(h.state.runner.result.as_ref().unwrap().rows_affected, 1);
+
+    h.state.runner.editor.set_text(
+        "SELECT a.name, b.price FROM books b JOIN authors a ON a.id = b.author_id ORDER BY b.id",
+    );
+    h.dispatch(Action::RunQuery).await;
+    let result = h.state.runner.result.as_ref().unwrap();
+    assert_eq!(result.columns.len(), 2);
+    assert_eq!(result.rows[1][0], CellValue::Text("Pratchett".into()));
+    assert_eq!(result.rows[1][1], CellValue::Float(12.0)); // REAL affinity
+
+    h.state.runner.editor.set_text("SELECT * FROM nope");
+    h.dispatch(Action::RunQuery).await;
+    assert!(h.state.runner.error.as_deref().unwrap().contains("nope"));
+
+    h.dispatch(Action::Disconnect).await;
+    assert!(h.state.connections.active.is_none());
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn tree_groups_views_and_triggers_with_definitions() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+
+    let labels: Vec<String> = h
+        .state
+        .schema
+        .rows()
+        .iter()
+        .map(|r| match r {
+            SchemaRow::Group { kind, count, .. } => format!("{} ({})", kind.group_title(), count),
+            SchemaRow::Object(o) => o.name.clone(),
+        })
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "Tables (2)",
+            "authors",
+            "books",
+            "Views (1)",
+            "cheap_books",
+            "Triggers (1)",
+            "authors_trim",
+        ]
+    );
+
+    // A view is a relation: it gets columns and its CREATE VIEW as DDL.
+    for _ in 0..3 {
+        h.dispatch(Action::Down).await;
+    }
+    assert_eq!(h.state.schema.selected_relation(), Some("cheap_books"));
+    let details = h.state.schema.details.as_ref().unwrap();
+    assert_eq!(details.columns[0].name, "title");
+    assert!(details.ddl.starts_with("CREATE VIEW cheap_books"));
+
+    // A trigger only has a definition.
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::Down).await;
+    let (object, definition) = h.state.schema.definition.as_ref().unwrap();
+    assert_eq!(object.name, "authors_trim");
+    assert!(definition.starts_with("CREATE TRIGGER authors_trim"));
+
+    // Enter on a group header folds it.
+    h.dispatch(Action::Up).await;
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(h.state.schema.rows().len(), 6);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn missing_file_is_an_error_not_a_new_database() {
+    let path = std::env::temp_dir().join(format!("lensql-missing-{}.db", ulid::Ulid::new()));
+    let mut h = Harness::new(path.to_str().unwrap());
+
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.connections.active.is_none());
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn database_picker_lists_main_and_rejects_unknown_schema() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    let label = h.state.status.connection_label.clone();
+
+    h.dispatch(Action::OpenDatabasePicker).await;
+    let picker = h.state.schema.picker.as_ref().unwrap();
+    assert_eq!(picker.items, ["main"]);
+    assert_eq!(picker.current.as_deref(), Some("main"));
+
+    // A name that isn't attached is refused and the connection stays put.
+    let picker = h.state.schema.picker.as_mut().unwrap();
+    picker.items.push("nope".into());
+    picker.selected = 1;
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.status.message.contains("nope"));
+    assert_eq!(h.state.status.connection_label, label);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn data_filter_binds_values_and_sorts() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::OpenTableData).await;
+
+    // price < 10 OR title LIKE 'M%', highest id first.
+    h.dispatch(Action::OpenFilterBuilder).await;
+    let draft = h.state.data.filter_draft.as_mut().unwrap();
+    draft.filter.conditions[0] = Condition {
+        column: "price".into(),
+        op: FilterOp::Lt,
+        value: "10".into(),
+        ..Default::default()
+    };
+    h.dispatch(Action::AddFilterCondition).await;
+    let draft = h.state.data.filter_draft.as_mut().unwrap();
+    draft.filter.conditions[1] = Condition {
+        join: Join::Or,
+        column: "title".into(),
+        op: FilterOp::Like,
+        value: "M%".into(),
+    };
+    draft.filter.sort.push(SortKey {
+        column: "id".into(),
+        descending: true,
+    });
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.data.filter_draft.is_none());
+    let titles: Vec<_> = h.state.data.rows.iter().map(|r| r[2].clone()).collect();
+    assert_eq!(
+        titles,
+        [
+            CellValue::Text("Mort".into()),
+            CellValue::Text("The Dispossessed".into()),
+        ]
+    );
+
+    // A quote in the value is data, not SQL.
+    h.dispatch(Action::OpenFilterBuilder).await;
+    let draft = h.state.data.filter_draft.as_mut().unwrap();
+    draft.filter.conditions = vec![Condition {
+        column: "title".into(),
+        value: "x' OR '1'='1".into(),
+        ..Default::default()
+    }];
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.data.rows.is_empty());
+
+    h.dispatch(Action::ClearRowFilter).await;
+    assert_eq!(h.state.data.rows.len(), 2);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn exports_streamed_table_and_runner_rows() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::OpenTableData).await;
+
+    let dir = std::env::temp_dir();
+    let csv = dir.join(format!("lensql-export-{}.csv", ulid::Ulid::new()));
+    h.dispatch(Action::OpenExport).await;
+    let draft = h.state.data.export.as_mut().unwrap();
+    draft.path = csv.to_str().unwrap().to_string();
+    draft.all_rows = true;
+    draft.options.null = "NULL".into();
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.status.message.starts_with("Exported 2 rows"));
+    assert_eq!(
+        std::fs::read_to_string(&csv).unwrap(),
+        "id,author_id,title,price,cover\n\
+         1,1,The Dispossessed,9.5,0xCAFE\n\
+         2,2,Mort,NULL,NULL\n"
+    );
+
+    // SQL upserts replay against the table and restore the exported rows.
+    let sql = dir.join(format!("lensql-export-{}.sql", ulid::Ulid::new()));
+    h.dispatch(Action::OpenExport).await;
+    let draft = h.state.data.export.as_mut().unwrap();
+    draft.set_format(lensql::db::export::ExportFormat::Sql);
+    draft.path = sql.to_str().unwrap().to_string();
+    draft.all_rows = true;
+    draft.options.statement = lensql::db::export::SqlStatement::Upsert;
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.status.message.starts_with("Exported 2 rows"));
+    let script = std::fs::read_to_string(&sql).unwrap();
+    assert!(script.contains("(1, 1, 'The Dispossessed', 9.5, X'CAFE')"));
+    assert!(script.contains("ON CONFLICT (\"id\") DO UPDATE SET"));
+    let pool = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().filename(&path))
+        .await
+        .unwrap();
+    sqlx::raw_sql("UPDATE books SET title = 'changed'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::raw_sql(&script).execute(&pool).await.unwrap();
+    let titles: Vec<String> = sqlx::query_scalar("SELECT title FROM books ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(titles, ["The Dispossessed", "Mort"]);
+    pool.close().await;
+    let _ = std::fs::remove_file(sql);
+
+    // Runner: a full export runs the statement again and streams it.
+    h.dispatch(Action::GoRunner).await;
+    h.state
+        .runner
+        .editor
+        .set_text("SELECT name FROM authors ORDER BY id");
+    h.dispatch(Action::RunQuery).await;
+    let ndjson = dir.join(format!("lensql-export-{}.ndjson", ulid::Ulid::new()));
+    h.dispatch(Action::FocusResults).await;
+    h.dispatch(Action::OpenExport).await;
+    let draft = h.state.runner.export.as_mut().unwrap();
+    draft.path = ndjson.to_str().unwrap().to_string();
+    draft.options.format = lensql::db::export::ExportFormat::Ndjson;
+    draft.all_rows = true;
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(
+        std::fs::read_to_string(&ndjson).unwrap(),
+        "{\"name\":\"Le Guin\"}\n{\"name\":\"Pratchett\"}\n"
+    );
+
+    // A failed export reports the error and leaves the earlier file alone.
+    let exported = std::fs::read_to_string(&ndjson).unwrap();
+    let partial = dir.join(format!(
+        "{}.part",
+        ndjson.file_name().unwrap().to_str().unwrap()
+    ));
+    h.state.runner.result.as_mut().unwrap().sql = "SELECT * FROM nope".into();
+    h.dispatch(Action::OpenExport).await;
+    let draft = h.state.runner.export.as_mut().unwrap();
+    draft.path = ndjson.to_str().unwrap().to_string();
+    draft.all_rows = true;
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.status.message.contains("Export failed"));
+    assert_eq!(std::fs::read_to_string(&ndjson).unwrap(), exported);
+    assert!(!partial.exists());
+
+    // A long export runs beside other commands until Ctrl+X stops it.
+    h.state.runner.result.as_mut().unwrap().sql =
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 300000000) \
+         SELECT i FROM n"
+            .into();
+    h.dispatch(Action::OpenExport).await;
+    let draft = h.state.runner.export.as_mut().unwrap();
+    draft.path = ndjson.to_str().unwrap().to_string();
+    draft.all_rows = true;
+    h.send(Action::Confirm).await;
+    h.state.runner.editor.set_text("SELECT count(*) FROM books");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(
+        h.state.runner.result.as_ref().unwrap().rows[0][0],
+        CellValue::Int(2)
+    );
+    assert!(h.state.runner.transfer.is_some());
+    h.send(Action::CancelQuery).await;
+    tokio::time::timeout(Duration::from_secs(5), h.settle())
+        .await
+        .expect("the export was not cancelled");
+    assert_eq!(h.state.status.message, "Export cancelled");
+    assert!(h.state.runner.transfer.is_none());
+    assert_eq!(std::fs::read_to_string(&ndjson).unwrap(), exported);
+    assert!(!partial.exists());
+
+    let _ = std::fs::remove_file(ndjson);
+    let _ = std::fs::remove_file(csv);
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn imports_csv_with_dry_run_upsert_and_bad_values() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::OpenTableData).await;
+
+    let dir = std::env::temp_dir();
+    let csv = dir.join(format!("lensql-import-{}.csv", ulid::Ulid::new()));
+    std::fs::write(
+        &csv,
+        "ID,Title,price,cover\n2,Mort (revised),7.25,\n3,\"Small Gods, 1992\",12,0xBEEF\n",
+    )
+    .unwrap();
+    let import = |h: &mut Harness, file: &std::path::Path| {
+        h.state.data.import = None;
+        let _ = reduce_action(&mut h.state, Action::OpenImport);
+        let draft = h.state.data.import.as_mut().unwrap();
+        draft.path = file.to_str().unwrap().to_string();
+        draft.options.batch_size = 1;
+    };
+
+    // A dry run writes every batch and rolls it back.
+    import(&mut h, &csv);
+    let draft = h.state.data.import.as_mut().unwrap();
+    draft.options.dry_run = true;
+    draft.options.mode = ImportMode::Upsert;
+    h.dispatch(Action::Confirm).await;
+    let draft = h.state.data.import.as_ref().unwrap();
+    assert_eq!(draft.preview.as_ref().unwrap().rows.len(), 2);
+    assert_eq!(draft.mapping, [Some(0), None, Some(1), Some(2), Some(3)]);
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(
+        h.state.status.message,
+        "Dry run: 2 rows would be imported; nothing was written"
+    );
+    assert_eq!(h.state.data.rows.len(), 2);
+
+    // An upsert updates `Mort` and adds the new book, then reloads the page.
+    import(&mut h, &csv);
+    h.state.data.import.as_mut().unwrap().options.mode = ImportMode::Upsert;
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.data.import.is_none());
+    let rows = &h.state.data.rows;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1][2], CellValue::Text("Mort (revised)".into()));
+    assert_eq!(rows[1][3], CellValue::Float(7.25));
+    assert!(rows[1][4].is_null());
+    assert_eq!(rows[2][2], CellValue::Text("Small Gods, 1992".into()));
+    assert_eq!(rows[2][4], CellValue::Bytes(vec![0xBE, 0xEF]));
+
+    // A value that doesn't fit stops the import at its line; the dialog stays open.
+    std::fs::write(&csv, "id,price\n4,1\n5,cheap\n").unwrap();
+    import(&mut h, &csv);
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(
+        h.state.status.message,
+        "Import failed after 1 rows were written: \
+         line 3: price expects a number, got 'cheap'"
+    );
+    assert!(h.state.data.import.as_ref().unwrap().progress.is_none());
+
+    let _ = std::fs::remove_file(csv);
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn edits_cells_inserts_and_deletes_rows_in_one_transaction() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::OpenTableData).await;
+    let edit = |h: &mut Harness, value: &str| {
+        let _ = reduce_action(&mut h.state, Action::Confirm);
+        h.state.data.cell_editor.as_mut().unwrap().value = Some(value.to_string());
+        let _ = reduce_action(&mut h.state, Action::Confirm);
+    };
+
+    // Retitle the first book, delete `Mort` and add a new one.
+    h.dispatch(Action::Right).await;
+    h.dispatch(Action::Right).await;
+    edit(&mut h, "Anarres");
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::DeleteRow).await;
+    h.dispatch(Action::InsertRow).await;
+    edit(&mut h, "Small Gods");
+    h.dispatch(Action::ReviewChanges).await;
+    assert_eq!(h.state.data.review.as_ref().unwrap().statements.len(), 3);
+    h.dispatch(Action::Confirm).await;
+    assert!(h.state.data.review.is_none());
+    let titles: Vec<_> = h.state.data.rows.iter().map(|r| r[2].clone()).collect();
+    assert_eq!(
+        titles,
+        [
+            CellValue::Text("Anarres".into()),
+            CellValue::Text("Small Gods".into())
+        ]
+    );
+    // Without AUTOINCREMENT SQLite hands out the deleted rowid again.
+    assert_eq!(h.state.data.rows[1][0], CellValue::Int(2));
+
+    // A row deleted behind our back fails the save, and the update staged
+    // alongside it is rolled back.
+    h.dispatch(Action::Down).await;
+    edit(&mut h, "Reaper Man");
+    h.dispatch(Action::Up).await;
+    edit(&mut h, "The Left Hand of Darkness");
+    let pool = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().filename(&path))
+        .await
+        .unwrap();
+    sqlx::raw_sql("DELETE FROM books WHERE id = 2")
+        .execute(&pool)
+        .await
+        .unwrap();
+    h.dispatch(Action::ReviewChanges).await;
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(
+        h.state.status.message,
+        "Save failed: a key matched no row or several; nothing was saved"
+    );
+    assert_eq!(h.state.data.changes.count(), 2);
+    let titles: Vec<String> = sqlx::query_scalar("SELECT title FROM books ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(titles, ["Anarres"]);
+
+    pool.close().await;
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn follows_foreign_keys_both_ways_and_back() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::Down).await;
+    h.dispatch(Action::OpenTableData).await;
+
+    // books.author_id → the author's row.
+    h.dispatch(Action::Right).await;
+    h.dispatch(Action::FollowReference).await;
+    assert_eq!(h.state.data.breadcrumb(), "books › authors");
+    assert_eq!(h.state.data.pk_column.as_deref(), Some("id"));
+    assert_eq!(h.state.data.rows.len(), 1);
+    assert_eq!(h.state.data.rows[0][1], CellValue::Text("Le Guin".into()));
+
+    // authors ← books: every book by that author.
+    h.dispatch(Action::OpenReferencingTables).await;
+    let refs = &h.state.data.referenced_by;
+    assert_eq!(
+        (refs[0].table.as_str(), refs[0].key.ref_columns[0].as_str()),
+        ("books", "id")
+    );
+    h.dispatch(Action::Confirm).await;
+    assert_eq!(h.state.data.breadcrumb(), "books › authors › books");
+    assert_eq!(h.state.data.rows.len(), 1);
+    assert_eq!(
+        h.state.data.rows[0][2],
+        CellValue::Text("The Dispossessed".into())
+    );
+
+    // Esc walks the trail back to the unfiltered page, cursor and all.
+    h.dispatch(Action::Back).await;
+    h.dispatch(Action::Back).await;
+    assert_eq!(h.state.data.breadcrumb(), "books");
+    assert!(h.state.data.filter.is_empty());
+    assert_eq!((h.state.data.rows.len(), h.state.data.selected_col), (2, 1));
+    h.dispatch(Action::Back).await;
+    assert_eq!(h.state.screen, Screen::Schema);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn statement_timeout_fails_a_long_query() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.state.connections.items[0].statement_timeout_secs = Some(1);
+    h.dispatch(Action::Confirm).await;
+
+    h.dispatch(Action::GoRunner).await;
+    h.state.runner.editor.set_text(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 300000000) \
+         SELECT count(*) FROM n",
+    );
+    h.dispatch(Action::RunQuery).await;
+    assert!(!h.state.runner.running);
+    assert!(h.state.runner.task.is_none());
+    assert_eq!(
+        h.state.runner.error.as_deref(),
+        Some("Query timed out after 1s")
+    );
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn cancel_interrupts_a_long_statement() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::GoRunner).await;
+    h.state.runner.editor.set_text(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 300000000) \
+         SELECT count(*) FROM n",
+    );
+
+    // No rows come back before the count is done, so only an interrupt ends it.
+    h.send(Action::RunQuery).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    h.send(Action::CancelQuery).await;
+    tokio::time::timeout(Duration::from_secs(5), h.settle())
+        .await
+        .expect("the statement was not interrupted");
+    assert!(!h.state.runner.running && h.state.runner.task.is_none());
+    assert_eq!(h.state.runner.error.as_deref(), Some("Query cancelled"));
+
+    // The interrupted session is usable again.
+    h.state
+        .runner
+        .editor
+        .set_text("SELECT count(*) FROM authors");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(
+        h.state.runner.result.as_ref().unwrap().rows[0][0],
+        CellValue::Int(2)
+    );
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn streams_rows_as_the_grid_scrolls_and_stops_on_request() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.dispatch(Action::Confirm).await;
+    h.dispatch(Action::GoRunner).await;
+    h.dispatch(Action::FocusResults).await;
+    h.state.runner.editor.set_text(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 10000) \
+         SELECT i FROM n",
+    );
+
+    // 2000 rows are read ahead of the cursor, then the stream waits.
+    h.send(Action::RunQuery).await;
+    h.settle().await;
+    let fetched = |h: &Harness| h.state.runner.result.as_ref().unwrap().rows.fetched();
+    assert_eq!(fetched(&h), 2000);
+    assert!(h.state.runner.task.is_some());
+
+    h.state.runner.selected_row = 1500;
+    h.send(Action::Down).await;
+    h.settle().await;
+    assert_eq!(fetched(&h), 4000);
+    assert_eq!(
+        h.state.runner.result.as_ref().unwrap().rows[1501][0],
+        CellValue::Int(1502)
+    );
+
+    // Stopping keeps what was read and frees the connection.
+    h.send(Action::CancelQuery).await;
+    h.settle().await;
+    assert!(h.state.runner.task.is_none() && h.state.runner.error.is_none());
+    assert_eq!(h.state.status.message, "Fetch stopped after 4000 rows");
+    assert_eq!(fetched(&h), 4000);
+
+    h.state
+        .runner
+        .editor
+        .set_text("SELECT count(*) FROM authors");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(
+        h.state.runner.result.as_ref().unwrap().rows[0][0],
+        CellValue::Int(2)
+    );
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn sessions_run_side_by_side_in_tabs() {
+    let staging = seed().await;
+    let prod = seed().await;
+    let mut h = Harness::new(staging.to_str().unwrap());
+    let mut item = ConnectionItem::new("prod", "", 0, "", "", prod.to_str().unwrap());
+    item.kind = DbKind::Sqlite;
+    h.state.connections.items.push(item);
+    h.dispatch(Action::Confirm).await;
+
+    h.dispatch(Action::GoConnections).await;
+    h.state.connections.selected = 1;
+    h.dispatch(Action::ConnectInNewTab).await;
+    assert_eq!(h.state.sessions.tabs.len(), 2);
+    assert_eq!(h.state.screen, Screen::Schema);
+    assert!(h.state.status.connection_label.starts_with("prod"));
+
+    h.dispatch(Action::GoRunner).await;
+    h.state
+        .runner
+        .editor
+        .set_text("INSERT INTO authors (name) VALUES ('Austen')");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(h.state.runner.result.as_ref().unwrap().rows_affected, 1);
+
+    // A long statement here doesn't hold up the other tab.
+    h.state.runner.editor.set_text(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 300000000) \
+         SELECT count(*) FROM n",
+    );
+    h.send(Action::RunQuery).await;
+    h.dispatch(Action::PrevSession).await;
+    assert!(h.state.status.connection_label.starts_with("local"));
+    assert!(h.state.runner.task.is_none());
+    h.state
+        .runner
+        .editor
+        .set_text("SELECT count(*) FROM authors");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(
+        h.state.runner.result.as_ref().unwrap().rows[0][0],
+        CellValue::Int(2)
+    );
+
+    // Closing the live tab disconnects it and leaves the other one running.
+    h.dispatch(Action::CloseSession).await;
+    assert_eq!(h.state.sessions.tabs.len(), 1);
+    assert!(h.state.status.connection_label.starts_with("prod"));
+    assert!(h.state.runner.task.is_some());
+    h.send(Action::CancelQuery).await;
+    h.settle().await;
+    assert!(h.state.runner.task.is_none());
+    assert_eq!(h.state.runner.error.as_deref(), Some("Query cancelled"));
+
+    h.state
+        .runner
+        .editor
+        .set_text("SELECT count(*) FROM authors");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(
+        h.state.runner.result.as_ref().unwrap().rows[0][0],
+        CellValue::Int(3)
+    );
+
+    let _ = std::fs::remove_file(staging);
+    let _ = std::fs::remove_file(prod);
+}
+
+#[tokio::test]
+async fn test_connection_reports_without_connecting() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+
+    let _ = reduce_action(&mut h.state, Action::EditSelectedConnection);
+    h.dispatch(Action::TestConnection).await;
+    assert!(
+        h.state.status.message.starts_with("Connection OK"),
+        "{}",
+        h.state.status.message
+    );
+    assert!(h.state.connections.adding.is_some());
+    assert_eq!(h.state.connections.active, None);
+
+    h.state.connections.adding.as_mut().unwrap().database = "/nonexistent/lensql.db".into();
+    h.dispatch(Action::TestConnection).await;
+    assert!(
+        h.state.status.message.starts_with("Connection failed"),
+        "{}",
+        h.state.status.message
+    );
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn read_only_profile_refuses_writes() {
+    let path = seed().await;
+    let mut h = Harness::new(path.to_str().unwrap());
+    h.state.connections.items[0].options.read_only = true;
+    h.dispatch(Action::Confirm).await;
+
+    h.dispatch(Action::GoRunner).await;
+    h.state.runner.editor.set_text("SELECT count(*) FROM books");
+    h.dispatch(Action::RunQuery).await;
+    assert_eq!(h.state.runner.error, None);
+
+    h.state
+        .runner
+        .editor
+        .set_text("DELETE FROM books WHERE id = 1");
+    h.dispatch(Action::RunQuery).await;
+    let error = h.state.runner.error.clone().unwrap_or_default();
+    assert!(error.starts_with("Read-only connection"), "{}", error);
+    let pool = SqlitePoolOptions::new()
+        .connect_with(SqliteConnectOptions::new().filename(&path))
+        .await
+        .unwrap();
+    let books: i64 = sqlx::query_scalar("SELECT count(*) FROM books")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(books, 2);
+    pool.close().await;
+    let _ = std::fs::remove_file(path);
+}